@@ -28,6 +28,10 @@ impl Expiry {
         Self::from_str(string)
     }
 
+    /// Parses an expiry from `YYYY/MM/DD`.
+    ///
+    /// # Errors
+    /// If the string is not 4, 2 and 2 decimal digits separated by `/`.
     pub fn from_str(string: impl Into<String>) -> cck_common::Result<Self> {
         let value = string.into();
         let value = value.split('/').collect::<Vec<&str>>();
@@ -36,45 +40,25 @@ impl Expiry {
             Err(cck_common::Error)?
         }
 
-        if !value[0].parse::<usize>().unwrap() <= 9999 {
-            Err(cck_common::Error)?
-        }
-
-        if !value[1].parse::<usize>().unwrap() <= 12 {
-            Err(cck_common::Error)?
-        }
+        let digits = |part: &str, len: usize| -> cck_common::Result<Vec<u8>> {
+            if part.len() != len {
+                Err(cck_common::Error)?
+            }
 
-        if !value[2].parse::<usize>().unwrap() <= 31 {
-            Err(cck_common::Error)?
-        }
+            part.chars()
+                .map(|ch| ch.to_digit(10).map(|digit| digit as u8))
+                .collect::<Option<Vec<_>>>()
+                .ok_or(cck_common::Error)
+        };
 
-        let year = value[0]
-            .chars()
-            .map(|ch| ch.to_digit(10))
-            .collect::<Option<Vec<_>>>()
-            .unwrap();
+        let year = digits(value[0], 4)?;
 
-        let month = value[1]
-            .chars()
-            .map(|ch| ch.to_digit(10))
-            .collect::<Option<Vec<_>>>()
-            .unwrap();
+        let month = digits(value[1], 2)?;
 
-        let day = value[2]
-            .chars()
-            .map(|ch| ch.to_digit(10))
-            .collect::<Option<Vec<_>>>()
-            .unwrap();
+        let day = digits(value[2], 2)?;
 
         let date = (
-            year[0] as u8,
-            year[1] as u8,
-            year[2] as u8,
-            year[3] as u8,
-            month[0] as u8,
-            month[1] as u8,
-            day[0] as u8,
-            day[1] as u8,
+            year[0], year[1], year[2], year[3], month[0], month[1], day[0], day[1],
         );
 
         Ok(Self::from(date))
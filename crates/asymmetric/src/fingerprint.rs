@@ -1,3 +1,12 @@
+use std::fmt::Display;
+
+use cck_common::size::{SIZE_32, SIZE_64};
+
+use crate::{
+    pgp_words::{PGP_WORDS_EVEN, PGP_WORDS_ODD},
+    Key,
+};
+
 /// Use BLAKE3 to generate fingerprint.
 ///
 /// Returns the fingerprint of the key.
@@ -17,3 +26,268 @@ pub fn blake3_digest(key: &[u8]) -> Vec<u8> {
 
 //     string
 // }
+
+/// The size of a key id: the last 8 bytes of a fingerprint.
+pub const KEY_ID_SIZE: usize = 8;
+
+/// The width of a randomart image.
+const RANDOMART_WIDTH: usize = 17;
+
+/// The height of a randomart image.
+const RANDOMART_HEIGHT: usize = 9;
+
+/// The symbols of a randomart image, by number of visits. The start is `S`, the end `E`.
+const RANDOMART_SYMBOLS: &[u8] = b" .o+=*BOX@%&#/^SE";
+
+/// Fingerprint of a key (BLAKE3, 32 bytes), with renderings that are easy to compare by a human.
+///
+/// - *`Display` / `to_hex`: grouped uppercase hex, e.g. `3FA1 09BC ...`*
+///
+/// - *`key_id`: the last 8 bytes, e.g. `89ABCDEF01234567`*
+///
+/// - *`to_words`: the PGP word list, e.g. `topmost Istanbul Pluto ...`*
+///
+/// - *`to_randomart`: an OpenSSH-style randomart image*
+///
+/// `parse` accepts the hex (grouped or not), the words, or base64 (see `string::encode`).
+///
+/// # Example
+/// ```
+/// let fingerprint = Fingerprint::from_key(&public_key)?;
+///
+/// println!("{}\n{}", fingerprint, fingerprint.to_randomart());
+///
+/// assert_eq!(Fingerprint::parse(fingerprint.to_words())?, fingerprint);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fingerprint([u8; SIZE_32]);
+
+impl Fingerprint {
+    /// Creates a fingerprint from its bytes.
+    ///
+    /// # Errors
+    /// If the bytes are not 32 bytes.
+    pub fn from_bytes(bytes: &[u8]) -> cck_common::Result<Self> {
+        Ok(Self(bytes.try_into().map_err(|_| cck_common::Error)?))
+    }
+
+    /// Creates the fingerprint of a key.
+    ///
+    /// # Errors
+    /// If the fingerprint of the key is not 32 bytes.
+    pub fn from_key(key: &impl Key) -> cck_common::Result<Self> {
+        Self::from_bytes(key.fingerprint())
+    }
+
+    /// Parses a fingerprint from its hex (grouped or not, any case), its words or base64.
+    ///
+    /// # Errors
+    /// If the text is none of these forms, or is not 32 bytes.
+    pub fn parse(text: impl AsRef<str>) -> cck_common::Result<Self> {
+        let text = text.as_ref().trim();
+
+        if let Ok(bytes) = parse_hex(text) {
+            return Self::from_bytes(&bytes);
+        }
+
+        if let Ok(fingerprint) = Self::parse_words(text) {
+            return Ok(fingerprint);
+        }
+
+        Self::from_bytes(cck_format::base64ct::decode(text, &mut [0u8; SIZE_64])?)
+    }
+
+    /// Returns the bytes of the fingerprint.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns the key id: the last 8 bytes of the fingerprint.
+    pub fn key_id(&self) -> KeyId {
+        let mut key_id = [0u8; KEY_ID_SIZE];
+
+        key_id.copy_from_slice(&self.0[SIZE_32 - KEY_ID_SIZE..]);
+
+        KeyId(key_id)
+    }
+
+    /// Returns the uppercase hex of the fingerprint, in groups of 4 digits separated by a space.
+    pub fn to_hex(&self) -> String {
+        to_hex(&self.0)
+    }
+
+    /// Returns the words of the fingerprint (PGP word list), separated by a space.
+    ///
+    /// The words alternate between two lists, so that a swapped or missing word is detected.
+    pub fn to_words(&self) -> String {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(i, byte)| match i % 2 {
+                0 => PGP_WORDS_EVEN[*byte as usize],
+                _ => PGP_WORDS_ODD[*byte as usize],
+            })
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+
+    /// Returns the randomart image of the fingerprint (the "drunken bishop" of OpenSSH).
+    ///
+    /// A bishop starts at the center of a 17x9 board and, for each 2 bits of the fingerprint (from the lowest
+    /// bits of each byte), moves diagonally: left or right (bit 0), up or down (bit 1). Each square shows how
+    /// often it was visited.
+    pub fn to_randomart(&self) -> String {
+        let mut board = [[0usize; RANDOMART_WIDTH]; RANDOMART_HEIGHT];
+
+        let (mut x, mut y) = (RANDOMART_WIDTH / 2, RANDOMART_HEIGHT / 2);
+
+        let (start_x, start_y) = (x, y);
+
+        for byte in self.0 {
+            for step in 0..4 {
+                let bits = byte >> (step * 2);
+
+                x = match bits & 1 {
+                    0 => x.saturating_sub(1),
+                    _ => (x + 1).min(RANDOMART_WIDTH - 1),
+                };
+
+                y = match bits & 2 {
+                    0 => y.saturating_sub(1),
+                    _ => (y + 1).min(RANDOMART_HEIGHT - 1),
+                };
+
+                board[y][x] += 1;
+            }
+        }
+
+        let start = RANDOMART_SYMBOLS.len() - 2;
+        let end = RANDOMART_SYMBOLS.len() - 1;
+
+        let mut art = format!("+{:-^width$}+\n", "[BLAKE3]", width = RANDOMART_WIDTH);
+
+        for (row, squares) in board.iter().enumerate() {
+            art.push('|');
+
+            for (column, visits) in squares.iter().enumerate() {
+                let symbol = if (column, row) == (x, y) {
+                    end
+                } else if (column, row) == (start_x, start_y) {
+                    start
+                } else {
+                    (*visits).min(start - 1)
+                };
+
+                art.push(RANDOMART_SYMBOLS[symbol] as char);
+            }
+
+            art.push_str("|\n");
+        }
+
+        art.push_str(&format!("+{}+", "-".repeat(RANDOMART_WIDTH)));
+
+        art
+    }
+
+    fn parse_words(text: &str) -> cck_common::Result<Self> {
+        let mut bytes = [0u8; SIZE_32];
+
+        let mut words = text.split_whitespace();
+
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            let word = words.next().ok_or(cck_common::Error)?;
+
+            let list = match i % 2 {
+                0 => &PGP_WORDS_EVEN,
+                _ => &PGP_WORDS_ODD,
+            };
+
+            *byte = list
+                .iter()
+                .position(|w| w.eq_ignore_ascii_case(word))
+                .ok_or(cck_common::Error)? as u8;
+        }
+
+        if words.next().is_some() {
+            Err(cck_common::Error)?
+        }
+
+        Ok(Self(bytes))
+    }
+}
+
+impl Display for Fingerprint {
+    /// Grouped uppercase hex (see `to_hex`).
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+/// Key id: the last 8 bytes of a fingerprint, short enough to be typed by a human.
+///
+/// Different keys may have the same key id: look keys up by their full fingerprint when it is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KeyId([u8; KEY_ID_SIZE]);
+
+impl KeyId {
+    /// Parses a key id from its 16 hex digits (grouped or not, any case, with or without `0x`).
+    ///
+    /// # Errors
+    /// If the text is not 8 bytes of hex.
+    pub fn parse(text: impl AsRef<str>) -> cck_common::Result<Self> {
+        let text = text.as_ref().trim();
+
+        let text = text
+            .strip_prefix("0x")
+            .or_else(|| text.strip_prefix("0X"))
+            .unwrap_or(text);
+
+        Ok(Self(
+            parse_hex(text)?.try_into().map_err(|_| cck_common::Error)?,
+        ))
+    }
+
+    /// Returns the bytes of the key id.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns the uppercase hex of the key id, in groups of 4 digits separated by a space.
+    pub fn to_hex(&self) -> String {
+        to_hex(&self.0)
+    }
+}
+
+impl Display for KeyId {
+    /// Uppercase hex, without separators.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02X}", byte))
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes
+        .chunks(2)
+        .map(|group| group.iter().map(|byte| format!("{:02X}", byte)).collect())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Parse hex digits, ignoring the spaces and colons between them.
+fn parse_hex(text: &str) -> cck_common::Result<Vec<u8>> {
+    let digits = text
+        .chars()
+        .filter(|ch| !ch.is_whitespace() && *ch != ':')
+        .map(|ch| ch.to_digit(16).map(|digit| digit as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or(cck_common::Error)?;
+
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        Err(cck_common::Error)?
+    }
+
+    Ok(digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair[1])
+        .collect())
+}
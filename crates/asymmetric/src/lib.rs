@@ -3,8 +3,10 @@ pub(crate) mod fingerprint;
 mod expiry;
 mod key;
 mod key_type;
+mod pgp_words;
 mod string;
 pub mod x25519;
 pub use expiry::*;
+pub use fingerprint::{Fingerprint, KeyId, KEY_ID_SIZE};
 pub use key::*;
 pub use key_type::*;
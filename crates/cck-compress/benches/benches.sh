@@ -1 +1 @@
-cargo -q bench --features=alloc --package cck-compress --bench deflate -- --exact --nocapture
\ No newline at end of file
+cargo -q bench --features=alloc,zstd,lz4,brotli --package cck-compress --bench deflate -- --exact --nocapture
\ No newline at end of file
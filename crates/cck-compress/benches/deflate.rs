@@ -2,6 +2,7 @@
 // cargo bench --package cck-compress --bench deflate -- --exact --nocapture
 // cargo bench --package cck-compress --bench deflate -- deflate_compress --exact --nocapture
 // cargo bench --features=alloc --package cck-compress --bench deflate -- deflate_compress --exact --nocapture
+// cargo bench --features=alloc,zstd,lz4,brotli --package cck-compress --bench deflate -- codec --nocapture
 
 #![feature(test)]
 extern crate test;
@@ -46,3 +47,123 @@ fn deflate_decompress_vec(b: &mut Bencher) {
         cck_compress::deflate::decompress_vec(bytes).unwrap();
     });
 }
+
+/// A log-like corpus (64 KiB) shared by the codec comparison.
+#[cfg(feature = "alloc")]
+fn corpus() -> Vec<u8> {
+    let mut corpus = Vec::with_capacity(64 * 1024);
+    let mut state: u32 = 1;
+
+    while corpus.len() < 64 * 1024 {
+        state = state.wrapping_mul(1103515245).wrapping_add(12345);
+
+        corpus.extend_from_slice(
+            format!(
+                "2024-01-01T00:00:{:02}Z INFO request id={} status={} bytes={}\n",
+                state % 60,
+                state >> 8,
+                [200, 201, 404, 500][(state % 4) as usize],
+                state % 10000
+            )
+            .as_bytes(),
+        );
+    }
+
+    corpus.truncate(64 * 1024);
+    corpus
+}
+
+#[cfg(feature = "alloc")]
+fn codec_compress(b: &mut Bencher, algorithm: cck_compress::Algorithm) {
+    let bytes = corpus();
+    b.bytes = bytes.len() as u64;
+
+    b.iter(|| {
+        cck_compress::compress_vec_with(algorithm, cck_compress::Level::default(), &bytes).unwrap();
+    });
+}
+
+#[cfg(feature = "alloc")]
+fn codec_decompress(b: &mut Bencher, algorithm: cck_compress::Algorithm) {
+    let bytes = corpus();
+    b.bytes = bytes.len() as u64;
+
+    let compressed =
+        cck_compress::compress_vec_with(algorithm, cck_compress::Level::default(), &bytes).unwrap();
+
+    b.iter(|| {
+        cck_compress::decompress_vec_with(algorithm, &compressed).unwrap();
+    });
+}
+
+#[cfg(feature = "alloc")]
+#[bench]
+fn codec_deflate_compress(b: &mut Bencher) {
+    codec_compress(b, cck_compress::Algorithm::Deflate);
+}
+
+#[cfg(feature = "alloc")]
+#[bench]
+fn codec_deflate_decompress(b: &mut Bencher) {
+    codec_decompress(b, cck_compress::Algorithm::Deflate);
+}
+
+#[cfg(feature = "alloc")]
+#[bench]
+fn codec_zlib_compress(b: &mut Bencher) {
+    codec_compress(b, cck_compress::Algorithm::Zlib);
+}
+
+#[cfg(feature = "alloc")]
+#[bench]
+fn codec_zlib_decompress(b: &mut Bencher) {
+    codec_decompress(b, cck_compress::Algorithm::Zlib);
+}
+
+#[cfg(feature = "alloc")]
+#[bench]
+fn codec_gzip_compress(b: &mut Bencher) {
+    codec_compress(b, cck_compress::Algorithm::Gzip);
+}
+
+#[cfg(feature = "alloc")]
+#[bench]
+fn codec_gzip_decompress(b: &mut Bencher) {
+    codec_decompress(b, cck_compress::Algorithm::Gzip);
+}
+
+#[cfg(feature = "zstd")]
+#[bench]
+fn codec_zstd_compress(b: &mut Bencher) {
+    codec_compress(b, cck_compress::Algorithm::Zstd);
+}
+
+#[cfg(feature = "zstd")]
+#[bench]
+fn codec_zstd_decompress(b: &mut Bencher) {
+    codec_decompress(b, cck_compress::Algorithm::Zstd);
+}
+
+#[cfg(feature = "lz4")]
+#[bench]
+fn codec_lz4_compress(b: &mut Bencher) {
+    codec_compress(b, cck_compress::Algorithm::Lz4);
+}
+
+#[cfg(feature = "lz4")]
+#[bench]
+fn codec_lz4_decompress(b: &mut Bencher) {
+    codec_decompress(b, cck_compress::Algorithm::Lz4);
+}
+
+#[cfg(feature = "brotli")]
+#[bench]
+fn codec_brotli_compress(b: &mut Bencher) {
+    codec_compress(b, cck_compress::Algorithm::Brotli);
+}
+
+#[cfg(feature = "brotli")]
+#[bench]
+fn codec_brotli_decompress(b: &mut Bencher) {
+    codec_decompress(b, cck_compress::Algorithm::Brotli);
+}
@@ -0,0 +1,77 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use super::{Algorithm, Codec, Level, Limits};
+use cck_common::{Error, Result};
+
+/// The brotli window size (log2), the brotli default.
+pub const BROTLI_LGWIN: i32 = 22;
+
+/// The size of the internal buffer of the decompressor.
+const BROTLI_BUFFER_SIZE: usize = 4096;
+
+/// Compresses the given message with the given level (brotli, RFC 7932).
+///
+/// The level is mapped to the brotli quality with `Level::brotli`.
+///
+/// # Example
+/// ```
+/// let compressed = cck_compress::brotli::compress_vec(cck_compress::Level::Best, b"Hello, World!").unwrap();
+/// ```
+pub fn compress_vec(level: Level, bytes: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    let params = ::brotli::enc::BrotliEncoderParams {
+        quality: level.brotli() as i32,
+        lgwin: BROTLI_LGWIN,
+        ..Default::default()
+    };
+
+    let mut buffer = Vec::new();
+
+    ::brotli::BrotliCompress(&mut bytes.as_ref(), &mut buffer, &params).map_err(|_| Error)?;
+
+    Ok(buffer)
+}
+
+/// Decompresses the given message (brotli, RFC 7932) with the default limits (`Limits::default`).
+///
+/// # Example
+/// ```
+/// let decompressed = cck_compress::brotli::decompress_vec(compressed).unwrap();
+/// ```
+pub fn decompress_vec(bytes: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    decompress_vec_with_limits(bytes, &Limits::default())
+}
+
+/// Decompresses the given message (brotli, RFC 7932) with the given limits.
+///
+/// # Errors
+/// If the message is invalid or truncated, or exceeds the limits.
+pub fn decompress_vec_with_limits(bytes: impl AsRef<[u8]>, limits: &Limits) -> Result<Vec<u8>> {
+    let bytes = bytes.as_ref();
+
+    limits.read_to_end(
+        ::brotli::Decompressor::new(bytes, BROTLI_BUFFER_SIZE),
+        bytes.len(),
+    )
+}
+
+/// Brotli
+///
+/// brotli (RFC 7932) as a `Codec`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Brotli;
+
+impl Codec for Brotli {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::Brotli
+    }
+
+    fn compress_vec(&self, level: Level, bytes: &[u8]) -> Result<Vec<u8>> {
+        compress_vec(level, bytes)
+    }
+
+    fn decompress_vec_with_limits(&self, bytes: &[u8], limits: &Limits) -> Result<Vec<u8>> {
+        decompress_vec_with_limits(bytes, limits)
+    }
+}
@@ -1,6 +1,6 @@
-use super::Level;
-use cck_common::{Error, Result};
-use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress};
+use super::{flate, Level};
+use cck_common::Result;
+use flate2::Decompress;
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
@@ -8,6 +8,9 @@ extern crate alloc;
 #[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
+#[cfg(feature = "alloc")]
+use super::{Algorithm, Codec, Limits};
+
 /// Compresses the given message with the given level.
 ///
 /// # Arguments
@@ -18,6 +21,9 @@ use alloc::vec::Vec;
 ///
 /// * `buffer` - The buffer to use.
 ///
+/// # Errors
+/// If the buffer is too small.
+///
 /// # Example
 /// ```
 /// let mut buffer = [0; 1024];
@@ -29,29 +35,24 @@ pub fn compress<'a, const T: usize>(
     bytes: impl AsRef<[u8]>,
     buffer: &'a mut [u8; T],
 ) -> Result<&'a [u8]> {
-    let mut compress = Compress::new(Compression::new(level.into()), false);
-
-    compress
-        .compress(bytes.as_ref(), buffer, FlushCompress::Finish)
-        .map_err(|_| Error)?;
-
-    let len = compress.total_out();
-
-    Ok(&buffer[..len as usize])
+    flate::compress_into(flate::compress_new(level, false), bytes.as_ref(), buffer)
 }
 
 /// Decompresses the given message.
-/// 
+///
 /// # Arguments
-/// 
+///
 /// * `bytes` - The message to decompress.
-/// 
+///
 /// * `buffer` - The buffer to use.
-/// 
+///
+/// # Errors
+/// If the buffer is too small, or the message is invalid or truncated.
+///
 /// # Example
 /// ```
 /// let compressed = cck_compress::compress(cck_compress::Level::One, b"Hello, World!", &mut buffer).unwrap();
-/// 
+///
 /// let mut buffer = [0; 1024];
 ///
 /// let decompressed = cck_compress::decompress(compressed, &mut buffer).unwrap();
@@ -60,67 +61,145 @@ pub fn decompress<'a, const T: usize>(
     bytes: impl AsRef<[u8]>,
     buffer: &'a mut [u8; T],
 ) -> Result<&'a [u8]> {
-    let mut decompress = Decompress::new(false);
-
-    decompress
-        .decompress(bytes.as_ref(), buffer, FlushDecompress::Finish)
-        .map_err(|_| Error)?;
-
-    let len = decompress.total_out();
-
-    Ok(&buffer[..len as usize])
+    flate::decompress_into(Decompress::new(false), bytes.as_ref(), buffer)
 }
 
 /// Compresses the given message with the given level.
-/// 
+///
 /// # Arguments
-/// 
+///
 /// * `level` - The level to use.
-/// 
+///
 /// * `bytes` - The message to compress.
-/// 
+///
 /// # Example
 /// ```
 /// let compressed = cck_compress::compress_vec(cck_compress::Level::One, b"Hello, World!").unwrap();
 /// ```
 #[cfg(feature = "alloc")]
 pub fn compress_vec(level: Level, bytes: impl AsRef<[u8]>) -> Result<Vec<u8>> {
-    let bytes = bytes.as_ref();
-
-    let mut buffer = Vec::with_capacity(bytes.len() * 2);
-
-    let mut compress = Compress::new(Compression::new(level.into()), false);
-
-    compress
-        .compress_vec(bytes, &mut buffer, FlushCompress::Finish)
-        .map_err(|_| Error)?;
-
-    Ok(buffer)
+    flate::compress_vec(flate::compress_new(level, false), bytes.as_ref())
 }
 
-/// Decompresses the given message.
-/// 
+/// Decompresses the given message with the default limits (`Limits::default`).
+///
 /// # Arguments
-/// 
+///
 /// * `bytes` - The message to decompress.
-/// 
+///
 /// # Example
 /// ```
 /// let compressed = cck_compress::compress_vec(cck_compress::Level::One, b"Hello, World!").unwrap();
-/// 
+///
 /// let decompressed = cck_compress::decompress_vec(compressed).unwrap();
 /// ```
 #[cfg(feature = "alloc")]
 pub fn decompress_vec(bytes: impl AsRef<[u8]>) -> Result<Vec<u8>> {
-    let bytes = bytes.as_ref();
+    decompress_vec_with_limits(bytes, &Limits::default())
+}
+
+/// Decompresses the given message with the given limits.
+///
+/// # Arguments
+///
+/// * `bytes` - The message to decompress.
+///
+/// * `limits` - The limits of the decompressed message.
+///
+/// # Errors
+/// If the message is invalid or truncated, or exceeds the limits.
+#[cfg(feature = "alloc")]
+pub fn decompress_vec_with_limits(bytes: impl AsRef<[u8]>, limits: &Limits) -> Result<Vec<u8>> {
+    Ok(flate::decompress_vec(Decompress::new(false), false, None, bytes.as_ref(), limits)?.0)
+}
 
-    let mut buffer = Vec::with_capacity(bytes.len() * 2);
+/// Compresses the given message with the given level and preset dictionary.
+///
+/// The dictionary holds content that is likely to occur in the message (only its last 32 KiB are used),
+/// and the same dictionary is needed to decompress.
+///
+/// # Arguments
+///
+/// * `level` - The level to use.
+///
+/// * `dictionary` - The preset dictionary to use.
+///
+/// * `bytes` - The message to compress.
+///
+/// # Example
+/// ```
+/// let compressed = cck_compress::deflate::compress_vec_with_dictionary(cck_compress::Level::Best, br#"{"id":,"name":""}"#, br#"{"id":1,"name":"a"}"#).unwrap();
+/// ```
+#[cfg(feature = "alloc")]
+pub fn compress_vec_with_dictionary(
+    level: Level,
+    dictionary: &[u8],
+    bytes: impl AsRef<[u8]>,
+) -> Result<Vec<u8>> {
+    flate::compress_vec(
+        flate::compress_new_with_dictionary(level, false, dictionary)?,
+        bytes.as_ref(),
+    )
+}
 
-    let mut decompress = Decompress::new(false);
+/// Decompresses the given message with the given preset dictionary and limits.
+///
+/// Raw deflate does not record the dictionary: another dictionary gives a wrong message or an error.
+///
+/// # Errors
+/// If the message is invalid or truncated, or exceeds the limits.
+#[cfg(feature = "alloc")]
+pub fn decompress_vec_with_dictionary(
+    dictionary: &[u8],
+    bytes: impl AsRef<[u8]>,
+    limits: &Limits,
+) -> Result<Vec<u8>> {
+    Ok(flate::decompress_vec(
+        Decompress::new(false),
+        false,
+        Some(dictionary),
+        bytes.as_ref(),
+        limits,
+    )?
+    .0)
+}
 
-    decompress
-        .decompress_vec(bytes, &mut buffer, FlushDecompress::Finish)
-        .map_err(|_| Error)?;
+/// Deflate
+///
+/// Raw deflate (RFC 1951) as a `Codec`.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Deflate;
 
-    Ok(buffer)
+#[cfg(feature = "alloc")]
+impl Codec for Deflate {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::Deflate
+    }
+
+    fn compress_vec(&self, level: Level, bytes: &[u8]) -> Result<Vec<u8>> {
+        compress_vec(level, bytes)
+    }
+
+    fn decompress_vec_with_limits(&self, bytes: &[u8], limits: &Limits) -> Result<Vec<u8>> {
+        decompress_vec_with_limits(bytes, limits)
+    }
+
+    fn compress_vec_with_dictionary(
+        &self,
+        level: Level,
+        dictionary: &[u8],
+        bytes: &[u8],
+    ) -> Result<Vec<u8>> {
+        compress_vec_with_dictionary(level, dictionary, bytes)
+    }
+
+    fn decompress_vec_with_dictionary(
+        &self,
+        dictionary: &[u8],
+        bytes: &[u8],
+        limits: &Limits,
+    ) -> Result<Vec<u8>> {
+        decompress_vec_with_dictionary(dictionary, bytes, limits)
+    }
 }
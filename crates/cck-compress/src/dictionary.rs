@@ -0,0 +1,226 @@
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use super::{Algorithm, Level, Limits};
+use cck_common::{size::SIZE_32, Error, Result};
+
+/// The size of a dictionary id (BLAKE3).
+pub const DICTIONARY_ID_SIZE: usize = SIZE_32;
+
+/// Dictionary
+///
+/// A compression dictionary, identified by the BLAKE3 hash of its bytes.
+///
+/// A message compressed with a dictionary starts with the dictionary id: dictionary id (32 bytes) || compressed message.
+/// The decompressor reads the id and looks up the dictionary (see `Dictionaries`).
+///
+/// Supported by deflate, zlib and zstd.
+///
+/// # Example
+/// ```
+/// let dictionary = Dictionary::new(br#"{"id":,"name":"","email":""}"#.to_vec());
+///
+/// let compressed = dictionary.compress_vec(Algorithm::Deflate, Level::Best, br#"{"id":1,"name":"a","email":"a@b"}"#).unwrap();
+///
+/// let message = dictionary.decompress_vec(Algorithm::Deflate, &compressed, &Limits::default()).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dictionary {
+    id: [u8; DICTIONARY_ID_SIZE],
+    bytes: Vec<u8>,
+}
+
+impl Dictionary {
+    /// Creates a dictionary from its bytes: raw content (a preset dictionary), or a trained zstd dictionary.
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self {
+            id: dictionary_id(&bytes),
+            bytes,
+        }
+    }
+
+    /// Trains a zstd dictionary on the given samples.
+    ///
+    /// The content of a trained dictionary is also usable as a deflate or zlib preset dictionary.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The messages to train on.
+    ///
+    /// * `max_size` - The maximum size of the dictionary.
+    ///
+    /// # Errors
+    /// If there are too few samples to train on.
+    #[cfg(feature = "zstd")]
+    pub fn train<S: AsRef<[u8]>>(samples: &[S], max_size: usize) -> Result<Self> {
+        Ok(Self::new(super::zstd::train_dictionary(samples, max_size)?))
+    }
+
+    /// Returns the dictionary id: the BLAKE3 hash of the dictionary.
+    pub fn id(&self) -> &[u8; DICTIONARY_ID_SIZE] {
+        &self.id
+    }
+
+    /// Returns the bytes of the dictionary, to store it.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Compresses the given message with the dictionary.
+    ///
+    /// Returns: dictionary id || compressed message
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The algorithm to use.
+    ///
+    /// * `level` - The level to use.
+    ///
+    /// * `bytes` - The message to compress.
+    ///
+    /// # Errors
+    /// If the algorithm does not support dictionaries.
+    pub fn compress_vec(
+        &self,
+        algorithm: Algorithm,
+        level: Level,
+        bytes: impl AsRef<[u8]>,
+    ) -> Result<Vec<u8>> {
+        let compressed =
+            algorithm
+                .codec()
+                .compress_vec_with_dictionary(level, &self.bytes, bytes.as_ref())?;
+
+        let mut message = Vec::with_capacity(DICTIONARY_ID_SIZE + compressed.len());
+
+        message.extend_from_slice(&self.id);
+
+        message.extend_from_slice(&compressed);
+
+        Ok(message)
+    }
+
+    /// Decompresses the given message (dictionary id || compressed message) with the dictionary.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The algorithm to use.
+    ///
+    /// * `bytes` - The message to decompress.
+    ///
+    /// * `limits` - The limits of the decompressed message.
+    ///
+    /// # Errors
+    /// If the message references another dictionary, the algorithm does not support dictionaries,
+    /// the message is invalid or truncated, or exceeds the limits.
+    pub fn decompress_vec(
+        &self,
+        algorithm: Algorithm,
+        bytes: impl AsRef<[u8]>,
+        limits: &Limits,
+    ) -> Result<Vec<u8>> {
+        let bytes = bytes.as_ref();
+
+        if referenced_dictionary_id(bytes)? != &self.id {
+            Err(Error)?
+        }
+
+        algorithm.codec().decompress_vec_with_dictionary(
+            &self.bytes,
+            &bytes[DICTIONARY_ID_SIZE..],
+            limits,
+        )
+    }
+}
+
+/// Dictionaries
+///
+/// A set of dictionaries by id, to decompress messages compressed with any of them.
+///
+/// # Example
+/// ```
+/// let mut dictionaries = Dictionaries::new();
+///
+/// dictionaries.insert(Dictionary::new(stored));
+///
+/// let message = dictionaries.decompress_vec(Algorithm::Zstd, &compressed, &Limits::default()).unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dictionaries {
+    dictionaries: BTreeMap<[u8; DICTIONARY_ID_SIZE], Dictionary>,
+}
+
+impl Dictionaries {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the given dictionary and returns its id.
+    pub fn insert(&mut self, dictionary: Dictionary) -> [u8; DICTIONARY_ID_SIZE] {
+        let id = dictionary.id;
+
+        self.dictionaries.insert(id, dictionary);
+
+        id
+    }
+
+    /// Returns the dictionary with the given id.
+    pub fn get(&self, id: &[u8]) -> Option<&Dictionary> {
+        self.dictionaries
+            .get(<&[u8; DICTIONARY_ID_SIZE]>::try_from(id).ok()?)
+    }
+
+    /// Removes the dictionary with the given id.
+    pub fn remove(&mut self, id: &[u8]) -> Option<Dictionary> {
+        self.dictionaries
+            .remove(<&[u8; DICTIONARY_ID_SIZE]>::try_from(id).ok()?)
+    }
+
+    /// Returns the number of dictionaries.
+    pub fn len(&self) -> usize {
+        self.dictionaries.len()
+    }
+
+    /// Returns true if there are no dictionaries.
+    pub fn is_empty(&self) -> bool {
+        self.dictionaries.is_empty()
+    }
+
+    /// Decompresses the given message (dictionary id || compressed message) with the dictionary it references.
+    ///
+    /// # Errors
+    /// If the referenced dictionary is unknown, the algorithm does not support dictionaries,
+    /// the message is invalid or truncated, or exceeds the limits.
+    pub fn decompress_vec(
+        &self,
+        algorithm: Algorithm,
+        bytes: impl AsRef<[u8]>,
+        limits: &Limits,
+    ) -> Result<Vec<u8>> {
+        let bytes = bytes.as_ref();
+
+        self.get(referenced_dictionary_id(bytes)?)
+            .ok_or(Error)?
+            .decompress_vec(algorithm, bytes, limits)
+    }
+}
+
+/// Returns the id of the given dictionary bytes: their BLAKE3 hash.
+pub fn dictionary_id(bytes: &[u8]) -> [u8; DICTIONARY_ID_SIZE] {
+    cck_hash::blake3::digest(bytes, &[])
+}
+
+/// Returns the dictionary id that the given message (dictionary id || compressed message) references.
+///
+/// # Errors
+/// If the message is shorter than a dictionary id.
+pub fn referenced_dictionary_id(bytes: &[u8]) -> Result<&[u8; DICTIONARY_ID_SIZE]> {
+    bytes
+        .get(..DICTIONARY_ID_SIZE)
+        .ok_or(Error)?
+        .try_into()
+        .map_err(|_| Error)
+}
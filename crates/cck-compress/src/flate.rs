@@ -0,0 +1,180 @@
+use super::Level;
+#[cfg(feature = "alloc")]
+use super::Limits;
+use cck_common::{Error, Result};
+use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress, Status};
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// The initial capacity added to the output of `compress_vec` and `decompress_vec`.
+#[cfg(feature = "alloc")]
+const VEC_MIN_CAPACITY: usize = 64;
+
+pub(crate) fn compress_new(level: Level, zlib_header: bool) -> Compress {
+    Compress::new(Compression::new(level.deflate()), zlib_header)
+}
+
+/// Creates a compressor with a preset dictionary.
+///
+/// A zlib stream records the Adler-32 checksum of the dictionary (DICTID) in its header.
+#[cfg(feature = "alloc")]
+pub(crate) fn compress_new_with_dictionary(
+    level: Level,
+    zlib_header: bool,
+    dictionary: &[u8],
+) -> Result<Compress> {
+    let mut compress = compress_new(level, zlib_header);
+
+    compress.set_dictionary(dictionary).map_err(|_| Error)?;
+
+    Ok(compress)
+}
+
+/// Compresses into the buffer.
+///
+/// # Errors
+/// If the buffer is too small.
+pub(crate) fn compress_into<'a>(
+    mut compress: Compress,
+    bytes: &[u8],
+    buffer: &'a mut [u8],
+) -> Result<&'a [u8]> {
+    let status = compress
+        .compress(bytes, buffer, FlushCompress::Finish)
+        .map_err(|_| Error)?;
+
+    if status != Status::StreamEnd {
+        Err(Error)?
+    }
+
+    Ok(&buffer[..compress.total_out() as usize])
+}
+
+/// Decompresses into the buffer.
+///
+/// # Errors
+/// If the buffer is too small, or the stream is invalid or truncated.
+pub(crate) fn decompress_into<'a>(
+    mut decompress: Decompress,
+    bytes: &[u8],
+    buffer: &'a mut [u8],
+) -> Result<&'a [u8]> {
+    let status = decompress
+        .decompress(bytes, buffer, FlushDecompress::Finish)
+        .map_err(|_| Error)?;
+
+    if status != Status::StreamEnd {
+        Err(Error)?
+    }
+
+    Ok(&buffer[..decompress.total_out() as usize])
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn compress_vec(mut compress: Compress, bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut buffer = Vec::with_capacity(bytes.len() / 2 + VEC_MIN_CAPACITY);
+
+    loop {
+        let total = (compress.total_in(), compress.total_out());
+
+        let status = compress
+            .compress_vec(
+                &bytes[compress.total_in() as usize..],
+                &mut buffer,
+                FlushCompress::Finish,
+            )
+            .map_err(|_| Error)?;
+
+        if status == Status::StreamEnd {
+            return Ok(buffer);
+        }
+
+        grow(
+            &mut buffer,
+            total != (compress.total_in(), compress.total_out()),
+            usize::MAX,
+        )?;
+    }
+}
+
+/// Decompresses a stream. Returns the message and the number of bytes of the stream (trailing bytes are not read).
+///
+/// A raw deflate stream is decompressed with the preset dictionary from the start,
+/// a zlib stream only if its header asks for it (and the DICTID matches).
+///
+/// # Errors
+/// If the stream is invalid or truncated, the stream needs another dictionary, or the message exceeds the limits.
+#[cfg(feature = "alloc")]
+pub(crate) fn decompress_vec(
+    mut decompress: Decompress,
+    zlib_header: bool,
+    dictionary: Option<&[u8]>,
+    bytes: &[u8],
+    limits: &Limits,
+) -> Result<(Vec<u8>, usize)> {
+    if let (false, Some(dictionary)) = (zlib_header, dictionary) {
+        decompress.set_dictionary(dictionary).map_err(|_| Error)?;
+    }
+
+    let max_output = limits.max_output(bytes.len());
+
+    let mut buffer =
+        Vec::with_capacity((bytes.len() * 2 + VEC_MIN_CAPACITY).min(max_output.saturating_add(1)));
+
+    loop {
+        let total = (decompress.total_in(), decompress.total_out());
+
+        let status = match decompress.decompress_vec(
+            &bytes[decompress.total_in() as usize..],
+            &mut buffer,
+            FlushDecompress::None,
+        ) {
+            Ok(status) => status,
+            Err(error) => match (error.needs_dictionary(), dictionary) {
+                (Some(_), Some(dictionary)) => {
+                    decompress.set_dictionary(dictionary).map_err(|_| Error)?;
+                    continue;
+                }
+                _ => Err(Error)?,
+            },
+        };
+
+        if buffer.len() > max_output {
+            Err(Error)?
+        }
+
+        if status == Status::StreamEnd {
+            return Ok((buffer, decompress.total_in() as usize));
+        }
+
+        grow(
+            &mut buffer,
+            total != (decompress.total_in(), decompress.total_out()),
+            max_output,
+        )?;
+    }
+}
+
+/// Doubles the capacity of the buffer if it is full, up to one byte more than `max_output`.
+///
+/// # Errors
+/// If the buffer is not full and the stream made no progress (e.g. truncated input).
+#[cfg(feature = "alloc")]
+fn grow(buffer: &mut Vec<u8>, progress: bool, max_output: usize) -> Result<()> {
+    if buffer.len() == buffer.capacity() {
+        buffer.reserve_exact(
+            buffer
+                .capacity()
+                .min(max_output.saturating_add(1) - buffer.len())
+                .max(1),
+        );
+    } else if !progress {
+        Err(Error)?
+    }
+
+    Ok(())
+}
@@ -0,0 +1,368 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use super::{flate, Algorithm, Codec, Level, Limits};
+use cck_common::{Error, Result};
+use flate2::{Crc, Decompress};
+
+/// The gzip magic bytes.
+pub const GZIP_ID: [u8; 2] = [0x1f, 0x8b];
+
+/// The operating system id "unknown".
+pub const GZIP_OS_UNKNOWN: u8 = 255;
+
+/// The size of the gzip trailer: CRC-32 (4 bytes) || ISIZE (4 bytes).
+pub const GZIP_TRAILER_SIZE: usize = 8;
+
+/// The maximum size of each optional header field (extra field, file name, comment) in bytes.
+///
+/// The file name and comment are zero-terminated, so without a limit a header could be read indefinitely.
+pub const GZIP_HEADER_FIELD_MAX_SIZE: usize = 4096;
+
+const CM_DEFLATE: u8 = 8;
+
+const FTEXT: u8 = 1 << 0;
+const FHCRC: u8 = 1 << 1;
+const FEXTRA: u8 = 1 << 2;
+const FNAME: u8 = 1 << 3;
+const FCOMMENT: u8 = 1 << 4;
+const FRESERVED: u8 = 0xe0;
+
+/// GzHeader
+///
+/// The gzip member header (RFC 1952).
+///
+/// # Example
+/// ```
+/// let header = GzHeader {
+///     filename: Some(b"hello.txt".to_vec()),
+///     ..Default::default()
+/// };
+///
+/// let compressed = compress_vec_with_header(&header, Level::default(), b"hello").unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GzHeader {
+    /// The message is probably text.
+    pub text: bool,
+    /// The modification time (seconds since the Unix epoch), 0 if unknown.
+    pub mtime: u32,
+    /// The operating system id.
+    pub os: u8,
+    /// The extra field.
+    pub extra: Option<Vec<u8>>,
+    /// The original file name, without the terminating zero.
+    pub filename: Option<Vec<u8>>,
+    /// The comment, without the terminating zero.
+    pub comment: Option<Vec<u8>>,
+    /// Write (and require) a header CRC-16.
+    pub header_crc: bool,
+}
+
+impl core::default::Default for GzHeader {
+    /// Returns an empty header.
+    ///
+    /// Default: no fields, `mtime` 0, `os` `GZIP_OS_UNKNOWN`
+    fn default() -> Self {
+        Self {
+            text: false,
+            mtime: 0,
+            os: GZIP_OS_UNKNOWN,
+            extra: None,
+            filename: None,
+            comment: None,
+            header_crc: false,
+        }
+    }
+}
+
+impl GzHeader {
+    /// Encode the header.
+    ///
+    /// # Errors
+    /// If the file name or comment contains a zero byte, or a field is longer than `GZIP_HEADER_FIELD_MAX_SIZE`.
+    pub fn encode(&self, level: Level) -> Result<Vec<u8>> {
+        let mut flags = 0;
+
+        let fields = [
+            (self.text, FTEXT),
+            (self.header_crc, FHCRC),
+            (self.extra.is_some(), FEXTRA),
+            (self.filename.is_some(), FNAME),
+            (self.comment.is_some(), FCOMMENT),
+        ];
+
+        for (set, flag) in fields {
+            if set {
+                flags |= flag;
+            }
+        }
+
+        // XFL: 2 = slowest algorithm, 4 = fastest algorithm
+        let xfl = match level.deflate() {
+            9 => 2,
+            1 => 4,
+            _ => 0,
+        };
+
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(&GZIP_ID);
+
+        bytes.extend_from_slice(&[CM_DEFLATE, flags]);
+
+        bytes.extend_from_slice(&self.mtime.to_le_bytes());
+
+        bytes.extend_from_slice(&[xfl, self.os]);
+
+        for field in [&self.extra, &self.filename, &self.comment].into_iter().flatten() {
+            if field.len() > GZIP_HEADER_FIELD_MAX_SIZE {
+                Err(Error)?
+            }
+        }
+
+        if let Some(extra) = &self.extra {
+            let len = u16::try_from(extra.len()).map_err(|_| Error)?;
+
+            bytes.extend_from_slice(&len.to_le_bytes());
+
+            bytes.extend_from_slice(extra);
+        }
+
+        for field in [&self.filename, &self.comment].into_iter().flatten() {
+            if field.contains(&0) {
+                Err(Error)?
+            }
+
+            bytes.extend_from_slice(field);
+
+            bytes.push(0);
+        }
+
+        if self.header_crc {
+            let crc = crc32(&bytes) as u16;
+
+            bytes.extend_from_slice(&crc.to_le_bytes());
+        }
+
+        Ok(bytes)
+    }
+
+    /// Decode a header from the start of the given bytes.
+    ///
+    /// Returns the header and its length.
+    ///
+    /// # Errors
+    /// If the header is invalid or truncated, a field is longer than `GZIP_HEADER_FIELD_MAX_SIZE`,
+    /// or the header CRC-16 does not match.
+    pub fn decode(bytes: &[u8]) -> Result<(Self, usize)> {
+        let mut bytes = bytes.iter();
+
+        let mut len = 0;
+
+        let header = Self::parse(&mut || {
+            len += 1;
+
+            bytes.next().copied().ok_or(Error)
+        })?;
+
+        Ok((header, len))
+    }
+
+    /// Parse a header byte by byte.
+    pub(crate) fn parse(next: &mut dyn FnMut() -> Result<u8>) -> Result<Self> {
+        let mut crc = Crc::new();
+
+        let mut read = || -> Result<u8> {
+            let byte = next()?;
+
+            crc.update(&[byte]);
+
+            Ok(byte)
+        };
+
+        let mut fixed = [0u8; 10];
+
+        for byte in fixed.iter_mut() {
+            *byte = read()?;
+        }
+
+        let flags = fixed[3];
+
+        if fixed[..2] != GZIP_ID || fixed[2] != CM_DEFLATE || flags & FRESERVED != 0 {
+            Err(Error)?
+        }
+
+        let extra = if flags & FEXTRA != 0 {
+            let len = u16::from_le_bytes([read()?, read()?]);
+
+            if len as usize > GZIP_HEADER_FIELD_MAX_SIZE {
+                Err(Error)?
+            }
+
+            Some((0..len).map(|_| read()).collect::<Result<Vec<u8>>>()?)
+        } else {
+            None
+        };
+
+        let mut zero_terminated = |flag: u8| -> Result<Option<Vec<u8>>> {
+            if flags & flag == 0 {
+                return Ok(None);
+            }
+
+            let mut field = Vec::new();
+
+            loop {
+                match read()? {
+                    0 => return Ok(Some(field)),
+                    _ if field.len() == GZIP_HEADER_FIELD_MAX_SIZE => Err(Error)?,
+                    byte => field.push(byte),
+                }
+            }
+        };
+
+        let filename = zero_terminated(FNAME)?;
+
+        let comment = zero_terminated(FCOMMENT)?;
+
+        let header_crc = flags & FHCRC != 0;
+
+        if header_crc {
+            let expected = crc.sum() as u16;
+
+            if u16::from_le_bytes([next()?, next()?]) != expected {
+                Err(Error)?
+            }
+        }
+
+        Ok(Self {
+            text: flags & FTEXT != 0,
+            mtime: u32::from_le_bytes([fixed[4], fixed[5], fixed[6], fixed[7]]),
+            os: fixed[9],
+            extra,
+            filename,
+            comment,
+            header_crc,
+        })
+    }
+}
+
+/// Compresses the given message with the given level (gzip, RFC 1952).
+///
+/// # Example
+/// ```
+/// let compressed = cck_compress::gzip::compress_vec(cck_compress::Level::Fast, b"Hello, World!").unwrap();
+/// ```
+pub fn compress_vec(level: Level, bytes: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    compress_vec_with_header(&GzHeader::default(), level, bytes)
+}
+
+/// Compresses the given message with the given header and level (gzip, RFC 1952).
+///
+/// # Arguments
+///
+/// * `header` - The header to use.
+///
+/// * `level` - The level to use.
+///
+/// * `bytes` - The message to compress.
+pub fn compress_vec_with_header(
+    header: &GzHeader,
+    level: Level,
+    bytes: impl AsRef<[u8]>,
+) -> Result<Vec<u8>> {
+    let bytes = bytes.as_ref();
+
+    let mut gzip = header.encode(level)?;
+
+    gzip.extend_from_slice(&flate::compress_vec(
+        flate::compress_new(level, false),
+        bytes,
+    )?);
+
+    gzip.extend_from_slice(&trailer(crc32(bytes), bytes.len() as u32));
+
+    Ok(gzip)
+}
+
+/// Decompresses the given message (gzip, RFC 1952) with the default limits (`Limits::default`).
+///
+/// # Example
+/// ```
+/// let decompressed = cck_compress::gzip::decompress_vec(compressed).unwrap();
+/// ```
+pub fn decompress_vec(bytes: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    Ok(decompress_vec_with_header(bytes, &Limits::default())?.1)
+}
+
+/// Decompresses the given message (gzip, RFC 1952) with the given limits.
+///
+/// The ISIZE field of the trailer is only checked after decompression, so it is not trusted for the limits.
+pub fn decompress_vec_with_limits(bytes: impl AsRef<[u8]>, limits: &Limits) -> Result<Vec<u8>> {
+    Ok(decompress_vec_with_header(bytes, limits)?.1)
+}
+
+/// Decompresses the given message (gzip, RFC 1952) with the given limits and returns its header.
+///
+/// Only a single member is read.
+///
+/// # Errors
+/// If the message is invalid or truncated, there are trailing bytes, the CRC-32 or length does not match,
+/// or the message exceeds the limits.
+pub fn decompress_vec_with_header(
+    bytes: impl AsRef<[u8]>,
+    limits: &Limits,
+) -> Result<(GzHeader, Vec<u8>)> {
+    let bytes = bytes.as_ref();
+
+    let (header, len) = GzHeader::decode(bytes)?;
+
+    let (message, used) =
+        flate::decompress_vec(Decompress::new(false), false, None, &bytes[len..], limits)?;
+
+    if bytes[len + used..] != trailer(crc32(&message), message.len() as u32) {
+        Err(Error)?
+    }
+
+    Ok((header, message))
+}
+
+/// CRC-32 || ISIZE (the length modulo 2^32)
+pub(crate) fn trailer(crc: u32, size: u32) -> [u8; GZIP_TRAILER_SIZE] {
+    let mut trailer = [0u8; GZIP_TRAILER_SIZE];
+
+    trailer[..4].copy_from_slice(&crc.to_le_bytes());
+
+    trailer[4..].copy_from_slice(&size.to_le_bytes());
+
+    trailer
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = Crc::new();
+
+    crc.update(bytes);
+
+    crc.sum()
+}
+
+/// Gzip
+///
+/// gzip (RFC 1952) as a `Codec`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Gzip;
+
+impl Codec for Gzip {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::Gzip
+    }
+
+    fn compress_vec(&self, level: Level, bytes: &[u8]) -> Result<Vec<u8>> {
+        compress_vec(level, bytes)
+    }
+
+    fn decompress_vec_with_limits(&self, bytes: &[u8], limits: &Limits) -> Result<Vec<u8>> {
+        decompress_vec_with_limits(bytes, limits)
+    }
+}
@@ -1,8 +1,30 @@
+#[cfg(feature = "brotli")]
+pub mod brotli;
 pub mod deflate;
+#[cfg(feature = "dictionary")]
+pub mod dictionary;
+mod flate;
+#[cfg(feature = "alloc")]
+pub mod gzip;
+#[cfg(feature = "lz4")]
+pub mod lz4;
+#[cfg(feature = "std")]
+pub mod stream;
+pub mod zlib;
+#[cfg(feature = "zstd")]
+pub mod zstd;
+
+use cck_common::{Error, Result};
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
 /// The compression level.
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Level {
     Any(u32),
     Fast = 1,
@@ -27,6 +49,44 @@ impl Into<u32> for Level {
     }
 }
 
+impl Level {
+    /// Returns the deflate (zlib, gzip) level: 1 (fast) to 9 (best).
+    ///
+    /// `Level::Any(n)` is clamped to 9.
+    pub fn deflate(&self) -> u32 {
+        match self {
+            Level::Any(n) => (*n).min(9),
+            Level::Fast => 1,
+            Level::Normal => 6,
+            Level::Best => 9,
+        }
+    }
+
+    /// Returns the zstd level: 1 (fast), 3 (the zstd default) and 19 (best without long-window memory).
+    ///
+    /// `Level::Any(n)` is clamped to 1..=22.
+    pub fn zstd(&self) -> i32 {
+        match self {
+            Level::Any(n) => (*n).clamp(1, 22) as i32,
+            Level::Fast => 1,
+            Level::Normal => 3,
+            Level::Best => 19,
+        }
+    }
+
+    /// Returns the brotli quality: 1 (fast), 6 and 11 (best).
+    ///
+    /// `Level::Any(n)` is clamped to 11.
+    pub fn brotli(&self) -> u32 {
+        match self {
+            Level::Any(n) => (*n).min(11),
+            Level::Fast => 1,
+            Level::Normal => 6,
+            Level::Best => 11,
+        }
+    }
+}
+
 impl core::default::Default for Level {
 
     /// Returns the default compression level.
@@ -36,3 +96,268 @@ impl core::default::Default for Level {
         Self::Normal
     }
 }
+
+/// The default maximum size of a decompressed message: 64 MiB.
+pub const DEFAULT_MAX_OUTPUT_SIZE: usize = 64 * 1024 * 1024;
+
+/// The default maximum compression ratio (decompressed size / compressed size).
+pub const DEFAULT_MAX_RATIO: usize = 100;
+
+/// The default size below which the compression ratio is not checked: 1 MiB.
+pub const DEFAULT_RATIO_THRESHOLD: usize = 1024 * 1024;
+
+/// Limits
+///
+/// The limits of a decompression, against decompression bombs.
+///
+/// The whole-message decompressions (`decompress_vec`) stop with an error as soon as the output exceeds
+/// `max_output_size`, or exceeds `ratio_threshold` and `max_ratio` times the compressed size.
+///
+/// # Example
+/// ```
+/// let limits = cck_compress::Limits {
+///     max_output_size: 1024 * 1024,
+///     ..Default::default()
+/// };
+///
+/// let decompressed = cck_compress::deflate::decompress_vec_with_limits(compressed, &limits).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum size of the decompressed message.
+    pub max_output_size: usize,
+    /// The maximum compression ratio (decompressed size / compressed size).
+    pub max_ratio: usize,
+    /// The ratio is only checked once the decompressed message is larger than this.
+    pub ratio_threshold: usize,
+}
+
+impl core::default::Default for Limits {
+    /// Returns the default policy, used by `decompress_vec`.
+    ///
+    /// Default: `DEFAULT_MAX_OUTPUT_SIZE`, `DEFAULT_MAX_RATIO`, `DEFAULT_RATIO_THRESHOLD`
+    fn default() -> Self {
+        Self {
+            max_output_size: DEFAULT_MAX_OUTPUT_SIZE,
+            max_ratio: DEFAULT_MAX_RATIO,
+            ratio_threshold: DEFAULT_RATIO_THRESHOLD,
+        }
+    }
+}
+
+impl Limits {
+    /// Returns limits that never stop a decompression.
+    ///
+    /// Only use this for trusted input.
+    pub fn unlimited() -> Self {
+        Self {
+            max_output_size: usize::MAX,
+            max_ratio: usize::MAX,
+            ratio_threshold: usize::MAX,
+        }
+    }
+
+    /// Returns the maximum size of the message decompressed from `compressed_size` bytes.
+    pub fn max_output(&self, compressed_size: usize) -> usize {
+        self.max_ratio
+            .saturating_mul(compressed_size)
+            .max(self.ratio_threshold)
+            .min(self.max_output_size)
+    }
+
+    /// Reads the decompressed message to the end.
+    ///
+    /// # Errors
+    /// If the reader fails, or the message exceeds the limits.
+    #[cfg(feature = "std")]
+    pub(crate) fn read_to_end(
+        &self,
+        reader: impl std::io::Read,
+        compressed_size: usize,
+    ) -> Result<Vec<u8>> {
+        use std::io::Read;
+
+        let max_output = self.max_output(compressed_size);
+
+        let mut buffer = Vec::new();
+
+        // one byte more than the limit tells an exceeded limit from an exact fit
+        reader
+            .take((max_output as u64).saturating_add(1))
+            .read_to_end(&mut buffer)
+            .map_err(|_| Error)?;
+
+        if buffer.len() > max_output {
+            Err(Error)?
+        }
+
+        Ok(buffer)
+    }
+}
+
+/// The compression algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Deflate = 0x01,
+    Zlib = 0x02,
+    Gzip = 0x03,
+    #[cfg(feature = "zstd")]
+    Zstd = 0x04,
+    #[cfg(feature = "lz4")]
+    Lz4 = 0x05,
+    #[cfg(feature = "brotli")]
+    Brotli = 0x06,
+}
+
+impl Algorithm {
+    /// Returns the algorithm id.
+    pub fn id(&self) -> u8 {
+        *self as u8
+    }
+
+    /// Returns the algorithm of the given id.
+    ///
+    /// # Errors
+    /// If the id is unknown, or its feature is not enabled.
+    pub fn from_id(id: u8) -> Result<Self> {
+        match id {
+            0x01 => Ok(Self::Deflate),
+            0x02 => Ok(Self::Zlib),
+            0x03 => Ok(Self::Gzip),
+            #[cfg(feature = "zstd")]
+            0x04 => Ok(Self::Zstd),
+            #[cfg(feature = "lz4")]
+            0x05 => Ok(Self::Lz4),
+            #[cfg(feature = "brotli")]
+            0x06 => Ok(Self::Brotli),
+            _ => Err(Error),
+        }
+    }
+
+    /// Returns the codec of the algorithm.
+    #[cfg(feature = "alloc")]
+    pub fn codec(&self) -> &'static dyn Codec {
+        match self {
+            Algorithm::Deflate => &deflate::Deflate,
+            Algorithm::Zlib => &zlib::Zlib,
+            Algorithm::Gzip => &gzip::Gzip,
+            #[cfg(feature = "zstd")]
+            Algorithm::Zstd => &zstd::Zstd,
+            #[cfg(feature = "lz4")]
+            Algorithm::Lz4 => &lz4::Lz4,
+            #[cfg(feature = "brotli")]
+            Algorithm::Brotli => &brotli::Brotli,
+        }
+    }
+}
+
+impl core::default::Default for Algorithm {
+    /// Returns the default compression algorithm.
+    ///
+    /// Default: `Algorithm::Deflate`
+    fn default() -> Self {
+        Self::Deflate
+    }
+}
+
+/// Codec
+///
+/// A compression algorithm with a whole-message API.
+///
+/// # Example
+/// ```
+/// let codec = cck_compress::Algorithm::Deflate.codec();
+///
+/// let compressed = codec.compress_vec(cck_compress::Level::Best, b"Hello, World!").unwrap();
+///
+/// let decompressed = codec.decompress_vec(&compressed).unwrap();
+/// ```
+#[cfg(feature = "alloc")]
+pub trait Codec {
+    /// Returns the algorithm of the codec.
+    fn algorithm(&self) -> Algorithm;
+
+    /// Compresses the given message with the given level.
+    fn compress_vec(&self, level: Level, bytes: &[u8]) -> Result<Vec<u8>>;
+
+    /// Decompresses the given message with the default limits (`Limits::default`).
+    fn decompress_vec(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        self.decompress_vec_with_limits(bytes, &Limits::default())
+    }
+
+    /// Decompresses the given message with the given limits.
+    ///
+    /// # Errors
+    /// If the message is invalid or truncated, or exceeds the limits.
+    fn decompress_vec_with_limits(&self, bytes: &[u8], limits: &Limits) -> Result<Vec<u8>>;
+
+    /// Compresses the given message with the given level and dictionary.
+    ///
+    /// # Errors
+    /// If the algorithm does not support dictionaries (gzip, LZ4, brotli).
+    fn compress_vec_with_dictionary(
+        &self,
+        _level: Level,
+        _dictionary: &[u8],
+        _bytes: &[u8],
+    ) -> Result<Vec<u8>> {
+        Err(Error)
+    }
+
+    /// Decompresses the given message with the given dictionary and limits.
+    ///
+    /// # Errors
+    /// If the algorithm does not support dictionaries (gzip, LZ4, brotli), the message is invalid or truncated,
+    /// or exceeds the limits.
+    fn decompress_vec_with_dictionary(
+        &self,
+        _dictionary: &[u8],
+        _bytes: &[u8],
+        _limits: &Limits,
+    ) -> Result<Vec<u8>> {
+        Err(Error)
+    }
+}
+
+/// Compresses the given message with the given algorithm and level.
+///
+/// # Example
+/// ```
+/// let compressed = cck_compress::compress_vec_with(cck_compress::Algorithm::Deflate, cck_compress::Level::Fast, b"Hello, World!").unwrap();
+/// ```
+#[cfg(feature = "alloc")]
+pub fn compress_vec_with(
+    algorithm: Algorithm,
+    level: Level,
+    bytes: impl AsRef<[u8]>,
+) -> Result<Vec<u8>> {
+    algorithm.codec().compress_vec(level, bytes.as_ref())
+}
+
+/// Decompresses the given message with the given algorithm and the default limits (`Limits::default`).
+///
+/// # Example
+/// ```
+/// let decompressed = cck_compress::decompress_vec_with(cck_compress::Algorithm::Deflate, compressed).unwrap();
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decompress_vec_with(algorithm: Algorithm, bytes: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    algorithm.codec().decompress_vec(bytes.as_ref())
+}
+
+/// Decompresses the given message with the given algorithm and limits.
+///
+/// # Example
+/// ```
+/// let decompressed = cck_compress::decompress_vec_with_limits(cck_compress::Algorithm::Deflate, compressed, &cck_compress::Limits::default()).unwrap();
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decompress_vec_with_limits(
+    algorithm: Algorithm,
+    bytes: impl AsRef<[u8]>,
+    limits: &Limits,
+) -> Result<Vec<u8>> {
+    algorithm
+        .codec()
+        .decompress_vec_with_limits(bytes.as_ref(), limits)
+}
@@ -0,0 +1,63 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+use std::io::Write;
+
+use super::{Algorithm, Codec, Level, Limits};
+use cck_common::{Error, Result};
+
+/// Compresses the given message (LZ4 frame format).
+///
+/// LZ4 has a single speed-oriented mode, so the level is ignored.
+///
+/// # Example
+/// ```
+/// let compressed = cck_compress::lz4::compress_vec(cck_compress::Level::Fast, b"Hello, World!").unwrap();
+/// ```
+pub fn compress_vec(_level: Level, bytes: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+
+    encoder.write_all(bytes.as_ref()).map_err(|_| Error)?;
+
+    encoder.finish().map_err(|_| Error)
+}
+
+/// Decompresses the given message (LZ4 frame format) with the default limits (`Limits::default`).
+///
+/// # Example
+/// ```
+/// let decompressed = cck_compress::lz4::decompress_vec(compressed).unwrap();
+/// ```
+pub fn decompress_vec(bytes: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    decompress_vec_with_limits(bytes, &Limits::default())
+}
+
+/// Decompresses the given message (LZ4 frame format) with the given limits.
+///
+/// # Errors
+/// If the message is invalid or truncated, a checksum does not match, or the message exceeds the limits.
+pub fn decompress_vec_with_limits(bytes: impl AsRef<[u8]>, limits: &Limits) -> Result<Vec<u8>> {
+    let bytes = bytes.as_ref();
+
+    limits.read_to_end(lz4_flex::frame::FrameDecoder::new(bytes), bytes.len())
+}
+
+/// Lz4
+///
+/// LZ4 (frame format) as a `Codec`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lz4;
+
+impl Codec for Lz4 {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::Lz4
+    }
+
+    fn compress_vec(&self, level: Level, bytes: &[u8]) -> Result<Vec<u8>> {
+        compress_vec(level, bytes)
+    }
+
+    fn decompress_vec_with_limits(&self, bytes: &[u8], limits: &Limits) -> Result<Vec<u8>> {
+        decompress_vec_with_limits(bytes, limits)
+    }
+}
@@ -0,0 +1,332 @@
+use std::io::{self, Read, Write};
+
+use super::{
+    flate,
+    gzip::{self, GzHeader, GZIP_TRAILER_SIZE},
+    Level,
+};
+use flate2::{Compress, Crc, Decompress, FlushCompress, FlushDecompress, Status};
+
+/// The size of the internal buffers of `Encoder` and `Decoder`.
+pub const STREAM_BUFFER_SIZE: usize = 32 * 1024;
+
+/// The stream format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Raw deflate (RFC 1951).
+    Deflate,
+    /// zlib (RFC 1950): header and Adler-32 checksum.
+    Zlib,
+    /// gzip (RFC 1952): header and CRC-32 checksum.
+    Gzip,
+}
+
+impl core::default::Default for Format {
+    /// Returns the default stream format.
+    ///
+    /// Default: `Format::Deflate`
+    fn default() -> Self {
+        Self::Deflate
+    }
+}
+
+/// Encoder
+///
+/// Compresses everything written to it into the inner writer.
+///
+/// `finish` must be called to write the end of the stream.
+///
+/// # Example
+/// ```
+/// let mut encoder = Encoder::new(Format::Gzip, Level::default(), Vec::new()).unwrap();
+///
+/// encoder.write_all(b"hello").unwrap();
+///
+/// let compressed = encoder.finish().unwrap();
+/// ```
+pub struct Encoder<W: Write> {
+    writer: W,
+    compress: Compress,
+    buffer: Vec<u8>,
+    crc: Option<Crc>,
+}
+
+impl<W: Write> Encoder<W> {
+    /// Creates an encoder. A gzip stream gets an empty header (`GzHeader::default`).
+    pub fn new(format: Format, level: Level, writer: W) -> io::Result<Self> {
+        match format {
+            Format::Deflate => Ok(Self::with(flate::compress_new(level, false), None, writer)),
+            Format::Zlib => Ok(Self::with(flate::compress_new(level, true), None, writer)),
+            Format::Gzip => Self::new_gzip(&GzHeader::default(), level, writer),
+        }
+    }
+
+    /// Creates a gzip encoder with the given header. The header is written immediately.
+    pub fn new_gzip(header: &GzHeader, level: Level, mut writer: W) -> io::Result<Self> {
+        writer.write_all(&header.encode(level).map_err(|_| invalid_input())?)?;
+
+        Ok(Self::with(
+            flate::compress_new(level, false),
+            Some(Crc::new()),
+            writer,
+        ))
+    }
+
+    fn with(compress: Compress, crc: Option<Crc>, writer: W) -> Self {
+        Self {
+            writer,
+            compress,
+            buffer: Vec::with_capacity(STREAM_BUFFER_SIZE),
+            crc,
+        }
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Writes the end of the stream (and the gzip trailer) and returns the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        while self.step(&[], FlushCompress::Finish)? != Status::StreamEnd {}
+
+        if let Some(crc) = &self.crc {
+            self.writer
+                .write_all(&gzip::trailer(crc.sum(), crc.amount()))?;
+        }
+
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+
+    /// Compresses into the buffer, writes the buffer out, and returns the status.
+    fn step(&mut self, bytes: &[u8], flush: FlushCompress) -> io::Result<Status> {
+        self.buffer.clear();
+
+        let status = self
+            .compress
+            .compress_vec(bytes, &mut self.buffer, flush)
+            .map_err(|_| invalid_data())?;
+
+        self.writer.write_all(&self.buffer)?;
+
+        Ok(status)
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        let start = self.compress.total_in();
+
+        loop {
+            let read = (self.compress.total_in() - start) as usize;
+
+            if read == bytes.len() {
+                break;
+            }
+
+            self.step(&bytes[read..], FlushCompress::None)?;
+        }
+
+        if let Some(crc) = &mut self.crc {
+            crc.update(bytes);
+        }
+
+        Ok(bytes.len())
+    }
+
+    /// Writes out everything compressed so far (sync flush), then flushes the inner writer.
+    fn flush(&mut self) -> io::Result<()> {
+        loop {
+            self.step(&[], FlushCompress::Sync)?;
+
+            if self.buffer.len() < self.buffer.capacity() {
+                break;
+            }
+        }
+
+        self.writer.flush()
+    }
+}
+
+/// Decoder
+///
+/// Decompresses the inner reader.
+///
+/// The zlib and gzip checksums are verified at the end of the stream, and a mismatch returns `io::ErrorKind::InvalidData`.
+/// A truncated stream returns `io::ErrorKind::UnexpectedEof`.
+///
+/// # Example
+/// ```
+/// let mut decoder = Decoder::new(Format::Gzip, &compressed[..]).unwrap();
+///
+/// let mut message = Vec::new();
+///
+/// decoder.read_to_end(&mut message).unwrap();
+/// ```
+pub struct Decoder<R: Read> {
+    reader: R,
+    decompress: Decompress,
+    input: Vec<u8>,
+    pos: usize,
+    eof: bool,
+    done: bool,
+    crc: Option<Crc>,
+    header: Option<GzHeader>,
+}
+
+impl<R: Read> Decoder<R> {
+    /// Creates a decoder. The gzip header is read immediately.
+    pub fn new(format: Format, reader: R) -> io::Result<Self> {
+        let mut decoder = Self {
+            reader,
+            decompress: Decompress::new(format == Format::Zlib),
+            input: Vec::with_capacity(STREAM_BUFFER_SIZE),
+            pos: 0,
+            eof: false,
+            done: false,
+            crc: None,
+            header: None,
+        };
+
+        if format == Format::Gzip {
+            let mut error = None;
+
+            let header = GzHeader::parse(&mut || {
+                decoder.read_byte().map_err(|e| {
+                    error = Some(e);
+                    cck_common::Error
+                })
+            });
+
+            decoder.header = Some(header.map_err(|_| error.unwrap_or_else(invalid_data))?);
+
+            decoder.crc = Some(Crc::new());
+        }
+
+        Ok(decoder)
+    }
+
+    /// Returns the gzip header.
+    pub fn header(&self) -> Option<&GzHeader> {
+        self.header.as_ref()
+    }
+
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    fn fill(&mut self) -> io::Result<()> {
+        self.input.resize(STREAM_BUFFER_SIZE, 0);
+
+        let len = self.reader.read(&mut self.input)?;
+
+        self.input.truncate(len);
+
+        self.pos = 0;
+
+        self.eof = len == 0;
+
+        Ok(())
+    }
+
+    fn read_byte(&mut self) -> io::Result<u8> {
+        if self.pos == self.input.len() {
+            self.fill()?;
+
+            if self.eof {
+                Err(io::Error::from(io::ErrorKind::UnexpectedEof))?
+            }
+        }
+
+        self.pos += 1;
+
+        Ok(self.input[self.pos - 1])
+    }
+
+    /// Reads and checks the gzip trailer.
+    fn check_trailer(&mut self) -> io::Result<()> {
+        if let Some(crc) = &self.crc {
+            let expected = gzip::trailer(crc.sum(), crc.amount());
+
+            let mut trailer = [0u8; GZIP_TRAILER_SIZE];
+
+            for byte in trailer.iter_mut() {
+                *byte = self.read_byte()?;
+            }
+
+            if trailer != expected {
+                Err(invalid_data())?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for Decoder<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        if self.done || buffer.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            if self.pos == self.input.len() && !self.eof {
+                self.fill()?;
+            }
+
+            let total = (self.decompress.total_in(), self.decompress.total_out());
+
+            let flush = if self.eof {
+                FlushDecompress::Finish
+            } else {
+                FlushDecompress::None
+            };
+
+            let status = self
+                .decompress
+                .decompress(&self.input[self.pos..], buffer, flush)
+                .map_err(|_| invalid_data())?;
+
+            let read = (self.decompress.total_in() - total.0) as usize;
+
+            let written = (self.decompress.total_out() - total.1) as usize;
+
+            self.pos += read;
+
+            if let Some(crc) = &mut self.crc {
+                crc.update(&buffer[..written]);
+            }
+
+            if status == Status::StreamEnd {
+                self.done = true;
+
+                self.check_trailer()?;
+
+                return Ok(written);
+            }
+
+            if written > 0 {
+                return Ok(written);
+            }
+
+            if read == 0 && self.eof {
+                Err(io::Error::from(io::ErrorKind::UnexpectedEof))?
+            }
+
+            if read == 0 && self.pos < self.input.len() {
+                Err(invalid_data())?
+            }
+        }
+    }
+}
+
+fn invalid_data() -> io::Error {
+    io::Error::from(io::ErrorKind::InvalidData)
+}
+
+fn invalid_input() -> io::Error {
+    io::Error::from(io::ErrorKind::InvalidInput)
+}
@@ -0,0 +1,172 @@
+use super::{flate, Level};
+use cck_common::Result;
+use flate2::Decompress;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use super::{Algorithm, Codec, Limits};
+
+/// Compresses the given message with the given level (zlib, RFC 1950).
+///
+/// The zlib framing adds a 2-byte header and an Adler-32 checksum of the message.
+///
+/// # Arguments
+///
+/// * `level` - The level to use.
+///
+/// * `bytes` - The message to compress.
+///
+/// * `buffer` - The buffer to use.
+///
+/// # Errors
+/// If the buffer is too small.
+///
+/// # Example
+/// ```
+/// let mut buffer = [0; 1024];
+///
+/// let compressed = cck_compress::zlib::compress(cck_compress::Level::Fast, b"Hello, World!", &mut buffer).unwrap();
+/// ```
+pub fn compress<const T: usize>(
+    level: Level,
+    bytes: impl AsRef<[u8]>,
+    buffer: &mut [u8; T],
+) -> Result<&[u8]> {
+    flate::compress_into(flate::compress_new(level, true), bytes.as_ref(), buffer)
+}
+
+/// Decompresses the given message (zlib, RFC 1950).
+///
+/// # Arguments
+///
+/// * `bytes` - The message to decompress.
+///
+/// * `buffer` - The buffer to use.
+///
+/// # Errors
+/// If the buffer is too small, the message is invalid or truncated, or the checksum does not match.
+pub fn decompress<const T: usize>(bytes: impl AsRef<[u8]>, buffer: &mut [u8; T]) -> Result<&[u8]> {
+    flate::decompress_into(Decompress::new(true), bytes.as_ref(), buffer)
+}
+
+/// Compresses the given message with the given level (zlib, RFC 1950).
+///
+/// # Example
+/// ```
+/// let compressed = cck_compress::zlib::compress_vec(cck_compress::Level::Fast, b"Hello, World!").unwrap();
+/// ```
+#[cfg(feature = "alloc")]
+pub fn compress_vec(level: Level, bytes: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    flate::compress_vec(flate::compress_new(level, true), bytes.as_ref())
+}
+
+/// Decompresses the given message (zlib, RFC 1950) with the default limits (`Limits::default`).
+///
+/// # Example
+/// ```
+/// let decompressed = cck_compress::zlib::decompress_vec(compressed).unwrap();
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decompress_vec(bytes: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    decompress_vec_with_limits(bytes, &Limits::default())
+}
+
+/// Decompresses the given message (zlib, RFC 1950) with the given limits.
+///
+/// # Errors
+/// If the message is invalid or truncated, the checksum does not match, or the message exceeds the limits.
+#[cfg(feature = "alloc")]
+pub fn decompress_vec_with_limits(bytes: impl AsRef<[u8]>, limits: &Limits) -> Result<Vec<u8>> {
+    Ok(flate::decompress_vec(Decompress::new(true), true, None, bytes.as_ref(), limits)?.0)
+}
+
+/// Compresses the given message (zlib, RFC 1950) with the given level and preset dictionary.
+///
+/// The header records the Adler-32 checksum of the dictionary (DICTID).
+///
+/// # Arguments
+///
+/// * `level` - The level to use.
+///
+/// * `dictionary` - The preset dictionary to use.
+///
+/// * `bytes` - The message to compress.
+#[cfg(feature = "alloc")]
+pub fn compress_vec_with_dictionary(
+    level: Level,
+    dictionary: &[u8],
+    bytes: impl AsRef<[u8]>,
+) -> Result<Vec<u8>> {
+    flate::compress_vec(
+        flate::compress_new_with_dictionary(level, true, dictionary)?,
+        bytes.as_ref(),
+    )
+}
+
+/// Decompresses the given message (zlib, RFC 1950) with the given preset dictionary and limits.
+///
+/// The dictionary is only used if the header asks for it.
+///
+/// # Errors
+/// If the message is invalid or truncated, the DICTID does not match the dictionary, the checksum does not match,
+/// or the message exceeds the limits.
+#[cfg(feature = "alloc")]
+pub fn decompress_vec_with_dictionary(
+    dictionary: &[u8],
+    bytes: impl AsRef<[u8]>,
+    limits: &Limits,
+) -> Result<Vec<u8>> {
+    Ok(flate::decompress_vec(
+        Decompress::new(true),
+        true,
+        Some(dictionary),
+        bytes.as_ref(),
+        limits,
+    )?
+    .0)
+}
+
+/// Zlib
+///
+/// zlib (RFC 1950) as a `Codec`.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Zlib;
+
+#[cfg(feature = "alloc")]
+impl Codec for Zlib {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::Zlib
+    }
+
+    fn compress_vec(&self, level: Level, bytes: &[u8]) -> Result<Vec<u8>> {
+        compress_vec(level, bytes)
+    }
+
+    fn decompress_vec_with_limits(&self, bytes: &[u8], limits: &Limits) -> Result<Vec<u8>> {
+        decompress_vec_with_limits(bytes, limits)
+    }
+
+    fn compress_vec_with_dictionary(
+        &self,
+        level: Level,
+        dictionary: &[u8],
+        bytes: &[u8],
+    ) -> Result<Vec<u8>> {
+        compress_vec_with_dictionary(level, dictionary, bytes)
+    }
+
+    fn decompress_vec_with_dictionary(
+        &self,
+        dictionary: &[u8],
+        bytes: &[u8],
+        limits: &Limits,
+    ) -> Result<Vec<u8>> {
+        decompress_vec_with_dictionary(dictionary, bytes, limits)
+    }
+}
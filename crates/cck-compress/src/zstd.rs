@@ -0,0 +1,181 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use super::{Algorithm, Codec, Level, Limits};
+use cck_common::{Error, Result};
+
+/// Compresses the given message with the given level (zstd, RFC 8878).
+///
+/// The level is mapped with `Level::zstd`.
+///
+/// # Arguments
+///
+/// * `level` - The level to use.
+///
+/// * `bytes` - The message to compress.
+///
+/// * `buffer` - The buffer to use.
+///
+/// # Errors
+/// If the buffer is too small.
+///
+/// # Example
+/// ```
+/// let mut buffer = [0; 1024];
+///
+/// let compressed = cck_compress::zstd::compress(cck_compress::Level::Fast, b"Hello, World!", &mut buffer).unwrap();
+/// ```
+pub fn compress<const T: usize>(
+    level: Level,
+    bytes: impl AsRef<[u8]>,
+    buffer: &mut [u8; T],
+) -> Result<&[u8]> {
+    let len = ::zstd::bulk::compress_to_buffer(bytes.as_ref(), &mut buffer[..], level.zstd())
+        .map_err(|_| Error)?;
+
+    Ok(&buffer[..len])
+}
+
+/// Decompresses the given message (zstd, RFC 8878).
+///
+/// # Errors
+/// If the buffer is too small, or the message is invalid or truncated.
+pub fn decompress<const T: usize>(bytes: impl AsRef<[u8]>, buffer: &mut [u8; T]) -> Result<&[u8]> {
+    let len =
+        ::zstd::bulk::decompress_to_buffer(bytes.as_ref(), &mut buffer[..]).map_err(|_| Error)?;
+
+    Ok(&buffer[..len])
+}
+
+/// Compresses the given message with the given level (zstd, RFC 8878).
+///
+/// # Example
+/// ```
+/// let compressed = cck_compress::zstd::compress_vec(cck_compress::Level::Best, b"Hello, World!").unwrap();
+/// ```
+pub fn compress_vec(level: Level, bytes: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    ::zstd::bulk::compress(bytes.as_ref(), level.zstd()).map_err(|_| Error)
+}
+
+/// Decompresses the given message (zstd, RFC 8878) with the default limits (`Limits::default`).
+///
+/// # Example
+/// ```
+/// let decompressed = cck_compress::zstd::decompress_vec(compressed).unwrap();
+/// ```
+pub fn decompress_vec(bytes: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    decompress_vec_with_limits(bytes, &Limits::default())
+}
+
+/// Decompresses the given message (zstd, RFC 8878) with the given limits.
+///
+/// The content size in the frame header is not trusted for the limits.
+///
+/// # Errors
+/// If the message is invalid or truncated, or exceeds the limits.
+pub fn decompress_vec_with_limits(bytes: impl AsRef<[u8]>, limits: &Limits) -> Result<Vec<u8>> {
+    let bytes = bytes.as_ref();
+
+    let decoder = ::zstd::stream::read::Decoder::new(bytes).map_err(|_| Error)?;
+
+    limits.read_to_end(decoder, bytes.len())
+}
+
+/// Compresses the given message (zstd, RFC 8878) with the given level and dictionary.
+///
+/// The dictionary is either trained (`train_dictionary`) or raw content, and the same dictionary is needed to decompress.
+///
+/// # Arguments
+///
+/// * `level` - The level to use.
+///
+/// * `dictionary` - The dictionary to use.
+///
+/// * `bytes` - The message to compress.
+pub fn compress_vec_with_dictionary(
+    level: Level,
+    dictionary: &[u8],
+    bytes: impl AsRef<[u8]>,
+) -> Result<Vec<u8>> {
+    ::zstd::bulk::Compressor::with_dictionary(level.zstd(), dictionary)
+        .map_err(|_| Error)?
+        .compress(bytes.as_ref())
+        .map_err(|_| Error)
+}
+
+/// Decompresses the given message (zstd, RFC 8878) with the given dictionary and limits.
+///
+/// # Errors
+/// If the message is invalid or truncated, the frame needs another trained dictionary, or the message exceeds the limits.
+pub fn decompress_vec_with_dictionary(
+    dictionary: &[u8],
+    bytes: impl AsRef<[u8]>,
+    limits: &Limits,
+) -> Result<Vec<u8>> {
+    let bytes = bytes.as_ref();
+
+    let decoder =
+        ::zstd::stream::read::Decoder::with_dictionary(bytes, dictionary).map_err(|_| Error)?;
+
+    limits.read_to_end(decoder, bytes.len())
+}
+
+/// Trains a dictionary on the given samples (zdict).
+///
+/// The samples should be typical messages, and there should be many of them (e.g. a hundred times the dictionary size in total).
+///
+/// # Arguments
+///
+/// * `samples` - The messages to train on.
+///
+/// * `max_size` - The maximum size of the dictionary.
+///
+/// # Errors
+/// If there are too few samples to train on.
+///
+/// # Example
+/// ```
+/// let dictionary = cck_compress::zstd::train_dictionary(&samples, 4096).unwrap();
+/// ```
+pub fn train_dictionary<S: AsRef<[u8]>>(samples: &[S], max_size: usize) -> Result<Vec<u8>> {
+    ::zstd::dict::from_samples(samples, max_size).map_err(|_| Error)
+}
+
+/// Zstd
+///
+/// zstd (RFC 8878) as a `Codec`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Zstd;
+
+impl Codec for Zstd {
+    fn algorithm(&self) -> Algorithm {
+        Algorithm::Zstd
+    }
+
+    fn compress_vec(&self, level: Level, bytes: &[u8]) -> Result<Vec<u8>> {
+        compress_vec(level, bytes)
+    }
+
+    fn decompress_vec_with_limits(&self, bytes: &[u8], limits: &Limits) -> Result<Vec<u8>> {
+        decompress_vec_with_limits(bytes, limits)
+    }
+
+    fn compress_vec_with_dictionary(
+        &self,
+        level: Level,
+        dictionary: &[u8],
+        bytes: &[u8],
+    ) -> Result<Vec<u8>> {
+        compress_vec_with_dictionary(level, dictionary, bytes)
+    }
+
+    fn decompress_vec_with_dictionary(
+        &self,
+        dictionary: &[u8],
+        bytes: &[u8],
+        limits: &Limits,
+    ) -> Result<Vec<u8>> {
+        decompress_vec_with_dictionary(dictionary, bytes, limits)
+    }
+}
@@ -71,3 +71,44 @@ fn deflate() {
 
     assert_eq!(&decompressed[..BYTES.len()], &BYTES[..]);
 }
+
+#[cfg(feature = "alloc")]
+#[test]
+fn deflate_vec() {
+    // Compresses far better than 2:1.
+    let bytes = [7u8; 100_000];
+
+    let compressed = cck_compress::deflate::compress_vec(cck_compress::Level::Best, bytes).unwrap();
+
+    assert!(compressed.len() < 1_000);
+
+    let decompressed = cck_compress::deflate::decompress_vec(&compressed).unwrap();
+
+    assert_eq!(decompressed, bytes);
+
+    // Barely compresses.
+    let bytes: Vec<u8> = (0..100_000u32)
+        .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+        .collect();
+
+    let compressed =
+        cck_compress::deflate::compress_vec(cck_compress::Level::Fast, &bytes).unwrap();
+
+    assert_eq!(
+        cck_compress::deflate::decompress_vec(compressed).unwrap(),
+        bytes
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn deflate_vec_err() {
+    let compressed =
+        cck_compress::deflate::compress_vec(cck_compress::Level::default(), [7u8; 1_000]).unwrap();
+
+    // truncated
+    assert_eq!(
+        cck_compress::deflate::decompress_vec(&compressed[..compressed.len() - 1]).is_err(),
+        true
+    );
+}
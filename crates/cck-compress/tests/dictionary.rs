@@ -0,0 +1,253 @@
+// e.g.
+// cargo test --features=dictionary --package cck-compress --test dictionary --  --nocapture
+// cargo test --features=dictionary,zstd --package cck-compress --test dictionary --  --nocapture
+
+/// Small similar JSON messages.
+#[cfg(feature = "alloc")]
+fn samples(count: u32) -> Vec<Vec<u8>> {
+    (0..count)
+        .map(|i| {
+            format!(
+                r#"{{"id":{},"type":"event","user":{{"name":"user-{}","email":"user-{}@example.com"}},"status":"{}"}}"#,
+                i,
+                i * 7 % 1000,
+                i * 7 % 1000,
+                ["active", "pending", "deleted"][i as usize % 3]
+            )
+            .into_bytes()
+        })
+        .collect()
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn dictionary_preset() {
+    let dictionary = br#"{"id":,"type":"event","user":{"name":"user-","email":"user-@example.com"},"status":"active""pending""deleted"}"#;
+
+    let message = &samples(10)[9];
+
+    for algorithm in [
+        cck_compress::Algorithm::Deflate,
+        cck_compress::Algorithm::Zlib,
+    ] {
+        let codec = algorithm.codec();
+
+        let plain = codec
+            .compress_vec(cck_compress::Level::Best, message)
+            .unwrap();
+
+        let compressed = codec
+            .compress_vec_with_dictionary(cck_compress::Level::Best, dictionary, message)
+            .unwrap();
+
+        assert!(compressed.len() * 2 < plain.len());
+
+        assert_eq!(
+            &codec
+                .decompress_vec_with_dictionary(
+                    dictionary,
+                    &compressed,
+                    &cck_compress::Limits::default()
+                )
+                .unwrap(),
+            message
+        );
+
+        // A dictionary is ignored if the stream does not use one.
+        assert_eq!(
+            &codec
+                .decompress_vec_with_dictionary(
+                    dictionary,
+                    &plain,
+                    &cck_compress::Limits::default()
+                )
+                .unwrap(),
+            message
+        );
+
+        assert_eq!(codec.decompress_vec(&compressed).is_err(), true);
+    }
+
+    // The zlib header records the dictionary (DICTID).
+    let compressed = cck_compress::zlib::compress_vec_with_dictionary(
+        cck_compress::Level::Best,
+        dictionary,
+        message,
+    )
+    .unwrap();
+
+    assert_eq!(
+        cck_compress::zlib::decompress_vec_with_dictionary(
+            b"another dictionary",
+            &compressed,
+            &cck_compress::Limits::default()
+        )
+        .is_err(),
+        true
+    );
+}
+
+#[cfg(feature = "dictionary")]
+#[test]
+fn dictionary() {
+    let dictionary = cck_compress::dictionary::Dictionary::new(
+        br#"{"id":,"type":"event","user":{"name":"user-","email":"user-@example.com"},"status":"active""pending""deleted"}"#.to_vec(),
+    );
+
+    assert_eq!(
+        dictionary.id(),
+        &cck_compress::dictionary::dictionary_id(dictionary.as_bytes())
+    );
+
+    let other = cck_compress::dictionary::Dictionary::new(b"another dictionary".to_vec());
+
+    let mut dictionaries = cck_compress::dictionary::Dictionaries::new();
+
+    assert_eq!(dictionaries.insert(dictionary.clone()), *dictionary.id());
+
+    dictionaries.insert(other.clone());
+
+    assert_eq!(dictionaries.len(), 2);
+
+    // stored and loaded by its bytes
+    assert_eq!(
+        dictionaries.get(dictionary.id()).unwrap(),
+        &cck_compress::dictionary::Dictionary::new(dictionary.as_bytes().to_vec())
+    );
+
+    for message in samples(20) {
+        for algorithm in [
+            cck_compress::Algorithm::Deflate,
+            cck_compress::Algorithm::Zlib,
+        ] {
+            let compressed = dictionary
+                .compress_vec(algorithm, cck_compress::Level::Best, &message)
+                .unwrap();
+
+            assert_eq!(
+                cck_compress::dictionary::referenced_dictionary_id(&compressed).unwrap(),
+                dictionary.id()
+            );
+
+            assert_eq!(
+                dictionaries
+                    .decompress_vec(algorithm, &compressed, &cck_compress::Limits::default())
+                    .unwrap(),
+                message
+            );
+
+            // another dictionary
+            assert_eq!(
+                other
+                    .decompress_vec(algorithm, &compressed, &cck_compress::Limits::default())
+                    .is_err(),
+                true
+            );
+        }
+    }
+
+    let compressed = dictionary
+        .compress_vec(
+            cck_compress::Algorithm::Zlib,
+            cck_compress::Level::Best,
+            b"message",
+        )
+        .unwrap();
+
+    // unknown dictionary
+    dictionaries.remove(dictionary.id()).unwrap();
+
+    assert_eq!(
+        dictionaries
+            .decompress_vec(
+                cck_compress::Algorithm::Zlib,
+                &compressed,
+                &cck_compress::Limits::default()
+            )
+            .is_err(),
+        true
+    );
+
+    // too short
+    assert_eq!(
+        cck_compress::dictionary::referenced_dictionary_id(&compressed[..10]).is_err(),
+        true
+    );
+
+    // gzip has no preset dictionary
+    assert_eq!(
+        dictionary
+            .compress_vec(
+                cck_compress::Algorithm::Gzip,
+                cck_compress::Level::Best,
+                b"message"
+            )
+            .is_err(),
+        true
+    );
+}
+
+#[cfg(all(feature = "dictionary", feature = "zstd"))]
+#[test]
+fn dictionary_zstd() {
+    let samples = samples(2_000);
+
+    let dictionary = cck_compress::dictionary::Dictionary::train(&samples, 4096).unwrap();
+
+    assert!(dictionary.as_bytes().len() <= 4096);
+
+    let mut dictionaries = cck_compress::dictionary::Dictionaries::new();
+
+    dictionaries.insert(dictionary.clone());
+
+    let message = br#"{"id":123456,"type":"event","user":{"name":"user-42","email":"user-42@example.com"},"status":"pending"}"#;
+
+    let plain = cck_compress::zstd::compress_vec(cck_compress::Level::Best, message).unwrap();
+
+    let compressed = dictionary
+        .compress_vec(
+            cck_compress::Algorithm::Zstd,
+            cck_compress::Level::Best,
+            message,
+        )
+        .unwrap();
+
+    assert!(compressed.len() - cck_compress::dictionary::DICTIONARY_ID_SIZE < plain.len() / 2);
+
+    assert_eq!(
+        dictionaries
+            .decompress_vec(
+                cck_compress::Algorithm::Zstd,
+                &compressed,
+                &cck_compress::Limits::default()
+            )
+            .unwrap(),
+        message
+    );
+
+    // A trained dictionary is also a preset dictionary for deflate.
+    let compressed = dictionary
+        .compress_vec(
+            cck_compress::Algorithm::Deflate,
+            cck_compress::Level::Best,
+            message,
+        )
+        .unwrap();
+
+    assert_eq!(
+        dictionaries
+            .decompress_vec(
+                cck_compress::Algorithm::Deflate,
+                &compressed,
+                &cck_compress::Limits::default()
+            )
+            .unwrap(),
+        message
+    );
+
+    // too few samples
+    assert_eq!(
+        cck_compress::dictionary::Dictionary::train(&samples[..2], 4096).is_err(),
+        true
+    );
+}
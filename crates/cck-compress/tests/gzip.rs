@@ -0,0 +1,168 @@
+// e.g.
+// cargo test --features=alloc --package cck-compress --test gzip --  --nocapture
+
+#[cfg(feature = "alloc")]
+#[test]
+fn gzip() {
+    let header = cck_compress::gzip::GzHeader {
+        mtime: 1_700_000_000,
+        extra: Some(b"extra".to_vec()),
+        filename: Some(b"hello.txt".to_vec()),
+        comment: Some(b"comment".to_vec()),
+        header_crc: true,
+        ..Default::default()
+    };
+
+    let compressed = cck_compress::gzip::compress_vec_with_header(
+        &header,
+        cck_compress::Level::default(),
+        b"Hello, World!",
+    )
+    .unwrap();
+
+    assert_eq!(compressed[..2], cck_compress::gzip::GZIP_ID);
+
+    let (decoded, message) = cck_compress::gzip::decompress_vec_with_header(
+        &compressed,
+        &cck_compress::Limits::default(),
+    )
+    .unwrap();
+
+    assert_eq!(decoded, header);
+
+    assert_eq!(message, b"Hello, World!");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn gzip_decompress() {
+    // python3 -c "import gzip; print(list(gzip.compress(b'hello', mtime=0)))"
+    let compressed = [
+        31, 139, 8, 0, 0, 0, 0, 0, 2, 3, 203, 72, 205, 201, 201, 7, 0, 134, 166, 16, 54, 5, 0, 0, 0,
+    ];
+
+    assert_eq!(
+        cck_compress::gzip::decompress_vec(compressed).unwrap(),
+        b"hello"
+    );
+
+    // with a file name
+    let compressed = [
+        31, 139, 8, 8, 0, 241, 83, 101, 2, 255, 104, 101, 108, 108, 111, 46, 116, 120, 116, 0, 243,
+        72, 205, 201, 201, 215, 81, 8, 207, 47, 202, 73, 81, 244, 24, 8, 14, 0, 137, 102, 243, 161,
+        130, 0, 0, 0,
+    ];
+
+    let (header, message) = cck_compress::gzip::decompress_vec_with_header(
+        compressed,
+        &cck_compress::Limits::default(),
+    )
+    .unwrap();
+
+    assert_eq!(header.filename.unwrap(), b"hello.txt");
+
+    assert_eq!(header.mtime, 1_700_000_000);
+
+    assert_eq!(message, b"Hello, World!".repeat(10));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn gzip_decompress_err() {
+    let compressed =
+        cck_compress::gzip::compress_vec(cck_compress::Level::default(), b"hello").unwrap();
+
+    // bad CRC-32
+    let mut corrupted = compressed.clone();
+
+    let len = corrupted.len();
+
+    corrupted[len - 8] ^= 1;
+
+    assert_eq!(
+        cck_compress::gzip::decompress_vec(&corrupted).is_err(),
+        true
+    );
+
+    // truncated
+    assert_eq!(
+        cck_compress::gzip::decompress_vec(&compressed[..len - 1]).is_err(),
+        true
+    );
+
+    // trailing bytes
+    let mut trailing = compressed.clone();
+
+    trailing.push(0);
+
+    assert_eq!(cck_compress::gzip::decompress_vec(&trailing).is_err(), true);
+
+    // zero byte in the file name
+    let header = cck_compress::gzip::GzHeader {
+        filename: Some(b"a\0b".to_vec()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        cck_compress::gzip::compress_vec_with_header(&header, cck_compress::Level::default(), b"")
+            .is_err(),
+        true
+    );
+
+    // file name longer than the maximum
+    let header = cck_compress::gzip::GzHeader {
+        filename: Some(vec![97; cck_compress::gzip::GZIP_HEADER_FIELD_MAX_SIZE + 1]),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        cck_compress::gzip::compress_vec_with_header(&header, cck_compress::Level::default(), b"")
+            .is_err(),
+        true
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn gzip_header_field_max_size() {
+    let max = cck_compress::gzip::GZIP_HEADER_FIELD_MAX_SIZE;
+
+    let header = cck_compress::gzip::GzHeader {
+        extra: Some(vec![1; max]),
+        filename: Some(vec![97; max]),
+        comment: Some(vec![98; max]),
+        ..Default::default()
+    };
+
+    let encoded = header.encode(cck_compress::Level::default()).unwrap();
+
+    assert_eq!(
+        cck_compress::gzip::GzHeader::decode(&encoded).unwrap(),
+        (header, encoded.len())
+    );
+
+    // FNAME (8) with an unterminated file name
+    let mut bytes = vec![31, 139, 8, 8, 0, 0, 0, 0, 0, 255];
+
+    bytes.extend_from_slice(&vec![97; max]);
+
+    assert_eq!(cck_compress::gzip::GzHeader::decode(&bytes).is_err(), true);
+
+    bytes.extend_from_slice(&vec![97; max]);
+
+    bytes.push(0);
+
+    assert_eq!(cck_compress::gzip::GzHeader::decode(&bytes).is_err(), true);
+
+    // FCOMMENT (16)
+    bytes[3] = 16;
+
+    assert_eq!(cck_compress::gzip::GzHeader::decode(&bytes).is_err(), true);
+
+    // FEXTRA (4) with XLEN 65535
+    let mut bytes = vec![31, 139, 8, 4, 0, 0, 0, 0, 0, 255, 255, 255];
+
+    bytes.extend_from_slice(&[0; 65535]);
+
+    assert_eq!(cck_compress::gzip::GzHeader::decode(&bytes).is_err(), true);
+}
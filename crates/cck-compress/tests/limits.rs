@@ -0,0 +1,227 @@
+// e.g.
+// cargo test --features=alloc --package cck-compress --test limits --  --nocapture
+// cargo test --features=alloc,zstd,lz4,brotli --package cck-compress --test limits --  --nocapture
+
+/// Writes the `count` low bits of `value`, least significant bit first (RFC 1951, 3.1.1).
+#[cfg(feature = "alloc")]
+fn write_bits(bytes: &mut Vec<u8>, bit: &mut usize, value: u32, count: u32) {
+    for i in 0..count {
+        if (*bit).is_multiple_of(8) {
+            bytes.push(0);
+        }
+
+        *bytes.last_mut().unwrap() |= (((value >> i) & 1) as u8) << (*bit % 8);
+
+        *bit += 1;
+    }
+}
+
+/// Writes a Huffman code, most significant bit first (RFC 1951, 3.1.1).
+#[cfg(feature = "alloc")]
+fn write_code(bytes: &mut Vec<u8>, bit: &mut usize, code: u32, count: u32) {
+    write_bits(bytes, bit, code.reverse_bits() >> (32 - count), count);
+}
+
+/// A crafted raw deflate bomb: a single fixed Huffman block (RFC 1951, 3.2.6) with a literal zero
+/// and `copies` back-references of 258 bytes at distance 1 (13 bits for 258 zero bytes).
+#[cfg(feature = "alloc")]
+fn deflate_bomb(copies: usize) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut bit = 0;
+
+    // BFINAL, BTYPE = 01 (fixed Huffman codes)
+    write_bits(&mut bytes, &mut bit, 1, 1);
+    write_bits(&mut bytes, &mut bit, 1, 2);
+
+    // literal 0
+    write_code(&mut bytes, &mut bit, 0x30, 8);
+
+    for _ in 0..copies {
+        // length 258 (symbol 285), distance 1 (distance code 0)
+        write_code(&mut bytes, &mut bit, 0xc5, 8);
+        write_code(&mut bytes, &mut bit, 0, 5);
+    }
+
+    // end of block (symbol 256)
+    write_code(&mut bytes, &mut bit, 0, 7);
+
+    bytes
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn limits_deflate_bomb() {
+    // 16 MiB
+    const COPIES: usize = 16 * 1024 * 1024 / 258;
+
+    let bomb = deflate_bomb(COPIES);
+
+    assert!(bomb.len() < COPIES * 2);
+
+    assert_eq!(
+        cck_compress::deflate::decompress_vec_with_limits(
+            &bomb,
+            &cck_compress::Limits::unlimited()
+        )
+        .unwrap(),
+        vec![0u8; COPIES * 258 + 1]
+    );
+
+    // The checksums are never reached: the bombs are stopped before the end of the stream.
+    let zlib = [&[0x78, 0x01][..], &bomb].concat();
+
+    let gzip = [
+        cck_compress::gzip::GzHeader::default()
+            .encode(cck_compress::Level::default())
+            .unwrap(),
+        bomb.clone(),
+    ]
+    .concat();
+
+    for (algorithm, compressed) in [
+        (cck_compress::Algorithm::Deflate, &bomb),
+        (cck_compress::Algorithm::Zlib, &zlib),
+        (cck_compress::Algorithm::Gzip, &gzip),
+    ] {
+        // default policy
+        assert_eq!(
+            cck_compress::decompress_vec_with(algorithm, compressed).is_err(),
+            true
+        );
+
+        // output size
+        let limits = cck_compress::Limits {
+            max_output_size: 1024 * 1024,
+            max_ratio: usize::MAX,
+            ratio_threshold: 0,
+        };
+
+        assert_eq!(
+            cck_compress::decompress_vec_with_limits(algorithm, compressed, &limits).is_err(),
+            true
+        );
+
+        // ratio
+        let limits = cck_compress::Limits {
+            max_output_size: usize::MAX,
+            max_ratio: 100,
+            ratio_threshold: 0,
+        };
+
+        assert_eq!(
+            cck_compress::decompress_vec_with_limits(algorithm, compressed, &limits).is_err(),
+            true
+        );
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn limits_output_size() {
+    let message = b"Hello, World!".repeat(100);
+
+    for algorithm in [
+        cck_compress::Algorithm::Deflate,
+        cck_compress::Algorithm::Zlib,
+        cck_compress::Algorithm::Gzip,
+        #[cfg(feature = "zstd")]
+        cck_compress::Algorithm::Zstd,
+        #[cfg(feature = "lz4")]
+        cck_compress::Algorithm::Lz4,
+        #[cfg(feature = "brotli")]
+        cck_compress::Algorithm::Brotli,
+    ] {
+        let compressed =
+            cck_compress::compress_vec_with(algorithm, cck_compress::Level::default(), &message)
+                .unwrap();
+
+        let mut limits = cck_compress::Limits {
+            max_output_size: message.len(),
+            ..Default::default()
+        };
+
+        // exact fit
+        assert_eq!(
+            cck_compress::decompress_vec_with_limits(algorithm, &compressed, &limits).unwrap(),
+            message
+        );
+
+        limits.max_output_size -= 1;
+
+        assert_eq!(
+            cck_compress::decompress_vec_with_limits(algorithm, &compressed, &limits).is_err(),
+            true
+        );
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn limits_ratio() {
+    // 4 MiB
+    let message = vec![0u8; 4 * 1024 * 1024];
+
+    for algorithm in [
+        cck_compress::Algorithm::Deflate,
+        cck_compress::Algorithm::Zlib,
+        cck_compress::Algorithm::Gzip,
+        #[cfg(feature = "zstd")]
+        cck_compress::Algorithm::Zstd,
+        #[cfg(feature = "lz4")]
+        cck_compress::Algorithm::Lz4,
+        #[cfg(feature = "brotli")]
+        cck_compress::Algorithm::Brotli,
+    ] {
+        let compressed =
+            cck_compress::compress_vec_with(algorithm, cck_compress::Level::default(), &message)
+                .unwrap();
+
+        // The ratio is above 100 and the message above the threshold (1 MiB).
+        assert_eq!(
+            cck_compress::decompress_vec_with(algorithm, &compressed).is_err(),
+            true
+        );
+
+        assert_eq!(algorithm.codec().decompress_vec(&compressed).is_err(), true);
+
+        assert_eq!(
+            cck_compress::decompress_vec_with_limits(
+                algorithm,
+                &compressed,
+                &cck_compress::Limits::unlimited()
+            )
+            .unwrap(),
+            message
+        );
+
+        // Below the threshold, the ratio is not checked.
+        let limits = cck_compress::Limits {
+            ratio_threshold: message.len(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            cck_compress::decompress_vec_with_limits(algorithm, &compressed, &limits).unwrap(),
+            message
+        );
+    }
+}
+
+#[test]
+fn limits_max_output() {
+    let limits = cck_compress::Limits::default();
+
+    assert_eq!(limits.max_output(0), cck_compress::DEFAULT_RATIO_THRESHOLD);
+
+    assert_eq!(
+        limits.max_output(100_000),
+        100_000 * cck_compress::DEFAULT_MAX_RATIO
+    );
+
+    assert_eq!(
+        limits.max_output(usize::MAX),
+        cck_compress::DEFAULT_MAX_OUTPUT_SIZE
+    );
+
+    assert_eq!(cck_compress::Limits::unlimited().max_output(0), usize::MAX);
+}
@@ -0,0 +1,51 @@
+// e.g.
+// cargo test --features=lz4 --package cck-compress --test lz4 --  --nocapture
+
+#[cfg(feature = "lz4")]
+#[test]
+fn lz4() {
+    let bytes = b"Hello, World! ".repeat(1_000);
+
+    for level in [
+        cck_compress::Level::Fast,
+        cck_compress::Level::Normal,
+        cck_compress::Level::Best,
+        cck_compress::Level::Any(100),
+    ] {
+        let compressed = cck_compress::lz4::compress_vec(level, &bytes).unwrap();
+
+        assert!(compressed.len() < bytes.len() / 10);
+
+        assert_eq!(
+            cck_compress::lz4::decompress_vec(&compressed).unwrap(),
+            bytes
+        );
+    }
+}
+
+#[cfg(feature = "lz4")]
+#[test]
+fn lz4_codec() {
+    let codec = cck_compress::Algorithm::Lz4.codec();
+
+    assert_eq!(codec.algorithm(), cck_compress::Algorithm::Lz4);
+
+    assert_eq!(
+        cck_compress::Algorithm::from_id(cck_compress::Algorithm::Lz4.id()).unwrap(),
+        cck_compress::Algorithm::Lz4
+    );
+
+    let compressed = codec
+        .compress_vec(cck_compress::Level::default(), b"Hello, World!")
+        .unwrap();
+
+    assert_eq!(codec.decompress_vec(&compressed).unwrap(), b"Hello, World!");
+
+    // truncated
+    assert_eq!(
+        codec
+            .decompress_vec(&compressed[..compressed.len() / 2])
+            .is_err(),
+        true
+    );
+}
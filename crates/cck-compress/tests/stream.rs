@@ -0,0 +1,147 @@
+// e.g.
+// cargo test --features=std --package cck-compress --test stream --  --nocapture
+
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
+
+#[cfg(feature = "std")]
+#[test]
+fn stream() {
+    let message: Vec<u8> = (0..200_000u32)
+        .map(|i| (i % 251) as u8 ^ (i / 1000) as u8)
+        .collect();
+
+    for format in [
+        cck_compress::stream::Format::Deflate,
+        cck_compress::stream::Format::Zlib,
+        cck_compress::stream::Format::Gzip,
+    ] {
+        let mut encoder =
+            cck_compress::stream::Encoder::new(format, cck_compress::Level::default(), Vec::new())
+                .unwrap();
+
+        for chunk in message.chunks(7_000) {
+            encoder.write_all(chunk).unwrap();
+        }
+
+        let compressed = encoder.finish().unwrap();
+
+        let mut decoder = cck_compress::stream::Decoder::new(format, &compressed[..]).unwrap();
+
+        let mut decompressed = Vec::new();
+
+        decoder.read_to_end(&mut decompressed).unwrap();
+
+        assert_eq!(decompressed, message);
+    }
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn stream_gzip_interop() {
+    let header = cck_compress::gzip::GzHeader {
+        filename: Some(b"hello.txt".to_vec()),
+        ..Default::default()
+    };
+
+    let mut encoder = cck_compress::stream::Encoder::new_gzip(
+        &header,
+        cck_compress::Level::default(),
+        Vec::new(),
+    )
+    .unwrap();
+
+    encoder.write_all(b"Hello, ").unwrap();
+
+    encoder.flush().unwrap();
+
+    encoder.write_all(b"World!").unwrap();
+
+    let compressed = encoder.finish().unwrap();
+
+    let (decoded, message) = cck_compress::gzip::decompress_vec_with_header(
+        &compressed,
+        &cck_compress::Limits::default(),
+    )
+    .unwrap();
+
+    assert_eq!(decoded, header);
+
+    assert_eq!(message, b"Hello, World!");
+
+    let compressed = cck_compress::gzip::compress_vec_with_header(
+        &header,
+        cck_compress::Level::default(),
+        b"Hello, World!",
+    )
+    .unwrap();
+
+    let mut decoder =
+        cck_compress::stream::Decoder::new(cck_compress::stream::Format::Gzip, &compressed[..])
+            .unwrap();
+
+    assert_eq!(decoder.header(), Some(&header));
+
+    let mut message = Vec::new();
+
+    decoder.read_to_end(&mut message).unwrap();
+
+    assert_eq!(message, b"Hello, World!");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn stream_err() {
+    let compressed =
+        cck_compress::gzip::compress_vec(cck_compress::Level::default(), b"Hello, World!").unwrap();
+
+    // truncated
+    let mut decoder = cck_compress::stream::Decoder::new(
+        cck_compress::stream::Format::Gzip,
+        &compressed[..compressed.len() - 10],
+    )
+    .unwrap();
+
+    assert_eq!(
+        decoder.read_to_end(&mut Vec::new()).unwrap_err().kind(),
+        std::io::ErrorKind::UnexpectedEof
+    );
+
+    // bad CRC-32
+    let mut corrupted = compressed.clone();
+
+    let len = corrupted.len();
+
+    corrupted[len - 8] ^= 1;
+
+    let mut decoder =
+        cck_compress::stream::Decoder::new(cck_compress::stream::Format::Gzip, &corrupted[..])
+            .unwrap();
+
+    assert_eq!(
+        decoder.read_to_end(&mut Vec::new()).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidData
+    );
+
+    // not gzip
+    assert_eq!(
+        cck_compress::stream::Decoder::new(
+            cck_compress::stream::Format::Gzip,
+            &b"hello, world"[..]
+        )
+        .is_err(),
+        true
+    );
+
+    // file name that never ends
+    assert_eq!(
+        cck_compress::stream::Decoder::new(
+            cck_compress::stream::Format::Gzip,
+            (&[31u8, 139, 8, 8, 0, 0, 0, 0, 0, 255][..]).chain(std::io::repeat(97)),
+        )
+        .err()
+        .unwrap()
+        .kind(),
+        std::io::ErrorKind::InvalidData
+    );
+}
@@ -0,0 +1,92 @@
+// e.g.
+// cargo test --package cck-compress --test zlib --  --nocapture
+
+#[test]
+fn zlib() {
+    let mut buffer = [0u8; 1024];
+
+    let compressed = cck_compress::zlib::compress(
+        cck_compress::Level::default(),
+        b"Hello, World!",
+        &mut buffer,
+    )
+    .unwrap();
+
+    // zlib header
+    assert_eq!(compressed[0], 0x78);
+
+    let mut buffer = [0u8; 1024];
+
+    let decompressed = cck_compress::zlib::decompress(compressed, &mut buffer).unwrap();
+
+    assert_eq!(decompressed, b"Hello, World!");
+}
+
+#[test]
+fn zlib_decompress() {
+    // python3 -c "import zlib; print(list(zlib.compress(b'Hello, World!')))"
+    let compressed = [
+        120, 156, 243, 72, 205, 201, 201, 215, 81, 8, 207, 47, 202, 73, 81, 4, 0, 31, 158, 4, 106,
+    ];
+
+    let mut buffer = [0u8; 1024];
+
+    assert_eq!(
+        cck_compress::zlib::decompress(compressed, &mut buffer).unwrap(),
+        b"Hello, World!"
+    );
+
+    // bad checksum
+    let mut corrupted = compressed;
+
+    corrupted[20] ^= 1;
+
+    assert_eq!(
+        cck_compress::zlib::decompress(corrupted, &mut buffer).is_err(),
+        true
+    );
+}
+
+#[test]
+fn zlib_buffer_too_small() {
+    let mut buffer = [0u8; 4];
+
+    assert_eq!(
+        cck_compress::zlib::compress(
+            cck_compress::Level::default(),
+            b"Hello, World!",
+            &mut buffer
+        )
+        .is_err(),
+        true
+    );
+
+    let mut buffer = [0u8; 1024];
+
+    let compressed = cck_compress::zlib::compress(
+        cck_compress::Level::default(),
+        b"Hello, World!",
+        &mut buffer,
+    )
+    .unwrap();
+
+    let mut buffer = [0u8; 4];
+
+    assert_eq!(
+        cck_compress::zlib::decompress(compressed, &mut buffer).is_err(),
+        true
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn zlib_vec() {
+    let bytes = [b'a'; 100_000];
+
+    let compressed = cck_compress::zlib::compress_vec(cck_compress::Level::Best, bytes).unwrap();
+
+    assert_eq!(
+        cck_compress::zlib::decompress_vec(compressed).unwrap(),
+        bytes
+    );
+}
@@ -0,0 +1,83 @@
+// e.g.
+// cargo test --features=zstd --package cck-compress --test zstd --  --nocapture
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd() {
+    let bytes = b"Hello, World! ".repeat(1_000);
+
+    for level in [
+        cck_compress::Level::Fast,
+        cck_compress::Level::Normal,
+        cck_compress::Level::Best,
+        cck_compress::Level::Any(100),
+    ] {
+        let compressed = cck_compress::zstd::compress_vec(level, &bytes).unwrap();
+
+        assert!(compressed.len() < bytes.len() / 10);
+
+        assert_eq!(
+            cck_compress::zstd::decompress_vec(&compressed).unwrap(),
+            bytes
+        );
+    }
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd_codec() {
+    let codec = cck_compress::Algorithm::Zstd.codec();
+
+    assert_eq!(codec.algorithm(), cck_compress::Algorithm::Zstd);
+
+    assert_eq!(
+        cck_compress::Algorithm::from_id(cck_compress::Algorithm::Zstd.id()).unwrap(),
+        cck_compress::Algorithm::Zstd
+    );
+
+    let compressed = codec
+        .compress_vec(cck_compress::Level::default(), b"Hello, World!")
+        .unwrap();
+
+    assert_eq!(codec.decompress_vec(&compressed).unwrap(), b"Hello, World!");
+
+    // truncated
+    assert_eq!(
+        codec
+            .decompress_vec(&compressed[..compressed.len() / 2])
+            .is_err(),
+        true
+    );
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd_buffer() {
+    let mut buffer = [0u8; 1024];
+
+    let compressed = cck_compress::zstd::compress(
+        cck_compress::Level::default(),
+        b"Hello, World!",
+        &mut buffer,
+    )
+    .unwrap();
+
+    let mut buffer = [0u8; 1024];
+
+    assert_eq!(
+        cck_compress::zstd::decompress(compressed, &mut buffer).unwrap(),
+        b"Hello, World!"
+    );
+
+    let mut buffer = [0u8; 4];
+
+    assert_eq!(
+        cck_compress::zstd::compress(
+            cck_compress::Level::default(),
+            b"Hello, World!",
+            &mut buffer
+        )
+        .is_err(),
+        true
+    );
+}
@@ -32,7 +32,7 @@ pub fn decode<'a, const T: usize>(
 /// let base64 = base64ct::encode(&bytes, &mut buffer)?;
 /// ```
 pub fn encode<'a, const T: usize>(
-    bytes: &'a [u8],
+    bytes: &[u8],
     buffer: &'a mut [u8; T],
 ) -> Result<&'a str> {
     base64ct::Base64::encode(bytes, buffer).map_err(|_| Error)
@@ -25,8 +25,12 @@ pub const PEM_LABEL_CCK_PRIVATE_KEY: &Label = "CCK PRIVATE KEY";
 
 pub const PEM_LABEL_CCK_PUBLIC_KEY: &Label = "CCK PUBLIC KEY";
 
+pub const PEM_LABEL_CCK_SYMMETRIC_KEY: &Label = "CCK SYMMETRIC KEY";
+
 pub const PEM_LABEL_MESSAGE: &Label = "CCK MESSAGE";
 
+pub const PEM_LABEL_CCK_CERTIFICATE: &Label = "CCK CERTIFICATE";
+
 /// Encode a PEM block.
 ///
 /// # Example
@@ -37,7 +41,7 @@ pub const PEM_LABEL_MESSAGE: &Label = "CCK MESSAGE";
 /// ```
 pub fn encode<'a, const T: usize>(
     label: &Label,
-    bytes: &'a [u8],
+    bytes: &[u8],
     buffer: &'a mut [u8; T],
 ) -> Result<&'a str> {
     pem_rfc7468::encode(label, LINE_ENDING, bytes, buffer).map_err(|_| Error)
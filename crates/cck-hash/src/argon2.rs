@@ -1,25 +1,95 @@
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
 use cck_common::size::SIZE_32;
 
-pub enum Argorithm {
+/// The maximum number of iterations (`t_cost`) tried by `calibrate`.
+pub const ARGON2_CALIBRATE_MAX_T_COST: u32 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
     Argon2i,
     Argon2d,
     Argon2id,
 }
 
+/// Former (misspelled) name of `Algorithm`.
+#[deprecated(note = "use `Algorithm`")]
+pub type Argorithm = Algorithm;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Version {
     V0x10,
     V0x13,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Params {
     Default,
     Custom {
         m_cost: u32,
         t_cost: u32,
         p_cost: u32,
+        output_len: usize,
     },
 }
 
+/// Rehash Policy
+///
+/// The algorithm, version and parameters that stored PHC strings are expected to use.
+///
+/// Default: Argon2id, Version 0x13, `Params::Default`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Policy {
+    pub algorithm: Algorithm,
+    pub version: Version,
+    pub params: Params,
+}
+
+impl core::default::Default for Policy {
+    fn default() -> Self {
+        Self {
+            algorithm: Algorithm::Argon2id,
+            version: Version::V0x13,
+            params: Params::Default,
+        }
+    }
+}
+
+impl From<Algorithm> for argon2::Algorithm {
+    fn from(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Argon2i => argon2::Algorithm::Argon2i,
+            Algorithm::Argon2d => argon2::Algorithm::Argon2d,
+            Algorithm::Argon2id => argon2::Algorithm::Argon2id,
+        }
+    }
+}
+
+impl From<Version> for argon2::Version {
+    fn from(version: Version) -> Self {
+        match version {
+            Version::V0x10 => argon2::Version::V0x10,
+            Version::V0x13 => argon2::Version::V0x13,
+        }
+    }
+}
+
+impl TryFrom<Params> for argon2::Params {
+    type Error = cck_common::Error;
+
+    fn try_from(params: Params) -> cck_common::Result<Self> {
+        match params {
+            Params::Default => Ok(argon2::Params::default()),
+            Params::Custom {
+                m_cost,
+                t_cost,
+                p_cost,
+                output_len,
+            } => argon2::Params::new(m_cost, t_cost, p_cost, Some(output_len))
+                .map_err(|_| cck_common::Error),
+        }
+    }
+}
+
 // #[cfg(feature = "alloc")]
 // extern crate alloc;
 
@@ -79,32 +149,116 @@ pub fn argon2id() -> argon2::Argon2<'static> {
 
 /// Argon2 Custom
 pub fn argon2_custom(
-    argorithm: Argorithm,
+    algorithm: Algorithm,
     version: Version,
     params: Params,
 ) -> cck_common::Result<argon2::Argon2<'static>> {
-    let params = match params {
-        Params::Default => argon2::Params::default(),
-        Params::Custom {
+    Ok(argon2::Argon2::new(
+        algorithm.into(),
+        version.into(),
+        params.try_into()?,
+    ))
+}
+
+/// Argon2 Custom with Secret and Associated Data
+///
+/// The secret (pepper) is mixed into every hash and must be kept out of the password store. Up to 4 GiB.
+///
+/// The associated data is bound to every hash. Up to 32 bytes.
+///
+/// # Example
+/// ```
+/// let argon2 = argon2_with_secret(
+///     Algorithm::Argon2id,
+///     Version::V0x13,
+///     Params::Default,
+///     b"pepper",
+///     b"associated data",
+/// ).unwrap();
+/// ```
+pub fn argon2_with_secret<'a>(
+    algorithm: Algorithm,
+    version: Version,
+    params: Params,
+    secret: &'a [u8],
+    associated_data: &[u8],
+) -> cck_common::Result<argon2::Argon2<'a>> {
+    let params: argon2::Params = params.try_into()?;
+
+    let mut builder = argon2::ParamsBuilder::new();
+
+    builder
+        .m_cost(params.m_cost())
+        .t_cost(params.t_cost())
+        .p_cost(params.p_cost())
+        .data(argon2::AssociatedData::new(associated_data).map_err(|_| cck_common::Error)?);
+
+    if let Some(output_len) = params.output_len() {
+        builder.output_len(output_len);
+    }
+
+    argon2::Argon2::new_with_secret(
+        secret,
+        algorithm.into(),
+        version.into(),
+        builder.build().map_err(|_| cck_common::Error)?,
+    )
+    .map_err(|_| cck_common::Error)
+}
+
+/// Calibrate
+///
+/// Picks `m_cost` and `t_cost` so that one digest takes at least the given duration on the current machine.
+///
+/// Memory is doubled first (starting at 19 MiB) up to `max_m_cost` KiB, then iterations are increased
+/// up to `ARGON2_CALIBRATE_MAX_T_COST`.
+///
+/// Parallelism: 1, Output length: 32 bytes
+///
+/// # Errors
+/// If the duration is not reached with `ARGON2_CALIBRATE_MAX_T_COST` iterations.
+///
+/// # Example
+/// ```
+/// let params = calibrate(Algorithm::Argon2id, core::time::Duration::from_millis(500), 262144).unwrap();
+///
+/// let argon2 = argon2_custom(Algorithm::Argon2id, Version::V0x13, params).unwrap();
+/// ```
+pub fn calibrate(
+    algorithm: Algorithm,
+    target: core::time::Duration,
+    max_m_cost: u32,
+) -> cck_common::Result<Params> {
+    let mut m_cost = argon2::Params::DEFAULT_M_COST.min(max_m_cost);
+
+    let mut t_cost = 1;
+
+    loop {
+        let params = Params::Custom {
             m_cost,
             t_cost,
-            p_cost,
-        } => argon2::Params::new(m_cost, t_cost, p_cost, Some(SIZE_32))
-            .map_err(|_| cck_common::Error)?,
-    };
+            p_cost: 1,
+            output_len: SIZE_32,
+        };
 
-    Ok(argon2::Argon2::new(
-        match argorithm {
-            Argorithm::Argon2i => argon2::Algorithm::Argon2i,
-            Argorithm::Argon2d => argon2::Algorithm::Argon2d,
-            Argorithm::Argon2id => argon2::Algorithm::Argon2id,
-        },
-        match version {
-            Version::V0x10 => argon2::Version::V0x10,
-            Version::V0x13 => argon2::Version::V0x13,
-        },
-        params,
-    ))
+        let argon2 = argon2_custom(algorithm, Version::V0x13, params)?;
+
+        let now = std::time::Instant::now();
+
+        digest(&argon2, &[0u8; SIZE_32], &[0u8; SIZE_32])?;
+
+        if now.elapsed() >= target {
+            return Ok(params);
+        }
+
+        if m_cost <= max_m_cost / 2 {
+            m_cost *= 2;
+        } else if t_cost < ARGON2_CALIBRATE_MAX_T_COST {
+            t_cost += 1;
+        } else {
+            Err(cck_common::Error)?
+        }
+    }
 }
 
 /// Digest
@@ -141,3 +295,154 @@ pub fn digest(
         .map_err(|_| cck_common::Error)?;
     Ok(output)
 }
+
+/// Digest into Buffer
+///
+/// Digests the given password with the given salt, filling the given buffer.
+///
+/// The buffer length must match the output length of the Argon2 instance (4 bytes ~ when unspecified).
+///
+/// # Example
+/// ```
+/// let argon2 = argon2_custom(
+///     Algorithm::Argon2id,
+///     Version::V0x13,
+///     Params::Custom { m_cost: 19456, t_cost: 2, p_cost: 1, output_len: 64 },
+/// ).unwrap();
+///
+/// let mut buffer = [0u8; 64];
+///
+/// digest_into(&argon2, b"password", b"saltsalt", &mut buffer).unwrap();
+///
+/// let (encryption_key, mac_key) = buffer.split_at(32);
+/// ```
+pub fn digest_into(
+    argon2: &argon2::Argon2,
+    password: &[u8],
+    salt: &[u8],
+    buffer: &mut [u8],
+) -> cck_common::Result<()> {
+    argon2
+        .hash_password_into(password, salt, buffer)
+        .map_err(|_| cck_common::Error)
+}
+
+/// Hash Password
+///
+/// Hashes the given password with Argon2id (default parameters) and a random 16-byte salt.
+///
+/// Returns a PHC string, e.g. `$argon2id$v=19$m=19456,t=2,p=1$<salt>$<hash>`.
+///
+/// # Example
+/// ```
+/// let phc = hash_password(b"password").unwrap();
+///
+/// assert!(verify_password(b"password", &phc).is_ok());
+/// ```
+pub fn hash_password(password: &[u8]) -> cck_common::Result<String> {
+    hash_password_with(&argon2id(), password)
+}
+
+/// Hash Password
+///
+/// Hashes the given password with the given Argon2 instance and a random 16-byte salt.
+///
+/// Returns a PHC string.
+///
+/// # Example
+/// ```
+/// let argon2 = argon2_custom(
+///     Algorithm::Argon2id,
+///     Version::V0x13,
+///     Params::Custom { m_cost: 65536, t_cost: 3, p_cost: 1, output_len: 32 },
+/// ).unwrap();
+///
+/// let phc = hash_password_with(&argon2, b"password").unwrap();
+/// ```
+pub fn hash_password_with(argon2: &argon2::Argon2, password: &[u8]) -> cck_common::Result<String> {
+    let salt = SaltString::encode_b64(&cck_rand::gen_16()).map_err(|_| cck_common::Error)?;
+
+    let phc = argon2
+        .hash_password(password, &salt)
+        .map_err(|_| cck_common::Error)?;
+
+    Ok(phc.to_string())
+}
+
+/// Verify Password
+///
+/// Verifies the given password against the given PHC string.
+///
+/// The algorithm, version and parameters are taken from the PHC string.
+///
+/// # Example
+/// ```
+/// let phc = hash_password(b"password").unwrap();
+///
+/// assert!(verify_password(b"password", &phc).is_ok());
+///
+/// assert!(verify_password(b"wrong", &phc).is_err());
+/// ```
+pub fn verify_password(password: &[u8], phc: &str) -> cck_common::Result<()> {
+    verify_password_with(&argon2::Argon2::default(), password, phc)
+}
+
+/// Verify Password
+///
+/// Verifies the given password against the given PHC string, using the secret (pepper) of the given Argon2 instance.
+///
+/// The algorithm, version and parameters are taken from the PHC string.
+///
+/// # Example
+/// ```
+/// let argon2 = argon2_with_secret(Algorithm::Argon2id, Version::V0x13, Params::Default, b"pepper", &[]).unwrap();
+///
+/// let phc = hash_password_with(&argon2, b"password").unwrap();
+///
+/// assert!(verify_password_with(&argon2, b"password", &phc).is_ok());
+///
+/// assert!(verify_password(b"password", &phc).is_err());
+/// ```
+pub fn verify_password_with(
+    argon2: &argon2::Argon2,
+    password: &[u8],
+    phc: &str,
+) -> cck_common::Result<()> {
+    let phc = PasswordHash::new(phc).map_err(|_| cck_common::Error)?;
+
+    argon2
+        .verify_password(password, &phc)
+        .map_err(|_| cck_common::Error)
+}
+
+/// Needs Rehash
+///
+/// Returns true if the given PHC string was not produced with the algorithm, version and parameters of the given policy.
+///
+/// # Example
+/// ```
+/// let phc = hash_password(b"password").unwrap();
+///
+/// assert_eq!(needs_rehash(&phc, &Policy::default()).unwrap(), false);
+/// ```
+pub fn needs_rehash(phc: &str, policy: &Policy) -> cck_common::Result<bool> {
+    let phc = PasswordHash::new(phc).map_err(|_| cck_common::Error)?;
+
+    let algorithm =
+        argon2::Algorithm::try_from(phc.algorithm).map_err(|_| cck_common::Error)?;
+
+    // The version is optional in a PHC string, absent means 0x10.
+    let version = argon2::Version::try_from(phc.version.unwrap_or(0x10))
+        .map_err(|_| cck_common::Error)?;
+
+    let params = argon2::Params::try_from(&phc).map_err(|_| cck_common::Error)?;
+
+    let expected: argon2::Params = policy.params.try_into()?;
+
+    Ok(algorithm != policy.algorithm.into()
+        || version != policy.version.into()
+        || params.m_cost() != expected.m_cost()
+        || params.t_cost() != expected.t_cost()
+        || params.p_cost() != expected.p_cost()
+        || params.output_len().unwrap_or(SIZE_32) != expected.output_len().unwrap_or(SIZE_32))
+}
@@ -0,0 +1,67 @@
+use super::Digest;
+use cck_common::{
+    size::{SIZE_32, SIZE_64},
+    Error, Result,
+};
+
+/// BLAKE2b-512
+///
+/// # Example
+/// ```
+/// let hash = digest(&[], &[]);
+/// ```
+pub fn digest(bytes: &[u8], salt: &[u8]) -> [u8; SIZE_64] {
+    blake2b_digest(bytes, salt)
+}
+
+/// BLAKE2b-512
+///
+/// # Example
+/// ```
+/// let hash = blake2b_digest(&[], &[]);
+/// ```
+pub fn blake2b_digest(bytes: &[u8], salt: &[u8]) -> [u8; SIZE_64] {
+    let mut hasher = blake2::Blake2b512::new();
+
+    hasher.update(bytes);
+
+    hasher.update(salt);
+
+    hasher.finalize_reset().into()
+}
+
+/// BLAKE2s-256
+///
+/// # Example
+/// ```
+/// let hash = blake2s_digest(&[], &[]);
+/// ```
+pub fn blake2s_digest(bytes: &[u8], salt: &[u8]) -> [u8; SIZE_32] {
+    let mut hasher = blake2::Blake2s256::new();
+
+    hasher.update(bytes);
+
+    hasher.update(salt);
+
+    hasher.finalize_reset().into()
+}
+
+/// BLAKE2b-512 Message Authentication Code (keyed BLAKE2b)
+///
+/// The key may be up to 64 bytes long.
+///
+/// # Example
+/// ```
+/// let hash = blake2b_mac(&[0u8;32], &[], &[]).unwrap();
+/// ```
+pub fn blake2b_mac(key: &[u8], message: &[u8], salt: &[u8]) -> Result<[u8; SIZE_64]> {
+    use digest::Mac;
+
+    let mut mac = blake2::Blake2bMac512::new_from_slice(key).map_err(|_| Error)?;
+
+    mac.update(message);
+
+    mac.update(salt);
+
+    Ok(mac.finalize().into_bytes().into())
+}
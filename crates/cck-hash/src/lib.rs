@@ -1,4 +1,148 @@
+pub mod blake2;
 pub mod blake3;
 pub mod sha2;
+pub mod sha3;
 pub mod argon2;
-pub use digest::Digest;
\ No newline at end of file
+pub mod pbkdf2;
+pub mod scrypt;
+pub use digest::Digest;
+
+use cck_common::{Error, Result};
+
+/// The hash algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Sha256,
+    Sha512,
+    Sha512_256,
+    Sha3_256,
+    Sha3_512,
+    Blake2b,
+    Blake2s,
+    Blake3,
+}
+
+impl Algorithm {
+    /// Returns the output length of the algorithm in bytes.
+    pub fn output_len(&self) -> usize {
+        match self {
+            Algorithm::Sha256 => 32,
+            Algorithm::Sha512 => 64,
+            Algorithm::Sha512_256 => 32,
+            Algorithm::Sha3_256 => 32,
+            Algorithm::Sha3_512 => 64,
+            Algorithm::Blake2b => 64,
+            Algorithm::Blake2s => 32,
+            Algorithm::Blake3 => 32,
+        }
+    }
+}
+
+impl core::default::Default for Algorithm {
+    /// Returns the default hash algorithm.
+    ///
+    /// Default: `Algorithm::Blake3`
+    fn default() -> Self {
+        Self::Blake3
+    }
+}
+
+/// Digests the given bytes with the given algorithm.
+///
+/// # Arguments
+///
+/// * `algorithm` - The algorithm to use.
+///
+/// * `bytes` - The bytes to digest.
+///
+/// * `salt` - The salt to use.
+///
+/// * `buffer` - The buffer to use. Must be at least `algorithm.output_len()` bytes.
+///
+/// # Example
+/// ```
+/// let mut buffer = [0u8; 64];
+///
+/// let hash = cck_hash::digest_with(cck_hash::Algorithm::Sha3_256, &[], &[], &mut buffer).unwrap();
+/// ```
+pub fn digest_with<'a>(
+    algorithm: Algorithm,
+    bytes: &[u8],
+    salt: &[u8],
+    buffer: &'a mut [u8],
+) -> Result<&'a [u8]> {
+    let len = algorithm.output_len();
+
+    if buffer.len() < len {
+        Err(Error)?
+    }
+
+    match algorithm {
+        Algorithm::Sha256 => buffer[..len].copy_from_slice(&sha2::sha256_digest(bytes, salt)),
+        Algorithm::Sha512 => buffer[..len].copy_from_slice(&sha2::sha512_digest(bytes, salt)),
+        Algorithm::Sha512_256 => {
+            buffer[..len].copy_from_slice(&sha2::sha512_256_digest(bytes, salt))
+        }
+        Algorithm::Sha3_256 => buffer[..len].copy_from_slice(&sha3::sha3_256_digest(bytes, salt)),
+        Algorithm::Sha3_512 => buffer[..len].copy_from_slice(&sha3::sha3_512_digest(bytes, salt)),
+        Algorithm::Blake2b => buffer[..len].copy_from_slice(&blake2::blake2b_digest(bytes, salt)),
+        Algorithm::Blake2s => buffer[..len].copy_from_slice(&blake2::blake2s_digest(bytes, salt)),
+        Algorithm::Blake3 => buffer[..len].copy_from_slice(&blake3::digest(bytes, salt)),
+    }
+
+    Ok(&buffer[..len])
+}
+
+/// The password-based key derivation function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kdf {
+    Argon2(argon2::Algorithm, argon2::Version, argon2::Params),
+    Scrypt(scrypt::Params),
+    Pbkdf2Sha256(pbkdf2::Params),
+    Pbkdf2Sha512(pbkdf2::Params),
+}
+
+impl core::default::Default for Kdf {
+    /// Returns the default password-based key derivation function.
+    ///
+    /// Default: `Kdf::Argon2(Argon2id, V0x13, Params::Default)`
+    fn default() -> Self {
+        Self::Argon2(
+            argon2::Algorithm::Argon2id,
+            argon2::Version::V0x13,
+            argon2::Params::Default,
+        )
+    }
+}
+
+/// Derives a key from the given password and salt with the given key derivation function.
+///
+/// # Arguments
+///
+/// * `kdf` - The key derivation function to use.
+///
+/// * `password` - The password to derive from.
+///
+/// * `salt` - The salt to use.
+///
+/// * `buffer` - The buffer to fill with the derived key.
+///
+/// # Example
+/// ```
+/// let mut buffer = [0u8; 32];
+///
+/// cck_hash::derive_key_with(cck_hash::Kdf::default(), b"password", b"saltsalt", &mut buffer).unwrap();
+/// ```
+pub fn derive_key_with(kdf: Kdf, password: &[u8], salt: &[u8], buffer: &mut [u8]) -> Result<()> {
+    match kdf {
+        Kdf::Argon2(algorithm, version, params) => argon2::digest_into(
+            &argon2::argon2_custom(algorithm, version, params)?,
+            password,
+            salt,
+            buffer,
+        ),
+        Kdf::Scrypt(params) => scrypt::derive_key(params, password, salt, buffer),
+        Kdf::Pbkdf2Sha256(params) => pbkdf2::sha256_derive_key(params, password, salt, buffer),
+        Kdf::Pbkdf2Sha512(params) => pbkdf2::sha512_derive_key(params, password, salt, buffer),
+    }
+}
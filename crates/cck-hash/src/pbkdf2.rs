@@ -0,0 +1,96 @@
+use cck_common::{Error, Result};
+
+/// Default PBKDF2-HMAC-SHA256 iteration count (OWASP recommendation).
+pub const PBKDF2_DEFAULT_ROUNDS: u32 = 600_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Params {
+    Default,
+    Custom { rounds: u32 },
+}
+
+impl Params {
+    fn rounds(&self) -> Result<u32> {
+        match self {
+            Params::Default => Ok(PBKDF2_DEFAULT_ROUNDS),
+            Params::Custom { rounds: 0 } => Err(Error),
+            Params::Custom { rounds } => Ok(*rounds),
+        }
+    }
+}
+
+/// PBKDF2-HMAC-SHA256 derive key
+///
+/// # Example
+/// ```
+/// let mut buffer = [0u8; 32];
+///
+/// derive_key(Params::Default, b"password", b"salt", &mut buffer).unwrap();
+/// ```
+pub fn derive_key(params: Params, password: &[u8], salt: &[u8], buffer: &mut [u8]) -> Result<()> {
+    sha256_derive_key(params, password, salt, buffer)
+}
+
+/// PBKDF2-HMAC-SHA256 derive key
+///
+/// Derives a key from the given password and salt, filling the given buffer.
+///
+/// Default: 600,000 rounds
+///
+/// # Arguments
+///
+/// * `params` - The parameters to use.
+///
+/// * `password` - The password to derive from.
+///
+/// * `salt` - The salt to use.
+///
+/// * `buffer` - The buffer to fill with the derived key. 1 byte ~.
+///
+/// # Example
+/// ```
+/// let mut buffer = [0u8; 32];
+///
+/// sha256_derive_key(Params::Custom { rounds: 100_000 }, b"password", b"salt", &mut buffer).unwrap();
+/// ```
+pub fn sha256_derive_key(
+    params: Params,
+    password: &[u8],
+    salt: &[u8],
+    buffer: &mut [u8],
+) -> Result<()> {
+    if buffer.is_empty() {
+        Err(Error)?
+    }
+
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(password, salt, params.rounds()?, buffer);
+
+    Ok(())
+}
+
+/// PBKDF2-HMAC-SHA512 derive key
+///
+/// Derives a key from the given password and salt, filling the given buffer.
+///
+/// Default: 600,000 rounds
+///
+/// # Example
+/// ```
+/// let mut buffer = [0u8; 64];
+///
+/// sha512_derive_key(Params::Default, b"password", b"salt", &mut buffer).unwrap();
+/// ```
+pub fn sha512_derive_key(
+    params: Params,
+    password: &[u8],
+    salt: &[u8],
+    buffer: &mut [u8],
+) -> Result<()> {
+    if buffer.is_empty() {
+        Err(Error)?
+    }
+
+    pbkdf2::pbkdf2_hmac::<sha2::Sha512>(password, salt, params.rounds()?, buffer);
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use cck_common::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Params {
+    Default,
+    Custom { log_n: u8, r: u32, p: u32 },
+}
+
+impl TryFrom<Params> for scrypt::Params {
+    type Error = Error;
+
+    fn try_from(params: Params) -> Result<Self> {
+        match params {
+            Params::Default => Ok(scrypt::Params::recommended()),
+            Params::Custom { log_n, r, p } => {
+                scrypt::Params::new(log_n, r, p, scrypt::Params::RECOMMENDED_LEN).map_err(|_| Error)
+            }
+        }
+    }
+}
+
+/// Scrypt derive key
+///
+/// Derives a key from the given password and salt, filling the given buffer.
+///
+/// Default: log2(N): 17, r: 8, p: 1
+///
+/// # Arguments
+///
+/// * `params` - The parameters to use.
+///
+/// * `password` - The password to derive from.
+///
+/// * `salt` - The salt to use.
+///
+/// * `buffer` - The buffer to fill with the derived key. 1 byte ~.
+///
+/// # Example
+/// ```
+/// let mut buffer = [0u8; 32];
+///
+/// derive_key(Params::Default, b"password", b"salt", &mut buffer).unwrap();
+/// ```
+pub fn derive_key(params: Params, password: &[u8], salt: &[u8], buffer: &mut [u8]) -> Result<()> {
+    scrypt::scrypt(password, salt, &params.try_into()?, buffer).map_err(|_| Error)
+}
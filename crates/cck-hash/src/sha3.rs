@@ -0,0 +1,84 @@
+use super::Digest;
+use cck_common::size::{SIZE_32, SIZE_64};
+
+/// SHA3-256
+///
+/// # Example
+/// ```
+/// let hash = digest(&[], &[]);
+/// ```
+pub fn digest(bytes: &[u8], salt: &[u8]) -> [u8; SIZE_32] {
+    sha3_256_digest(bytes, salt)
+}
+
+/// SHA3-256
+///
+/// # Example
+/// ```
+/// let hash = sha3_256_digest(&[], &[]);
+/// ```
+pub fn sha3_256_digest(bytes: &[u8], salt: &[u8]) -> [u8; SIZE_32] {
+    let mut hasher = sha3::Sha3_256::new();
+
+    hasher.update(bytes);
+
+    hasher.update(salt);
+
+    hasher.finalize_reset().into()
+}
+
+/// SHA3-512
+///
+/// # Example
+/// ```
+/// let hash = sha3_512_digest(&[], &[]);
+/// ```
+pub fn sha3_512_digest(bytes: &[u8], salt: &[u8]) -> [u8; SIZE_64] {
+    let mut hasher = sha3::Sha3_512::new();
+
+    hasher.update(bytes);
+
+    hasher.update(salt);
+
+    hasher.finalize_reset().into()
+}
+
+/// SHAKE128 Extendable Output Function (XOF)
+///
+/// Fills the given buffer with the output.
+///
+/// # Example
+/// ```
+/// let mut buffer:[u8;32] = [0u8;32];
+///
+/// shake128_digest(&[], &[], &mut buffer);
+/// ```
+pub fn shake128_digest(bytes: &[u8], salt: &[u8], buffer: &mut [u8]) {
+    use digest::{ExtendableOutput, Update, XofReader};
+
+    sha3::Shake128::default()
+        .chain(bytes)
+        .chain(salt)
+        .finalize_xof()
+        .read(buffer)
+}
+
+/// SHAKE256 Extendable Output Function (XOF)
+///
+/// Fills the given buffer with the output.
+///
+/// # Example
+/// ```
+/// let mut buffer:[u8;64] = [0u8;64];
+///
+/// shake256_digest(&[], &[], &mut buffer);
+/// ```
+pub fn shake256_digest(bytes: &[u8], salt: &[u8], buffer: &mut [u8]) {
+    use digest::{ExtendableOutput, Update, XofReader};
+
+    sha3::Shake256::default()
+        .chain(bytes)
+        .chain(salt)
+        .finalize_xof()
+        .read(buffer)
+}
@@ -54,3 +54,181 @@ fn argon2id_err() {
         true
     );
 }
+
+#[test]
+fn hash_password() {
+    let phc = cck_hash::argon2::hash_password(b"password").unwrap();
+
+    assert_eq!(phc.starts_with("$argon2id$v=19$"), true);
+
+    // random salt
+    assert_ne!(phc, cck_hash::argon2::hash_password(b"password").unwrap());
+}
+
+#[test]
+fn verify_password() {
+    let phc = cck_hash::argon2::hash_password(b"password").unwrap();
+
+    assert_eq!(
+        cck_hash::argon2::verify_password(b"password", &phc).is_ok(),
+        true
+    );
+
+    // error: wrong password
+    assert_eq!(
+        cck_hash::argon2::verify_password(b"passw0rd", &phc).is_err(),
+        true
+    );
+
+    // error: not a PHC string
+    assert_eq!(
+        cck_hash::argon2::verify_password(b"password", "password").is_err(),
+        true
+    );
+}
+
+#[test]
+fn needs_rehash() {
+    let phc = cck_hash::argon2::hash_password(b"password").unwrap();
+
+    assert_eq!(
+        cck_hash::argon2::needs_rehash(&phc, &cck_hash::argon2::Policy::default()).unwrap(),
+        false
+    );
+
+    assert_eq!(
+        cck_hash::argon2::needs_rehash(
+            &phc,
+            &cck_hash::argon2::Policy {
+                params: cck_hash::argon2::Params::Custom {
+                    m_cost: 65536,
+                    t_cost: 3,
+                    p_cost: 1,
+                    output_len: 32,
+                },
+                ..Default::default()
+            }
+        )
+        .unwrap(),
+        true
+    );
+
+    assert_eq!(
+        cck_hash::argon2::needs_rehash(
+            &phc,
+            &cck_hash::argon2::Policy {
+                algorithm: cck_hash::argon2::Algorithm::Argon2i,
+                ..Default::default()
+            }
+        )
+        .unwrap(),
+        true
+    );
+}
+
+#[test]
+fn digest_into() {
+    let argon2 = cck_hash::argon2::argon2_custom(
+        cck_hash::argon2::Algorithm::Argon2id,
+        cck_hash::argon2::Version::V0x13,
+        cck_hash::argon2::Params::Custom {
+            m_cost: 4096,
+            t_cost: 3,
+            p_cost: 1,
+            output_len: 64,
+        },
+    )
+    .unwrap();
+
+    let mut buffer = [0u8; 64];
+
+    cck_hash::argon2::digest_into(&argon2, &[0u8; 32], &[0u8; 32], &mut buffer).unwrap();
+
+    assert_ne!(buffer, [0u8; 64]);
+
+    // error: output length mismatch
+    assert_eq!(
+        cck_hash::argon2::digest(&argon2, &[0u8; 32], &[0u8; 32]).is_err(),
+        true
+    );
+}
+
+#[test]
+fn argon2_with_secret() {
+    let argon2 = cck_hash::argon2::argon2_with_secret(
+        cck_hash::argon2::Algorithm::Argon2id,
+        cck_hash::argon2::Version::V0x13,
+        cck_hash::argon2::Params::Default,
+        b"pepper",
+        b"associated data",
+    )
+    .unwrap();
+
+    assert_ne!(
+        cck_hash::argon2::digest(&argon2, &[0u8; 32], &[0u8; 32]).unwrap(),
+        cck_hash::argon2::digest(&cck_hash::argon2::argon2id(), &[0u8; 32], &[0u8; 32]).unwrap()
+    );
+
+    let phc = cck_hash::argon2::hash_password_with(&argon2, b"password").unwrap();
+
+    assert_eq!(
+        cck_hash::argon2::verify_password_with(&argon2, b"password", &phc).is_ok(),
+        true
+    );
+
+    // error: missing secret
+    assert_eq!(
+        cck_hash::argon2::verify_password(b"password", &phc).is_err(),
+        true
+    );
+
+    // error: associated data size
+    assert_eq!(
+        cck_hash::argon2::argon2_with_secret(
+            cck_hash::argon2::Algorithm::Argon2id,
+            cck_hash::argon2::Version::V0x13,
+            cck_hash::argon2::Params::Default,
+            b"pepper",
+            &[0u8; 33],
+        )
+        .is_err(),
+        true
+    );
+}
+
+#[test]
+fn calibrate() {
+    let params = cck_hash::argon2::calibrate(
+        cck_hash::argon2::Algorithm::Argon2id,
+        core::time::Duration::from_millis(1),
+        4096,
+    )
+    .unwrap();
+
+    assert_eq!(
+        matches!(
+            params,
+            cck_hash::argon2::Params::Custom {
+                m_cost: 4096,
+                p_cost: 1,
+                output_len: 32,
+                ..
+            }
+        ),
+        true
+    );
+}
+
+#[test]
+fn calibrate_max_t_cost() {
+    // not reached with the smallest memory and the maximum iterations
+    assert_eq!(
+        cck_hash::argon2::calibrate(
+            cck_hash::argon2::Algorithm::Argon2id,
+            core::time::Duration::from_secs(3600),
+            8,
+        )
+        .is_err(),
+        true
+    );
+}
@@ -0,0 +1,67 @@
+// e.g.
+// cargo test --package cck-hash --test blake2 --  --nocapture
+// cargo test --package cck-hash --test blake2 -- blake2b_digest --nocapture
+
+#[test]
+fn blake2b_digest() {
+    let hash = cck_hash::blake2::blake2b_digest(&[], &[]);
+
+    assert_eq!(hash.len(), 64);
+
+    assert_eq!(
+        hash,
+        [
+            120, 106, 2, 247, 66, 1, 89, 3, 198, 198, 253, 133, 37, 82, 210, 114, 145, 47, 71, 64,
+            225, 88, 71, 97, 138, 134, 226, 23, 247, 31, 84, 25, 210, 94, 16, 49, 175, 238, 88, 83,
+            19, 137, 100, 68, 147, 78, 176, 75, 144, 58, 104, 91, 20, 72, 183, 85, 213, 111, 112,
+            26, 254, 155, 226, 206
+        ]
+    );
+}
+
+#[test]
+fn blake2s_digest() {
+    let hash = cck_hash::blake2::blake2s_digest(&[], &[]);
+
+    assert_eq!(hash.len(), 32);
+
+    assert_eq!(
+        hash,
+        [
+            105, 33, 122, 48, 121, 144, 128, 148, 225, 17, 33, 208, 66, 53, 74, 124, 31, 85, 182,
+            72, 44, 161, 165, 30, 27, 37, 13, 253, 30, 208, 238, 249
+        ]
+    );
+}
+
+#[test]
+fn blake2b_mac() {
+    let hash = cck_hash::blake2::blake2b_mac(&[0u8; 32], &[], &[]).unwrap();
+
+    assert_eq!(hash.len(), 64);
+
+    assert_eq!(
+        hash,
+        [
+            204, 120, 144, 48, 97, 15, 212, 187, 145, 245, 60, 246, 111, 232, 79, 168, 103, 12,
+            118, 104, 14, 119, 164, 90, 163, 35, 113, 167, 25, 1, 185, 243, 217, 84, 192, 138, 63,
+            150, 222, 194, 244, 72, 94, 0, 95, 183, 75, 27, 205, 213, 75, 191, 255, 5, 129, 166,
+            17, 177, 95, 185, 55, 2, 179, 71
+        ]
+    );
+}
+
+#[test]
+fn blake2b_mac_err() {
+    // error: key size
+    assert_eq!(
+        cck_hash::blake2::blake2b_mac(&[0u8; 65], &[], &[]).is_err(),
+        true
+    );
+
+    // ok: key size ~ 64
+    assert_eq!(
+        cck_hash::blake2::blake2b_mac(&[0u8; 64], &[], &[]).is_ok(),
+        true
+    );
+}
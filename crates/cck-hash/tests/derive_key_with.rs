@@ -0,0 +1,40 @@
+// e.g.
+// cargo test --package cck-hash --test derive_key_with --  --nocapture
+
+#[test]
+fn derive_key_with() {
+    let mut buffer = [0u8; 32];
+
+    cck_hash::derive_key_with(
+        cck_hash::Kdf::Pbkdf2Sha256(cck_hash::pbkdf2::Params::Custom { rounds: 1 }),
+        b"password",
+        b"salt",
+        &mut buffer,
+    )
+    .unwrap();
+
+    let mut expected = [0u8; 32];
+
+    cck_hash::pbkdf2::sha256_derive_key(
+        cck_hash::pbkdf2::Params::Custom { rounds: 1 },
+        b"password",
+        b"salt",
+        &mut expected,
+    )
+    .unwrap();
+
+    assert_eq!(buffer, expected);
+
+    cck_hash::derive_key_with(
+        cck_hash::Kdf::default(),
+        b"password",
+        &[0u8; 32],
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(
+        buffer,
+        cck_hash::argon2::digest(&cck_hash::argon2::argon2id(), b"password", &[0u8; 32]).unwrap()
+    );
+}
@@ -0,0 +1,36 @@
+// e.g.
+// cargo test --package cck-hash --test digest_with --  --nocapture
+
+#[test]
+fn digest_with() {
+    let mut buffer = [0u8; 64];
+
+    assert_eq!(
+        cck_hash::digest_with(cck_hash::Algorithm::Sha256, &[], &[], &mut buffer).unwrap(),
+        cck_hash::sha2::sha256_digest(&[], &[])
+    );
+
+    assert_eq!(
+        cck_hash::digest_with(cck_hash::Algorithm::Sha3_512, &[], &[], &mut buffer).unwrap(),
+        cck_hash::sha3::sha3_512_digest(&[], &[])
+    );
+
+    assert_eq!(
+        cck_hash::digest_with(cck_hash::Algorithm::Blake2s, &[], &[], &mut buffer).unwrap(),
+        cck_hash::blake2::blake2s_digest(&[], &[])
+    );
+
+    assert_eq!(
+        cck_hash::digest_with(cck_hash::Algorithm::default(), &[], &[], &mut buffer).unwrap(),
+        cck_hash::blake3::digest(&[], &[])
+    );
+}
+
+#[test]
+fn digest_with_err() {
+    // error: buffer size
+    assert_eq!(
+        cck_hash::digest_with(cck_hash::Algorithm::Sha512, &[], &[], &mut [0u8; 32]).is_err(),
+        true
+    );
+}
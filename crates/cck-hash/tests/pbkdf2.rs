@@ -0,0 +1,73 @@
+// e.g.
+// cargo test --package cck-hash --test pbkdf2 --  --nocapture
+
+#[test]
+fn pbkdf2_sha256_derive_key() {
+    let mut buffer = [0u8; 32];
+
+    cck_hash::pbkdf2::sha256_derive_key(
+        cck_hash::pbkdf2::Params::Custom { rounds: 1 },
+        b"password",
+        b"salt",
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(
+        buffer,
+        [
+            18, 15, 182, 207, 252, 248, 179, 44, 67, 231, 34, 82, 86, 196, 248, 55, 168, 101, 72,
+            201, 44, 204, 53, 72, 8, 5, 152, 124, 183, 11, 225, 123
+        ]
+    );
+}
+
+#[test]
+fn pbkdf2_sha512_derive_key() {
+    let mut buffer = [0u8; 64];
+
+    cck_hash::pbkdf2::sha512_derive_key(
+        cck_hash::pbkdf2::Params::Custom { rounds: 1 },
+        b"password",
+        b"salt",
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(
+        buffer,
+        [
+            134, 127, 112, 207, 26, 222, 2, 207, 243, 117, 37, 153, 163, 165, 61, 196, 175, 52,
+            199, 166, 105, 129, 90, 229, 213, 19, 85, 78, 28, 140, 242, 82, 192, 45, 71, 10, 40,
+            90, 5, 1, 186, 217, 153, 191, 233, 67, 192, 143, 5, 2, 53, 215, 214, 139, 29, 165, 94,
+            99, 247, 59, 96, 165, 127, 206
+        ]
+    );
+}
+
+#[test]
+fn pbkdf2_derive_key_err() {
+    // error: rounds
+    assert_eq!(
+        cck_hash::pbkdf2::derive_key(
+            cck_hash::pbkdf2::Params::Custom { rounds: 0 },
+            b"password",
+            b"salt",
+            &mut [0u8; 32],
+        )
+        .is_err(),
+        true
+    );
+
+    // error: buffer size
+    assert_eq!(
+        cck_hash::pbkdf2::derive_key(
+            cck_hash::pbkdf2::Params::Custom { rounds: 1 },
+            b"password",
+            b"salt",
+            &mut [],
+        )
+        .is_err(),
+        true
+    );
+}
@@ -0,0 +1,65 @@
+// e.g.
+// cargo test --package cck-hash --test scrypt --  --nocapture
+
+#[test]
+fn scrypt_derive_key() {
+    // RFC 7914
+    let mut buffer = [0u8; 64];
+
+    cck_hash::scrypt::derive_key(
+        cck_hash::scrypt::Params::Custom {
+            log_n: 10,
+            r: 8,
+            p: 16,
+        },
+        b"password",
+        b"NaCl",
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(
+        buffer,
+        [
+            253, 186, 190, 28, 157, 52, 114, 0, 120, 86, 231, 25, 13, 1, 233, 254, 124, 106, 215,
+            203, 200, 35, 120, 48, 231, 115, 118, 99, 75, 55, 49, 98, 46, 175, 48, 217, 46, 34,
+            163, 136, 111, 241, 9, 39, 157, 152, 48, 218, 199, 39, 175, 185, 74, 131, 238, 109,
+            131, 96, 203, 223, 162, 204, 6, 64
+        ]
+    );
+}
+
+#[test]
+fn scrypt_derive_key_err() {
+    // error: r
+    assert_eq!(
+        cck_hash::scrypt::derive_key(
+            cck_hash::scrypt::Params::Custom {
+                log_n: 10,
+                r: 0,
+                p: 1,
+            },
+            b"password",
+            b"NaCl",
+            &mut [0u8; 32],
+        )
+        .is_err(),
+        true
+    );
+
+    // error: buffer size
+    assert_eq!(
+        cck_hash::scrypt::derive_key(
+            cck_hash::scrypt::Params::Custom {
+                log_n: 10,
+                r: 8,
+                p: 1,
+            },
+            b"password",
+            b"NaCl",
+            &mut [],
+        )
+        .is_err(),
+        true
+    );
+}
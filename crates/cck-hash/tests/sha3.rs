@@ -0,0 +1,67 @@
+// e.g.
+// cargo test --package cck-hash --test sha3 --  --nocapture
+// cargo test --package cck-hash --test sha3 -- sha3_256_digest --nocapture
+
+#[test]
+fn sha3_256_digest() {
+    let hash = cck_hash::sha3::sha3_256_digest(&[], &[]);
+
+    assert_eq!(hash.len(), 32);
+
+    assert_eq!(
+        hash,
+        [
+            167, 255, 198, 248, 191, 30, 215, 102, 81, 193, 71, 86, 160, 97, 214, 98, 245, 128,
+            255, 77, 228, 59, 73, 250, 130, 216, 10, 75, 128, 248, 67, 74
+        ]
+    );
+}
+
+#[test]
+fn sha3_512_digest() {
+    let hash = cck_hash::sha3::sha3_512_digest(&[], &[]);
+
+    assert_eq!(hash.len(), 64);
+
+    assert_eq!(
+        hash,
+        [
+            166, 159, 115, 204, 162, 58, 154, 197, 200, 181, 103, 220, 24, 90, 117, 110, 151, 201,
+            130, 22, 79, 226, 88, 89, 224, 209, 220, 193, 71, 92, 128, 166, 21, 178, 18, 58, 241,
+            245, 249, 76, 17, 227, 233, 64, 44, 58, 197, 88, 245, 0, 25, 157, 149, 182, 211, 227,
+            1, 117, 133, 134, 40, 29, 205, 38
+        ]
+    );
+}
+
+#[test]
+fn shake128_digest() {
+    let mut hash = [0u8; 32];
+
+    cck_hash::sha3::shake128_digest(&[], &[], &mut hash);
+
+    assert_eq!(
+        hash,
+        [
+            127, 156, 43, 164, 232, 143, 130, 125, 97, 96, 69, 80, 118, 5, 133, 62, 215, 59, 128,
+            147, 246, 239, 188, 136, 235, 26, 110, 172, 250, 102, 239, 38
+        ]
+    );
+}
+
+#[test]
+fn shake256_digest() {
+    let mut hash = [0u8; 64];
+
+    cck_hash::sha3::shake256_digest(&[], &[], &mut hash);
+
+    assert_eq!(
+        hash,
+        [
+            70, 185, 221, 43, 11, 168, 141, 19, 35, 59, 63, 235, 116, 62, 235, 36, 63, 205, 82,
+            234, 98, 184, 27, 130, 181, 12, 39, 100, 110, 213, 118, 47, 215, 93, 196, 221, 216,
+            192, 242, 0, 203, 5, 1, 157, 103, 181, 146, 246, 252, 130, 28, 73, 71, 154, 180, 134,
+            64, 41, 46, 172, 179, 183, 196, 190
+        ]
+    );
+}
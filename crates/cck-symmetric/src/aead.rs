@@ -8,28 +8,51 @@ use alloc::vec::Vec;
 use aead::{Aead, Payload};
 
 
-use cck_common::{Error, Result};
-use aead::AeadInPlace;
+use cck_common::{size::SIZE_32, Error, Result};
+use aead::{generic_array::typenum::Unsigned, AeadCore, AeadInPlace, Tag};
+use subtle::ConstantTimeEq;
 
 pub use aead::{arrayvec::ArrayVec, Buffer, KeyInit};
 
+/// BLAKE3 derive key context for the key commitment.
+pub const AEAD_COMMITMENT_CONTEXT: &str = "cck-symmetric-aead-commitment";
+
+/// The length of the key commitment prepended by `aead_commit_encrypt_in_place`.
+pub const AEAD_COMMITMENT_SIZE: usize = SIZE_32;
+
+/// Rejects a nonce of the wrong length (instead of panicking in `nonce.into()`).
+fn check_nonce<A: AeadCore>(nonce: &[u8]) -> Result<()> {
+    if nonce.len() != A::NonceSize::USIZE {
+        Err(Error)?
+    }
+
+    Ok(())
+}
+
+/// Key commitment: BLAKE3 derive key over the key, salted with the nonce.
+fn commitment(key: &[u8], nonce: &[u8]) -> [u8; SIZE_32] {
+    cck_hash::blake3::derive_key(AEAD_COMMITMENT_CONTEXT, key, nonce)
+}
+
 /// Encrypts the given message with the given nonce and associated data.
 /// 
 /// # Arguments
 /// 
 /// * `aead` - The AEAD algorithm to use.
 /// 
-/// * `nonce` - The nonce to use.
+/// * `nonce` - The nonce to use. Must be the nonce length of the algorithm.
 /// 
 /// * `associated_data` - The associated data to use.
 /// 
 /// * `buffer` - The message to encrypt.
-pub fn aead_encrypt_in_place(
-    aead: &impl AeadInPlace,
+pub fn aead_encrypt_in_place<A: AeadInPlace>(
+    aead: &A,
     nonce: &[u8],
     associated_data: &[u8],
     buffer: &mut dyn Buffer,
 ) -> Result<()> {
+    check_nonce::<A>(nonce)?;
+
     aead.encrypt_in_place(nonce.into(), associated_data, buffer)
         .map_err(|_| Error)
 }
@@ -40,17 +63,19 @@ pub fn aead_encrypt_in_place(
 /// 
 /// * `aead` - The AEAD algorithm to use.
 /// 
-/// * `nonce` - The nonce to use.
+/// * `nonce` - The nonce to use. Must be the nonce length of the algorithm.
 /// 
 /// * `associated_data` - The associated data to use.
 /// 
 /// * `buffer` - The message(cipher) to decrypt.
-pub fn aead_decrypt_in_place(
-    aead: &impl AeadInPlace,
+pub fn aead_decrypt_in_place<A: AeadInPlace>(
+    aead: &A,
     nonce: &[u8],
     associated_data: &[u8],
     buffer: &mut dyn Buffer,
 ) -> Result<()> {
+    check_nonce::<A>(nonce)?;
+
     aead.decrypt_in_place(nonce.into(), associated_data, buffer)
         .map_err(|_| Error)
 }
@@ -61,7 +86,7 @@ pub fn aead_decrypt_in_place(
 /// 
 /// * `aead` - The AEAD algorithm to use.
 /// 
-/// * `nonce` - The nonce to use.
+/// * `nonce` - The nonce to use. Must be the nonce length of the algorithm.
 /// 
 /// * `associated_data` - The associated data to use.
 /// 
@@ -71,12 +96,14 @@ pub fn aead_decrypt_in_place(
 /// 
 /// The encrypted message.
 #[cfg(feature = "alloc")]
-pub fn aead_encrypt(
-    aead: &impl AeadInPlace,
+pub fn aead_encrypt<A: AeadInPlace>(
+    aead: &A,
     nonce: &[u8],
     associated_data: &[u8],
     message: &[u8],
 ) -> Result<Vec<u8>> {
+    check_nonce::<A>(nonce)?;
+
     aead.encrypt(
         nonce.into(),
         Payload {
@@ -93,7 +120,7 @@ pub fn aead_encrypt(
 /// 
 /// * `aead` - The AEAD algorithm to use.
 /// 
-/// * `nonce` - The nonce to use.
+/// * `nonce` - The nonce to use. Must be the nonce length of the algorithm.
 /// 
 /// * `associated_data` - The associated data to use.
 /// 
@@ -103,12 +130,14 @@ pub fn aead_encrypt(
 /// 
 /// The decrypted message.
 #[cfg(feature = "alloc")]
-pub fn aead_decrypt(
-    aead: &impl AeadInPlace,
+pub fn aead_decrypt<A: AeadInPlace>(
+    aead: &A,
     nonce: &[u8],
     associated_data: &[u8],
     message: &[u8],
 ) -> Result<Vec<u8>> {
+    check_nonce::<A>(nonce)?;
+
     aead.decrypt(
         nonce.into(),
         Payload {
@@ -118,3 +147,144 @@ pub fn aead_decrypt(
     )
     .map_err(|_| Error)
 }
+
+/// Encrypts the given message with the given nonce and associated data, and prepends a key commitment.
+///
+/// Plain AES-GCM and ChaCha20-Poly1305 are not key-committing: a ciphertext can be crafted to decrypt under two different keys.
+/// The 32-byte commitment binds the ciphertext to `key`, so it only decrypts under that key.
+///
+/// The AEAD is created from `key`, so the commitment and the ciphertext always use the same key.
+///
+/// Output: commitment (32 bytes) || ciphertext || tag
+///
+/// # Arguments
+///
+/// * `key` - The key to use.
+///
+/// * `nonce` - The nonce to use.
+///
+/// * `associated_data` - The associated data to use.
+///
+/// * `buffer` - The message to encrypt.
+///
+/// # Example
+/// ```
+/// aead_commit_encrypt_in_place::<XChaCha20Poly1305>(&key, &nonce, &[], &mut buffer).unwrap();
+/// ```
+pub fn aead_commit_encrypt_in_place<A: KeyInit + AeadInPlace>(
+    key: &[u8],
+    nonce: &[u8],
+    associated_data: &[u8],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    let aead = A::new_from_slice(key).map_err(|_| Error)?;
+
+    aead_encrypt_in_place(&aead, nonce, associated_data, buffer)?;
+
+    let len = buffer.len();
+
+    buffer
+        .extend_from_slice(&[0u8; AEAD_COMMITMENT_SIZE])
+        .map_err(|_| Error)?;
+
+    let bytes = buffer.as_mut();
+
+    bytes.copy_within(..len, AEAD_COMMITMENT_SIZE);
+
+    bytes[..AEAD_COMMITMENT_SIZE].copy_from_slice(&commitment(key, nonce));
+
+    Ok(())
+}
+
+/// Verifies the key commitment in constant time, then decrypts the given message with the given nonce and associated data.
+///
+/// The AEAD is created from `key`. If the commitment or the tag does not match, the buffer is left unchanged.
+///
+/// # Arguments
+///
+/// * `key` - The key to use.
+///
+/// * `nonce` - The nonce to use.
+///
+/// * `associated_data` - The associated data to use.
+///
+/// * `buffer` - The message(commitment || cipher) to decrypt.
+pub fn aead_commit_decrypt_in_place<A: KeyInit + AeadInPlace>(
+    key: &[u8],
+    nonce: &[u8],
+    associated_data: &[u8],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    check_nonce::<A>(nonce)?;
+
+    let aead = A::new_from_slice(key).map_err(|_| Error)?;
+
+    let len = buffer
+        .len()
+        .checked_sub(AEAD_COMMITMENT_SIZE + A::TagSize::USIZE)
+        .ok_or(Error)?;
+
+    let bytes = buffer.as_mut();
+
+    if bool::from(!bytes[..AEAD_COMMITMENT_SIZE].ct_eq(&commitment(key, nonce))) {
+        Err(Error)?
+    }
+
+    let (message, tag) = bytes[AEAD_COMMITMENT_SIZE..].split_at_mut(len);
+
+    aead.decrypt_in_place_detached(
+        nonce.into(),
+        associated_data,
+        message,
+        Tag::<A>::from_slice(tag),
+    )
+    .map_err(|_| Error)?;
+
+    bytes.copy_within(AEAD_COMMITMENT_SIZE..AEAD_COMMITMENT_SIZE + len, 0);
+
+    buffer.truncate(len);
+
+    Ok(())
+}
+
+/// Encrypts the given message with the given nonce and associated data, and prepends a key commitment.
+///
+/// See `aead_commit_encrypt_in_place`.
+///
+/// # Returns
+///
+/// The encrypted message(commitment || cipher).
+#[cfg(feature = "alloc")]
+pub fn aead_commit_encrypt<A: KeyInit + AeadInPlace>(
+    key: &[u8],
+    nonce: &[u8],
+    associated_data: &[u8],
+    message: &[u8],
+) -> Result<Vec<u8>> {
+    let mut buffer = message.to_vec();
+
+    aead_commit_encrypt_in_place::<A>(key, nonce, associated_data, &mut buffer)?;
+
+    Ok(buffer)
+}
+
+/// Verifies the key commitment in constant time, then decrypts the given message with the given nonce and associated data.
+///
+/// See `aead_commit_decrypt_in_place`.
+///
+/// # Returns
+///
+/// The decrypted message.
+#[cfg(feature = "alloc")]
+pub fn aead_commit_decrypt<A: KeyInit + AeadInPlace>(
+    key: &[u8],
+    nonce: &[u8],
+    associated_data: &[u8],
+    message: &[u8],
+) -> Result<Vec<u8>> {
+    let mut buffer = message.to_vec();
+
+    aead_commit_decrypt_in_place::<A>(key, nonce, associated_data, &mut buffer)?;
+
+    Ok(buffer)
+}
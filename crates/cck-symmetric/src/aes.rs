@@ -1,8 +1,11 @@
-use aes_gcm::{Aes128Gcm, Aes256Gcm};
+pub use aes_gcm::{Aes128Gcm, Aes256Gcm};
+pub use aes_gcm_siv::{Aes128GcmSiv, Aes256GcmSiv};
 
-use cck_common::size::{SIZE_16, SIZE_24, SIZE_32};
+use cck_common::size::{SIZE_16, SIZE_24, SIZE_32, SIZE_64};
 
-type Aes192Gcm = aes_gcm::AesGcm<aes_gcm::aes::Aes192, aes_gcm::aes::cipher::consts::U12>;
+pub use super::siv::{Aes128Siv, Aes256Siv};
+
+pub type Aes192Gcm = aes_gcm::AesGcm<aes_gcm::aes::Aes192, aes_gcm::aes::cipher::consts::U12>;
 
 use super::aead::KeyInit;
 
@@ -20,3 +23,31 @@ pub fn aes_192_gcm(key: &[u8; SIZE_24]) -> Aes192Gcm {
 pub fn aes_256_gcm(key: &[u8; SIZE_32]) -> Aes256Gcm {
     Aes256Gcm::new_from_slice(key).unwrap()
 }
+
+/// Generate a new aes_128_gcm_siv from the given key.
+///
+/// AES-GCM-SIV (RFC 8452) is nonce-misuse-resistant: reusing a nonce only reveals whether two messages are equal.
+pub fn aes_128_gcm_siv(key: &[u8; SIZE_16]) -> Aes128GcmSiv {
+    Aes128GcmSiv::new_from_slice(key).unwrap()
+}
+
+/// Generate a new aes_256_gcm_siv from the given key.
+///
+/// AES-GCM-SIV (RFC 8452) is nonce-misuse-resistant: reusing a nonce only reveals whether two messages are equal.
+pub fn aes_256_gcm_siv(key: &[u8; SIZE_32]) -> Aes256GcmSiv {
+    Aes256GcmSiv::new_from_slice(key).unwrap()
+}
+
+/// Generate a new aes_128_siv (RFC 5297) from the given key.
+///
+/// The nonce is 16 bytes. For deterministic encryption, use `siv::aes_128_siv_deterministic_encrypt_in_place`.
+pub fn aes_128_siv(key: &[u8; SIZE_32]) -> Aes128Siv {
+    Aes128Siv::new_from_slice(key).unwrap()
+}
+
+/// Generate a new aes_256_siv (RFC 5297) from the given key.
+///
+/// The nonce is 16 bytes. For deterministic encryption, use `siv::aes_256_siv_deterministic_encrypt_in_place`.
+pub fn aes_256_siv(key: &[u8; SIZE_64]) -> Aes256Siv {
+    Aes256Siv::new_from_slice(key).unwrap()
+}
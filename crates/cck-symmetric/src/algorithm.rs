@@ -0,0 +1,125 @@
+use cck_common::{
+    size::{SIZE_12, SIZE_16, SIZE_24, SIZE_32, SIZE_64},
+    Error, Result,
+};
+
+/// The AEAD algorithm.
+///
+/// The id is the first byte of a sealed envelope (see `seal`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Aes128Gcm,
+    Aes192Gcm,
+    Aes256Gcm,
+    ChaCha20Poly1305,
+    XChaCha20Poly1305,
+    Aes128GcmSiv,
+    Aes256GcmSiv,
+    Aes128Siv,
+    Aes256Siv,
+}
+
+impl Algorithm {
+    /// Returns the algorithm id.
+    pub fn id(&self) -> u8 {
+        match self {
+            Algorithm::Aes128Gcm => 0x01,
+            Algorithm::Aes192Gcm => 0x02,
+            Algorithm::Aes256Gcm => 0x03,
+            Algorithm::ChaCha20Poly1305 => 0x04,
+            Algorithm::XChaCha20Poly1305 => 0x05,
+            Algorithm::Aes128GcmSiv => 0x06,
+            Algorithm::Aes256GcmSiv => 0x07,
+            Algorithm::Aes128Siv => 0x08,
+            Algorithm::Aes256Siv => 0x09,
+        }
+    }
+
+    /// Returns the algorithm of the given id.
+    pub fn from_id(id: u8) -> Result<Self> {
+        match id {
+            0x01 => Ok(Algorithm::Aes128Gcm),
+            0x02 => Ok(Algorithm::Aes192Gcm),
+            0x03 => Ok(Algorithm::Aes256Gcm),
+            0x04 => Ok(Algorithm::ChaCha20Poly1305),
+            0x05 => Ok(Algorithm::XChaCha20Poly1305),
+            0x06 => Ok(Algorithm::Aes128GcmSiv),
+            0x07 => Ok(Algorithm::Aes256GcmSiv),
+            0x08 => Ok(Algorithm::Aes128Siv),
+            0x09 => Ok(Algorithm::Aes256Siv),
+            _ => Err(Error),
+        }
+    }
+
+    /// Returns the key length in bytes.
+    pub fn key_len(&self) -> usize {
+        match self {
+            Algorithm::Aes128Gcm => SIZE_16,
+            Algorithm::Aes192Gcm => SIZE_24,
+            Algorithm::Aes256Gcm => SIZE_32,
+            Algorithm::ChaCha20Poly1305 => SIZE_32,
+            Algorithm::XChaCha20Poly1305 => SIZE_32,
+            Algorithm::Aes128GcmSiv => SIZE_16,
+            Algorithm::Aes256GcmSiv => SIZE_32,
+            Algorithm::Aes128Siv => SIZE_32,
+            Algorithm::Aes256Siv => SIZE_64,
+        }
+    }
+
+    /// Returns the nonce length in bytes.
+    pub fn nonce_len(&self) -> usize {
+        match self {
+            Algorithm::XChaCha20Poly1305 => SIZE_24,
+            Algorithm::Aes128Siv | Algorithm::Aes256Siv => SIZE_16,
+            _ => SIZE_12,
+        }
+    }
+}
+
+impl core::default::Default for Algorithm {
+    /// Returns the default algorithm.
+    ///
+    /// Default: `Algorithm::XChaCha20Poly1305` (random nonces are safe at 24 bytes)
+    fn default() -> Self {
+        Self::XChaCha20Poly1305
+    }
+}
+
+impl core::fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Algorithm::Aes128Gcm => f.write_str("AES-128-GCM"),
+            Algorithm::Aes192Gcm => f.write_str("AES-192-GCM"),
+            Algorithm::Aes256Gcm => f.write_str("AES-256-GCM"),
+            Algorithm::ChaCha20Poly1305 => f.write_str("ChaCha20-Poly1305"),
+            Algorithm::XChaCha20Poly1305 => f.write_str("XChaCha20-Poly1305"),
+            Algorithm::Aes128GcmSiv => f.write_str("AES-128-GCM-SIV"),
+            Algorithm::Aes256GcmSiv => f.write_str("AES-256-GCM-SIV"),
+            Algorithm::Aes128Siv => f.write_str("AES-128-SIV"),
+            Algorithm::Aes256Siv => f.write_str("AES-256-SIV"),
+        }
+    }
+}
+
+impl core::str::FromStr for Algorithm {
+    type Err = Error;
+
+    fn from_str(string: &str) -> Result<Self> {
+        match string {
+            string if string.eq_ignore_ascii_case("AES-128-GCM") => Ok(Algorithm::Aes128Gcm),
+            string if string.eq_ignore_ascii_case("AES-192-GCM") => Ok(Algorithm::Aes192Gcm),
+            string if string.eq_ignore_ascii_case("AES-256-GCM") => Ok(Algorithm::Aes256Gcm),
+            string if string.eq_ignore_ascii_case("ChaCha20-Poly1305") => {
+                Ok(Algorithm::ChaCha20Poly1305)
+            }
+            string if string.eq_ignore_ascii_case("XChaCha20-Poly1305") => {
+                Ok(Algorithm::XChaCha20Poly1305)
+            }
+            string if string.eq_ignore_ascii_case("AES-128-GCM-SIV") => Ok(Algorithm::Aes128GcmSiv),
+            string if string.eq_ignore_ascii_case("AES-256-GCM-SIV") => Ok(Algorithm::Aes256GcmSiv),
+            string if string.eq_ignore_ascii_case("AES-128-SIV") => Ok(Algorithm::Aes128Siv),
+            string if string.eq_ignore_ascii_case("AES-256-SIV") => Ok(Algorithm::Aes256Siv),
+            _ => Err(Error),
+        }
+    }
+}
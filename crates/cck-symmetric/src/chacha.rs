@@ -1,5 +1,5 @@
 use cck_common::size::SIZE_32;
-use chacha20poly1305::{ChaCha20Poly1305, XChaCha20Poly1305};
+pub use chacha20poly1305::{ChaCha20Poly1305, XChaCha20Poly1305};
 
 use super::aead::KeyInit;
 
@@ -0,0 +1,438 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use super::{Algorithm, SymmetricKey, SYMMETRIC_KEY_ENCODED_SIZE};
+use cck_asymmetric::x25519;
+use cck_common::{size::SIZE_32, Error, Result};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+/// BLAKE3 derive key context for the X25519 key-encryption key.
+pub const ENVELOPE_X25519_KEK_CONTEXT: &str = "cck-symmetric-envelope-x25519-kek";
+
+/// The length of the wrapped key length prefix in an envelope-encrypted object.
+pub const ENVELOPE_HEADER_LEN_SIZE: usize = 4;
+
+/// The algorithm of the data keys and of the X25519 key-encryption keys.
+pub const ENVELOPE_ALGORITHM: Algorithm = Algorithm::XChaCha20Poly1305;
+
+/// Key-encryption key, used to wrap a data key.
+#[derive(Debug, Clone, Copy)]
+pub enum WrappingKey<'a> {
+    /// A symmetric key, e.g. stored in the keyring.
+    Symmetric(&'a SymmetricKey),
+    /// The X25519 public key of the recipient.
+    X25519(&'a [u8; SIZE_32]),
+}
+
+/// Key-encryption key, used to unwrap a data key.
+#[derive(Clone, Copy)]
+pub enum UnwrappingKey<'a> {
+    /// A symmetric key, e.g. stored in the keyring.
+    Symmetric(&'a SymmetricKey),
+    /// The X25519 private key of the recipient.
+    X25519(&'a [u8; SIZE_32]),
+}
+
+impl core::fmt::Debug for UnwrappingKey<'_> {
+    /// The key bytes are not printed.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Symmetric(key) => f.debug_tuple("Symmetric").field(key).finish(),
+            Self::X25519(_) => f.debug_tuple("X25519").finish_non_exhaustive(),
+        }
+    }
+}
+
+/// The kind of key-encryption key of a wrapped key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapKind {
+    Symmetric = 0x01,
+    X25519 = 0x02,
+}
+
+impl WrapKind {
+    fn from_id(id: u8) -> Result<Self> {
+        match id {
+            0x01 => Ok(Self::Symmetric),
+            0x02 => Ok(Self::X25519),
+            _ => Err(Error),
+        }
+    }
+}
+
+/// WrappedKey
+///
+/// A data key sealed with a key-encryption key (KEK).
+///
+/// Header: kind (1 byte) || KEK id (32 bytes) || ephemeral public key (32 bytes, X25519 only)
+///
+/// Encoded: header || sealed data key (see `seal`)
+///
+/// The header is authenticated as the associated data of the sealed data key.
+///
+/// The KEK id is `SymmetricKey::id` for a symmetric KEK, and the BLAKE3 fingerprint of the recipient public key for X25519.
+///
+/// # Example
+/// ```
+/// let kek = SymmetricKey::generate(Algorithm::XChaCha20Poly1305);
+///
+/// let data_key = SymmetricKey::generate(Algorithm::XChaCha20Poly1305);
+///
+/// let wrapped = wrap_key(WrappingKey::Symmetric(&kek), &data_key).unwrap();
+///
+/// let unwrapped = unwrap_key(UnwrappingKey::Symmetric(&kek), &wrapped).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrappedKey {
+    kind: WrapKind,
+    kek_id: [u8; SIZE_32],
+    ephemeral_public_key: Option<[u8; SIZE_32]>,
+    sealed_key: Vec<u8>,
+}
+
+impl WrappedKey {
+    /// Returns the kind of key-encryption key.
+    pub fn kind(&self) -> WrapKind {
+        self.kind
+    }
+
+    /// Returns the id of the key-encryption key.
+    ///
+    /// Use it to look up the KEK, e.g. `Ring::get_symmetric_key_where_id_matches`.
+    pub fn kek_id(&self) -> &[u8; SIZE_32] {
+        &self.kek_id
+    }
+
+    /// Encode the wrapped key: header || sealed data key.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = self.header();
+
+        bytes.extend_from_slice(&self.sealed_key);
+
+        bytes
+    }
+
+    /// Decode a wrapped key: header || sealed data key.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let (&id, rest) = bytes.split_first().ok_or(Error)?;
+
+        let kind = WrapKind::from_id(id)?;
+
+        if rest.len() < SIZE_32 {
+            Err(Error)?
+        }
+
+        let (kek_id, rest) = rest.split_at(SIZE_32);
+
+        let (ephemeral_public_key, sealed_key) = match kind {
+            WrapKind::Symmetric => (None, rest),
+            WrapKind::X25519 => {
+                if rest.len() < SIZE_32 {
+                    Err(Error)?
+                }
+
+                let (public_key, rest) = rest.split_at(SIZE_32);
+
+                (Some(public_key.try_into().map_err(|_| Error)?), rest)
+            }
+        };
+
+        Ok(Self {
+            kind,
+            kek_id: kek_id.try_into().map_err(|_| Error)?,
+            ephemeral_public_key,
+            sealed_key: sealed_key.to_vec(),
+        })
+    }
+
+    /// kind || KEK id || ephemeral public key
+    fn header(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + SIZE_32 * 2 + self.sealed_key.len());
+
+        bytes.push(self.kind as u8);
+
+        bytes.extend_from_slice(&self.kek_id);
+
+        if let Some(public_key) = &self.ephemeral_public_key {
+            bytes.extend_from_slice(public_key);
+        }
+
+        bytes
+    }
+}
+
+/// Wraps the given data key with the given key-encryption key.
+///
+/// With `WrappingKey::X25519`, a fresh ephemeral key pair is generated, and the KEK is derived from the X25519 shared secret.
+///
+/// # Arguments
+///
+/// * `kek` - The key-encryption key.
+///
+/// * `data_key` - The data key to wrap.
+pub fn wrap_key(kek: WrappingKey, data_key: &SymmetricKey) -> Result<WrappedKey> {
+    let mut wrapped = match kek {
+        WrappingKey::Symmetric(kek) => WrappedKey {
+            kind: WrapKind::Symmetric,
+            kek_id: kek.id(),
+            ephemeral_public_key: None,
+            sealed_key: Vec::new(),
+        },
+        WrappingKey::X25519(public_key) => WrappedKey {
+            kind: WrapKind::X25519,
+            kek_id: cck_hash::blake3::digest(public_key, &[]),
+            ephemeral_public_key: None,
+            sealed_key: Vec::new(),
+        },
+    };
+
+    let kek = match kek {
+        WrappingKey::Symmetric(kek) => kek.clone(),
+        WrappingKey::X25519(public_key) => {
+            let mut ephemeral_private_key = x25519::gen_private_key();
+
+            let ephemeral_public_key = x25519::gen_public_key(&ephemeral_private_key);
+
+            let kek = x25519_kek(
+                &ephemeral_private_key,
+                public_key,
+                &ephemeral_public_key,
+                public_key,
+            );
+
+            ephemeral_private_key.zeroize();
+
+            wrapped.ephemeral_public_key = Some(ephemeral_public_key);
+
+            kek?
+        }
+    };
+
+    let mut encoded = [0u8; SYMMETRIC_KEY_ENCODED_SIZE];
+
+    let sealed_key = kek.seal(&wrapped.header(), data_key.encode(&mut encoded));
+
+    encoded.zeroize();
+
+    wrapped.sealed_key = sealed_key?;
+
+    Ok(wrapped)
+}
+
+/// Unwraps the given wrapped key with the given key-encryption key.
+///
+/// # Errors
+/// If the KEK does not match the KEK id of the wrapped key, or authentication fails.
+///
+/// # Arguments
+///
+/// * `kek` - The key-encryption key.
+///
+/// * `wrapped` - The wrapped data key.
+pub fn unwrap_key(kek: UnwrappingKey, wrapped: &WrappedKey) -> Result<SymmetricKey> {
+    let kek = match (kek, wrapped.kind) {
+        (UnwrappingKey::Symmetric(kek), WrapKind::Symmetric) => {
+            if !bool::from(kek.id().ct_eq(&wrapped.kek_id)) {
+                Err(Error)?
+            }
+
+            kek.clone()
+        }
+        (UnwrappingKey::X25519(private_key), WrapKind::X25519) => {
+            let public_key = x25519::gen_public_key(private_key);
+
+            if !bool::from(cck_hash::blake3::digest(&public_key, &[]).ct_eq(&wrapped.kek_id)) {
+                Err(Error)?
+            }
+
+            let ephemeral_public_key = wrapped.ephemeral_public_key.as_ref().ok_or(Error)?;
+
+            x25519_kek(
+                private_key,
+                ephemeral_public_key,
+                ephemeral_public_key,
+                &public_key,
+            )?
+        }
+        _ => Err(Error)?,
+    };
+
+    let mut encoded = kek.open(&wrapped.header(), &wrapped.sealed_key)?;
+
+    let data_key = SymmetricKey::decode(&encoded);
+
+    encoded.zeroize();
+
+    data_key
+}
+
+/// Rewraps the given wrapped key: unwraps with the old KEK and wraps with the new KEK.
+///
+/// # Arguments
+///
+/// * `old_kek` - The current key-encryption key.
+///
+/// * `new_kek` - The new key-encryption key.
+///
+/// * `wrapped` - The wrapped data key.
+pub fn rewrap_key(
+    old_kek: UnwrappingKey,
+    new_kek: WrappingKey,
+    wrapped: &WrappedKey,
+) -> Result<WrappedKey> {
+    wrap_key(new_kek, &unwrap_key(old_kek, wrapped)?)
+}
+
+/// Envelope encryption
+///
+/// Encrypts the given message with a fresh data key, and wraps the data key with the given key-encryption key.
+///
+/// Output: wrapped key length (4 bytes, big-endian) || wrapped key || payload (see `seal`)
+///
+/// The payload does not depend on the KEK, so `envelope_rewrap` can rotate the KEK without touching it.
+///
+/// # Arguments
+///
+/// * `kek` - The key-encryption key.
+///
+/// * `associated_data` - The associated data of the payload.
+///
+/// * `message` - The message to encrypt.
+///
+/// # Example
+/// ```
+/// let kek = SymmetricKey::generate(Algorithm::XChaCha20Poly1305);
+///
+/// let object = envelope_encrypt(WrappingKey::Symmetric(&kek), &[], b"hello").unwrap();
+///
+/// let message = envelope_decrypt(UnwrappingKey::Symmetric(&kek), &[], &object).unwrap();
+/// ```
+pub fn envelope_encrypt(
+    kek: WrappingKey,
+    associated_data: &[u8],
+    message: &[u8],
+) -> Result<Vec<u8>> {
+    let data_key = SymmetricKey::generate(ENVELOPE_ALGORITHM);
+
+    let wrapped = wrap_key(kek, &data_key)?;
+
+    let payload = data_key.seal(associated_data, message)?;
+
+    Ok(join(&wrapped, &payload))
+}
+
+/// Envelope decryption
+///
+/// Unwraps the data key with the given key-encryption key, and decrypts the payload.
+///
+/// # Arguments
+///
+/// * `kek` - The key-encryption key.
+///
+/// * `associated_data` - The associated data of the payload.
+///
+/// * `object` - The envelope-encrypted object.
+pub fn envelope_decrypt(
+    kek: UnwrappingKey,
+    associated_data: &[u8],
+    object: &[u8],
+) -> Result<Vec<u8>> {
+    let (wrapped, payload) = split(object)?;
+
+    unwrap_key(kek, &wrapped)?.open(associated_data, payload)
+}
+
+/// Rotates the key-encryption key of the given envelope-encrypted object.
+///
+/// Only the wrapped key is replaced. The payload bytes are copied as they are.
+///
+/// # Arguments
+///
+/// * `old_kek` - The current key-encryption key.
+///
+/// * `new_kek` - The new key-encryption key.
+///
+/// * `object` - The envelope-encrypted object.
+pub fn envelope_rewrap(
+    old_kek: UnwrappingKey,
+    new_kek: WrappingKey,
+    object: &[u8],
+) -> Result<Vec<u8>> {
+    let (wrapped, payload) = split(object)?;
+
+    Ok(join(&rewrap_key(old_kek, new_kek, &wrapped)?, payload))
+}
+
+/// Returns the wrapped key of the given envelope-encrypted object.
+///
+/// Use `WrappedKey::kek_id` to find the key-encryption key.
+pub fn envelope_wrapped_key(object: &[u8]) -> Result<WrappedKey> {
+    Ok(split(object)?.0)
+}
+
+fn join(wrapped: &WrappedKey, payload: &[u8]) -> Vec<u8> {
+    let wrapped = wrapped.encode();
+
+    let mut object = Vec::with_capacity(ENVELOPE_HEADER_LEN_SIZE + wrapped.len() + payload.len());
+
+    object.extend_from_slice(&(wrapped.len() as u32).to_be_bytes());
+
+    object.extend_from_slice(&wrapped);
+
+    object.extend_from_slice(payload);
+
+    object
+}
+
+fn split(object: &[u8]) -> Result<(WrappedKey, &[u8])> {
+    if object.len() < ENVELOPE_HEADER_LEN_SIZE {
+        Err(Error)?
+    }
+
+    let (len, rest) = object.split_at(ENVELOPE_HEADER_LEN_SIZE);
+
+    let len = u32::from_be_bytes(len.try_into().map_err(|_| Error)?) as usize;
+
+    if rest.len() < len {
+        Err(Error)?
+    }
+
+    let (wrapped, payload) = rest.split_at(len);
+
+    Ok((WrappedKey::decode(wrapped)?, payload))
+}
+
+/// Derives the X25519 key-encryption key.
+///
+/// BLAKE3 derive key over the shared secret, salted with: ephemeral public key || recipient public key.
+fn x25519_kek(
+    private_key: &[u8; SIZE_32],
+    their_public_key: &[u8; SIZE_32],
+    ephemeral_public_key: &[u8; SIZE_32],
+    recipient_public_key: &[u8; SIZE_32],
+) -> Result<SymmetricKey> {
+    let mut secret = x25519::diffie_hellman(private_key, their_public_key);
+
+    // Reject low-order public keys.
+    if bool::from(secret.ct_eq(&[0u8; SIZE_32])) {
+        Err(Error)?
+    }
+
+    let mut salt = [0u8; SIZE_32 * 2];
+
+    salt[..SIZE_32].copy_from_slice(ephemeral_public_key);
+
+    salt[SIZE_32..].copy_from_slice(recipient_public_key);
+
+    let mut key = cck_hash::blake3::derive_key(ENVELOPE_X25519_KEK_CONTEXT, &secret, &salt);
+
+    secret.zeroize();
+
+    let kek = SymmetricKey::from_bytes(ENVELOPE_ALGORITHM, &key);
+
+    key.zeroize();
+
+    kek
+}
@@ -0,0 +1,417 @@
+use aead::Buffer;
+use aes::cipher::consts::U16;
+use aes::cipher::{
+    generic_array::GenericArray, BlockCipher, BlockDecrypt, BlockDecryptMut, BlockEncrypt,
+    BlockEncryptMut, BlockSizeUser, KeyInit, KeyIvInit, StreamCipher, StreamCipherSeek,
+};
+use cck_common::{
+    size::{SIZE_12, SIZE_16, SIZE_24, SIZE_32},
+    Error, Result,
+};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use subtle::{ConstantTimeEq, ConstantTimeGreater, ConstantTimeLess};
+use zeroize::Zeroize;
+
+/// The block size of AES in bytes.
+pub const AES_BLOCK_SIZE: usize = SIZE_16;
+
+/// The size of a ChaCha20 block in bytes.
+pub const CHACHA20_BLOCK_SIZE: usize = 64;
+
+/// The size of the HMAC-SHA256 tag appended by `aes_*_cbc_hmac_sha256_encrypt_in_place`.
+pub const CBC_HMAC_SHA256_TAG_SIZE: usize = SIZE_32;
+
+/// AES-128-CTR
+///
+/// Applies the keystream to the buffer: encryption and decryption are the same operation.
+///
+/// The IV is the initial 128-bit big-endian counter block.
+///
+/// # Example
+/// ```
+/// let mut buffer = *b"hello";
+///
+/// aes_128_ctr_apply_keystream(&[0u8; 16], &[0u8; 16], &mut buffer);
+/// ```
+pub fn aes_128_ctr_apply_keystream(key: &[u8; SIZE_16], iv: &[u8; SIZE_16], buffer: &mut [u8]) {
+    ctr_apply_keystream::<aes::Aes128>(key, iv, buffer)
+}
+
+/// AES-192-CTR
+///
+/// Applies the keystream to the buffer: encryption and decryption are the same operation.
+pub fn aes_192_ctr_apply_keystream(key: &[u8; SIZE_24], iv: &[u8; SIZE_16], buffer: &mut [u8]) {
+    ctr_apply_keystream::<aes::Aes192>(key, iv, buffer)
+}
+
+/// AES-256-CTR
+///
+/// Applies the keystream to the buffer: encryption and decryption are the same operation.
+pub fn aes_256_ctr_apply_keystream(key: &[u8; SIZE_32], iv: &[u8; SIZE_16], buffer: &mut [u8]) {
+    ctr_apply_keystream::<aes::Aes256>(key, iv, buffer)
+}
+
+/// AES-128-CBC with PKCS#7 padding
+///
+/// Pads the buffer and encrypts it in place.
+///
+/// # Example
+/// ```
+/// let mut buffer = aead::arrayvec::ArrayVec::<u8, 32>::new();
+///
+/// aes_128_cbc_encrypt_in_place(&[0u8; 16], &[0u8; 16], &mut buffer).unwrap();
+/// ```
+pub fn aes_128_cbc_encrypt_in_place(
+    key: &[u8; SIZE_16],
+    iv: &[u8; SIZE_16],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    cbc_encrypt_in_place::<aes::Aes128>(key, iv, buffer)
+}
+
+/// AES-128-CBC with PKCS#7 padding
+///
+/// Decrypts the buffer in place and removes the padding.
+///
+/// # Errors
+/// If the length is not a positive multiple of 16, or the padding is invalid.
+///
+/// The padding is checked in constant time, and every failure returns the same error with the buffer cleared.
+/// This alone does not prevent a padding oracle: prefer `aes_128_cbc_hmac_sha256_decrypt_in_place`, which verifies
+/// a MAC over the IV and cipher before decrypting.
+pub fn aes_128_cbc_decrypt_in_place(
+    key: &[u8; SIZE_16],
+    iv: &[u8; SIZE_16],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    cbc_decrypt_in_place::<aes::Aes128>(key, iv, buffer)
+}
+
+/// AES-192-CBC with PKCS#7 padding
+///
+/// Pads the buffer and encrypts it in place.
+pub fn aes_192_cbc_encrypt_in_place(
+    key: &[u8; SIZE_24],
+    iv: &[u8; SIZE_16],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    cbc_encrypt_in_place::<aes::Aes192>(key, iv, buffer)
+}
+
+/// AES-192-CBC with PKCS#7 padding
+///
+/// Decrypts the buffer in place and removes the padding.
+pub fn aes_192_cbc_decrypt_in_place(
+    key: &[u8; SIZE_24],
+    iv: &[u8; SIZE_16],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    cbc_decrypt_in_place::<aes::Aes192>(key, iv, buffer)
+}
+
+/// AES-256-CBC with PKCS#7 padding
+///
+/// Pads the buffer and encrypts it in place.
+pub fn aes_256_cbc_encrypt_in_place(
+    key: &[u8; SIZE_32],
+    iv: &[u8; SIZE_16],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    cbc_encrypt_in_place::<aes::Aes256>(key, iv, buffer)
+}
+
+/// AES-256-CBC with PKCS#7 padding
+///
+/// Decrypts the buffer in place and removes the padding.
+pub fn aes_256_cbc_decrypt_in_place(
+    key: &[u8; SIZE_32],
+    iv: &[u8; SIZE_16],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    cbc_decrypt_in_place::<aes::Aes256>(key, iv, buffer)
+}
+
+/// AES-128-CBC with PKCS#7 padding, then HMAC-SHA256 (encrypt-then-MAC)
+///
+/// Pads and encrypts the buffer in place, then appends an HMAC-SHA256 tag over the IV and cipher.
+///
+/// Output: cipher || tag (32 bytes)
+///
+/// # Arguments
+///
+/// * `key` - The AES key to use.
+///
+/// * `mac_key` - The HMAC key to use. Must be independent of `key`.
+///
+/// * `iv` - The IV to use.
+///
+/// * `buffer` - The message to encrypt.
+///
+/// # Example
+/// ```
+/// let mut buffer = aead::arrayvec::ArrayVec::<u8, 64>::new();
+///
+/// aes_128_cbc_hmac_sha256_encrypt_in_place(&[0u8; 16], &[1u8; 32], &[0u8; 16], &mut buffer).unwrap();
+/// ```
+pub fn aes_128_cbc_hmac_sha256_encrypt_in_place(
+    key: &[u8; SIZE_16],
+    mac_key: &[u8; SIZE_32],
+    iv: &[u8; SIZE_16],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    cbc_hmac_encrypt_in_place::<aes::Aes128>(key, mac_key, iv, buffer)
+}
+
+/// AES-128-CBC with PKCS#7 padding, then HMAC-SHA256 (encrypt-then-MAC)
+///
+/// Verifies the HMAC-SHA256 tag over the IV and cipher in constant time, then decrypts the buffer in place and
+/// removes the padding. A modified cipher is rejected before it is decrypted, so the padding check can't be
+/// used as an oracle.
+///
+/// # Errors
+/// If the tag does not match (the buffer is left unchanged), or the padding is invalid.
+pub fn aes_128_cbc_hmac_sha256_decrypt_in_place(
+    key: &[u8; SIZE_16],
+    mac_key: &[u8; SIZE_32],
+    iv: &[u8; SIZE_16],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    cbc_hmac_decrypt_in_place::<aes::Aes128>(key, mac_key, iv, buffer)
+}
+
+/// AES-192-CBC with PKCS#7 padding, then HMAC-SHA256 (encrypt-then-MAC)
+///
+/// See `aes_128_cbc_hmac_sha256_encrypt_in_place`.
+pub fn aes_192_cbc_hmac_sha256_encrypt_in_place(
+    key: &[u8; SIZE_24],
+    mac_key: &[u8; SIZE_32],
+    iv: &[u8; SIZE_16],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    cbc_hmac_encrypt_in_place::<aes::Aes192>(key, mac_key, iv, buffer)
+}
+
+/// AES-192-CBC with PKCS#7 padding, then HMAC-SHA256 (encrypt-then-MAC)
+///
+/// See `aes_128_cbc_hmac_sha256_decrypt_in_place`.
+pub fn aes_192_cbc_hmac_sha256_decrypt_in_place(
+    key: &[u8; SIZE_24],
+    mac_key: &[u8; SIZE_32],
+    iv: &[u8; SIZE_16],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    cbc_hmac_decrypt_in_place::<aes::Aes192>(key, mac_key, iv, buffer)
+}
+
+/// AES-256-CBC with PKCS#7 padding, then HMAC-SHA256 (encrypt-then-MAC)
+///
+/// See `aes_128_cbc_hmac_sha256_encrypt_in_place`.
+pub fn aes_256_cbc_hmac_sha256_encrypt_in_place(
+    key: &[u8; SIZE_32],
+    mac_key: &[u8; SIZE_32],
+    iv: &[u8; SIZE_16],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    cbc_hmac_encrypt_in_place::<aes::Aes256>(key, mac_key, iv, buffer)
+}
+
+/// AES-256-CBC with PKCS#7 padding, then HMAC-SHA256 (encrypt-then-MAC)
+///
+/// See `aes_128_cbc_hmac_sha256_decrypt_in_place`.
+pub fn aes_256_cbc_hmac_sha256_decrypt_in_place(
+    key: &[u8; SIZE_32],
+    mac_key: &[u8; SIZE_32],
+    iv: &[u8; SIZE_16],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    cbc_hmac_decrypt_in_place::<aes::Aes256>(key, mac_key, iv, buffer)
+}
+
+/// ChaCha20 (RFC 8439)
+///
+/// Applies the keystream, starting at the given block counter, to the buffer.
+///
+/// # Arguments
+///
+/// * `key` - The key to use.
+///
+/// * `nonce` - The nonce to use.
+///
+/// * `counter` - The initial block counter (RFC 8439 uses 1 for encryption, 0 is the Poly1305 key block).
+///
+/// * `buffer` - The buffer to encrypt or decrypt.
+///
+/// # Errors
+/// If the keystream runs past the last block counter (2^32 blocks). The buffer is left unchanged.
+///
+/// # Example
+/// ```
+/// let mut buffer = *b"hello";
+///
+/// chacha20_apply_keystream(&[0u8; 32], &[0u8; 12], 1, &mut buffer).unwrap();
+/// ```
+pub fn chacha20_apply_keystream(
+    key: &[u8; SIZE_32],
+    nonce: &[u8; SIZE_12],
+    counter: u32,
+    buffer: &mut [u8],
+) -> Result<()> {
+    let mut cipher = chacha20::ChaCha20::new(key.into(), nonce.into());
+
+    cipher
+        .try_seek(counter as u64 * CHACHA20_BLOCK_SIZE as u64)
+        .map_err(|_| Error)?;
+
+    cipher.try_apply_keystream(buffer).map_err(|_| Error)
+}
+
+/// XChaCha20
+///
+/// Applies the keystream, starting at the given block counter, to the buffer.
+///
+/// # Errors
+/// If the keystream runs past the last block counter (2^32 blocks). The buffer is left unchanged.
+pub fn xchacha20_apply_keystream(
+    key: &[u8; SIZE_32],
+    nonce: &[u8; SIZE_24],
+    counter: u32,
+    buffer: &mut [u8],
+) -> Result<()> {
+    let mut cipher = chacha20::XChaCha20::new(key.into(), nonce.into());
+
+    cipher
+        .try_seek(counter as u64 * CHACHA20_BLOCK_SIZE as u64)
+        .map_err(|_| Error)?;
+
+    cipher.try_apply_keystream(buffer).map_err(|_| Error)
+}
+
+fn ctr_apply_keystream<C>(key: &[u8], iv: &[u8; SIZE_16], buffer: &mut [u8])
+where
+    C: BlockCipher + BlockSizeUser<BlockSize = U16> + BlockEncrypt + KeyInit,
+{
+    ctr::Ctr128BE::<C>::new(GenericArray::from_slice(key), iv.into()).apply_keystream(buffer);
+}
+
+/// CBC encryption with PKCS#7 padding.
+fn cbc_encrypt_in_place<C>(key: &[u8], iv: &[u8; SIZE_16], buffer: &mut dyn Buffer) -> Result<()>
+where
+    C: BlockCipher + BlockSizeUser<BlockSize = U16> + BlockEncrypt + KeyInit,
+{
+    let pad = AES_BLOCK_SIZE - buffer.len() % AES_BLOCK_SIZE;
+
+    buffer
+        .extend_from_slice(&[pad as u8; AES_BLOCK_SIZE][..pad])
+        .map_err(|_| Error)?;
+
+    let mut cipher = cbc::Encryptor::<C>::new(GenericArray::from_slice(key), iv.into());
+
+    for block in buffer.as_mut().chunks_exact_mut(AES_BLOCK_SIZE) {
+        cipher.encrypt_block_mut(GenericArray::from_mut_slice(block));
+    }
+
+    Ok(())
+}
+
+/// CBC encryption with PKCS#7 padding, then an HMAC-SHA256 tag over the IV and cipher.
+fn cbc_hmac_encrypt_in_place<C>(
+    key: &[u8],
+    mac_key: &[u8; SIZE_32],
+    iv: &[u8; SIZE_16],
+    buffer: &mut dyn Buffer,
+) -> Result<()>
+where
+    C: BlockCipher + BlockSizeUser<BlockSize = U16> + BlockEncrypt + KeyInit,
+{
+    cbc_encrypt_in_place::<C>(key, iv, buffer)?;
+
+    let tag = cbc_hmac(mac_key, iv, buffer.as_ref())?.finalize().into_bytes();
+
+    buffer.extend_from_slice(&tag).map_err(|_| Error)
+}
+
+/// Verification of the HMAC-SHA256 tag over the IV and cipher, then CBC decryption with PKCS#7 padding.
+fn cbc_hmac_decrypt_in_place<C>(
+    key: &[u8],
+    mac_key: &[u8; SIZE_32],
+    iv: &[u8; SIZE_16],
+    buffer: &mut dyn Buffer,
+) -> Result<()>
+where
+    C: BlockCipher + BlockSizeUser<BlockSize = U16> + BlockDecrypt + KeyInit,
+{
+    let len = buffer
+        .len()
+        .checked_sub(CBC_HMAC_SHA256_TAG_SIZE)
+        .ok_or(Error)?;
+
+    let (cipher, tag) = buffer.as_ref().split_at(len);
+
+    // constant time
+    cbc_hmac(mac_key, iv, cipher)?
+        .verify_slice(tag)
+        .map_err(|_| Error)?;
+
+    buffer.truncate(len);
+
+    cbc_decrypt_in_place::<C>(key, iv, buffer)
+}
+
+/// HMAC-SHA256 over the IV and cipher.
+fn cbc_hmac(mac_key: &[u8; SIZE_32], iv: &[u8; SIZE_16], cipher: &[u8]) -> Result<Hmac<Sha256>> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(mac_key).map_err(|_| Error)?;
+
+    mac.update(iv);
+
+    mac.update(cipher);
+
+    Ok(mac)
+}
+
+/// CBC decryption with PKCS#7 padding.
+///
+/// No partial plaintext is released on failure.
+fn cbc_decrypt_in_place<C>(key: &[u8], iv: &[u8; SIZE_16], buffer: &mut dyn Buffer) -> Result<()>
+where
+    C: BlockCipher + BlockSizeUser<BlockSize = U16> + BlockDecrypt + KeyInit,
+{
+    let len = buffer.len();
+
+    if len == 0 || !len.is_multiple_of(AES_BLOCK_SIZE) {
+        Err(Error)?
+    }
+
+    let mut cipher = cbc::Decryptor::<C>::new(GenericArray::from_slice(key), iv.into());
+
+    for block in buffer.as_mut().chunks_exact_mut(AES_BLOCK_SIZE) {
+        cipher.decrypt_block_mut(GenericArray::from_mut_slice(block));
+    }
+
+    let bytes = buffer.as_mut();
+
+    let last = &bytes[len - AES_BLOCK_SIZE..];
+
+    let pad = last[AES_BLOCK_SIZE - 1];
+
+    // 1 <= pad <= 16
+    let mut valid = pad.ct_gt(&0) & pad.ct_lt(&(AES_BLOCK_SIZE as u8 + 1));
+
+    for (i, byte) in last.iter().rev().enumerate() {
+        let in_padding = (i as u8).ct_lt(&pad);
+
+        valid &= !in_padding | byte.ct_eq(&pad);
+    }
+
+    if bool::from(valid) {
+        buffer.truncate(len - pad as usize);
+
+        Ok(())
+    } else {
+        bytes.zeroize();
+
+        buffer.truncate(0);
+
+        Err(Error)
+    }
+}
@@ -0,0 +1,207 @@
+use cck_common::{
+    size::{SIZE_32, SIZE_64},
+    Error, Result,
+};
+use cck_format::{base64ct, pem};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::Algorithm;
+
+/// BLAKE3 derive key context for the key id.
+pub const SYMMETRIC_KEY_ID_CONTEXT: &str = "cck-symmetric-key-id";
+
+/// The maximum length of an encoded symmetric key: algorithm id (1 byte) || key.
+pub const SYMMETRIC_KEY_ENCODED_SIZE: usize = 1 + SIZE_64;
+
+/// SymmetricKey
+///
+/// A symmetric key tagged with its AEAD algorithm.
+///
+/// The key bytes are zeroized on drop.
+///
+/// # Example
+/// ```
+/// let key = SymmetricKey::generate(Algorithm::XChaCha20Poly1305);
+///
+/// let id = key.id();
+///
+/// let pem = key.to_pem(&mut [0u8; 1024]).unwrap();
+/// ```
+#[derive(Clone)]
+pub struct SymmetricKey {
+    algorithm: Algorithm,
+    key: [u8; SIZE_64],
+}
+
+impl SymmetricKey {
+    /// Generate a new random key for the given algorithm.
+    pub fn generate(algorithm: Algorithm) -> Self {
+        let mut key = [0u8; SIZE_64];
+
+        key[..SIZE_32].copy_from_slice(&cck_rand::gen_32());
+
+        key[SIZE_32..].copy_from_slice(&cck_rand::gen_32());
+
+        key[algorithm.key_len()..].fill(0);
+
+        Self { algorithm, key }
+    }
+
+    /// Create a key from the given bytes.
+    ///
+    /// # Errors
+    /// If the length of the bytes is not the key length of the algorithm.
+    pub fn from_bytes(algorithm: Algorithm, bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != algorithm.key_len() {
+            Err(Error)?
+        }
+
+        let mut key = [0u8; SIZE_64];
+
+        key[..bytes.len()].copy_from_slice(bytes);
+
+        Ok(Self { algorithm, key })
+    }
+
+    /// Returns the algorithm of the key.
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Returns the key bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.key[..self.algorithm.key_len()]
+    }
+
+    /// Returns the key id.
+    ///
+    /// BLAKE3 derive key over the key bytes, salted with the algorithm id.
+    pub fn id(&self) -> [u8; SIZE_32] {
+        cck_hash::blake3::derive_key(
+            SYMMETRIC_KEY_ID_CONTEXT,
+            self.as_bytes(),
+            &[self.algorithm.id()],
+        )
+    }
+
+    /// Encode the key: algorithm id (1 byte) || key.
+    ///
+    /// Returns the encoded bytes, a prefix of the given buffer.
+    pub fn encode<'a>(&self, buffer: &'a mut [u8; SYMMETRIC_KEY_ENCODED_SIZE]) -> &'a [u8] {
+        let len = 1 + self.algorithm.key_len();
+
+        buffer[0] = self.algorithm.id();
+
+        buffer[1..len].copy_from_slice(self.as_bytes());
+
+        &buffer[..len]
+    }
+
+    /// Decode a key: algorithm id (1 byte) || key.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let (&id, key) = bytes.split_first().ok_or(Error)?;
+
+        Self::from_bytes(Algorithm::from_id(id)?, key)
+    }
+
+    /// Encode the key to a PEM block (label: `CCK SYMMETRIC KEY`).
+    pub fn to_pem<'a, const T: usize>(&self, buffer: &'a mut [u8; T]) -> Result<&'a str> {
+        let mut encoded = [0u8; SYMMETRIC_KEY_ENCODED_SIZE];
+
+        let result = pem::encode(
+            pem::PEM_LABEL_CCK_SYMMETRIC_KEY,
+            self.encode(&mut encoded),
+            buffer,
+        );
+
+        encoded.zeroize();
+
+        result
+    }
+
+    /// Decode the key from a PEM block (label: `CCK SYMMETRIC KEY`).
+    pub fn from_pem(pem: impl AsRef<[u8]>) -> Result<Self> {
+        let mut buffer = [0u8; SYMMETRIC_KEY_ENCODED_SIZE];
+
+        let result =
+            pem::decode(pem::PEM_LABEL_CCK_SYMMETRIC_KEY, pem, &mut buffer).and_then(Self::decode);
+
+        buffer.zeroize();
+
+        result
+    }
+
+    /// Encode the key to base64.
+    pub fn to_base64<'a, const T: usize>(&self, buffer: &'a mut [u8; T]) -> Result<&'a str> {
+        let mut encoded = [0u8; SYMMETRIC_KEY_ENCODED_SIZE];
+
+        let len = self.encode(&mut encoded).len();
+
+        let result = base64ct::encode(&encoded[..len], buffer);
+
+        encoded.zeroize();
+
+        result
+    }
+
+    /// Decode the key from base64.
+    pub fn from_base64(b64: impl AsRef<[u8]>) -> Result<Self> {
+        let mut buffer = [0u8; SYMMETRIC_KEY_ENCODED_SIZE];
+
+        let result = base64ct::decode(b64, &mut buffer).and_then(Self::decode);
+
+        buffer.zeroize();
+
+        result
+    }
+
+    /// Seal the given message with this key (see `seal`).
+    #[cfg(feature = "alloc")]
+    pub fn seal(&self, associated_data: &[u8], message: &[u8]) -> Result<Vec<u8>> {
+        super::seal(self.algorithm, self.as_bytes(), associated_data, message)
+    }
+
+    /// Open the given envelope with this key (see `open`).
+    ///
+    /// # Errors
+    /// If the envelope was sealed with another algorithm.
+    #[cfg(feature = "alloc")]
+    pub fn open(&self, associated_data: &[u8], envelope: &[u8]) -> Result<Vec<u8>> {
+        if envelope.first() != Some(&self.algorithm.id()) {
+            Err(Error)?
+        }
+
+        super::open(self.as_bytes(), associated_data, envelope)
+    }
+}
+
+impl core::fmt::Debug for SymmetricKey {
+    /// The key bytes are not printed.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SymmetricKey")
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for SymmetricKey {
+    /// Compares in constant time.
+    fn eq(&self, other: &Self) -> bool {
+        self.algorithm == other.algorithm && bool::from(self.key.ct_eq(&other.key))
+    }
+}
+
+impl Eq for SymmetricKey {}
+
+impl Drop for SymmetricKey {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
@@ -1,4 +1,23 @@
 mod aead;
+mod algorithm;
+#[cfg(feature = "alloc")]
+mod envelope;
+mod key;
+#[cfg(feature = "compress")]
+mod pipeline;
+#[cfg(feature = "alloc")]
+mod seal;
 pub mod aes;
 pub mod chacha;
+#[cfg(feature = "hazmat")]
+pub mod hazmat;
+pub mod siv;
 pub use aead::*;
+pub use algorithm::*;
+#[cfg(feature = "alloc")]
+pub use envelope::*;
+pub use key::*;
+#[cfg(feature = "compress")]
+pub use pipeline::*;
+#[cfg(feature = "alloc")]
+pub use seal::*;
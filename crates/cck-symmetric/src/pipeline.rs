@@ -0,0 +1,193 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use super::SymmetricKey;
+use cck_common::{Error, Result};
+use cck_compress::Level;
+use cck_format::pem;
+
+/// The magic bytes of a pipeline container.
+pub const PIPELINE_MAGIC: &[u8; 4] = b"CCKP";
+
+/// The version of the pipeline container.
+pub const PIPELINE_VERSION: u8 = 0x01;
+
+/// The compression id of an uncompressed payload.
+pub const PIPELINE_COMPRESSION_NONE: u8 = 0x00;
+
+/// The size of the pipeline header: magic (4 bytes) || version (1 byte) || compression id (1 byte).
+pub const PIPELINE_HEADER_SIZE: usize = 6;
+
+/// Pipeline
+///
+/// Compress (optional), then encrypt, then armor (optional).
+///
+/// Container: magic || version || compression id || envelope (see `seal`, starts with the AEAD algorithm id)
+///
+/// The header is authenticated together with the associated data, and `Pipeline::open` reverses the steps it records.
+///
+/// Compression leaks information about the plaintext through the length of the cipher (CRIME, BREACH).
+/// If an attacker can influence part of a plaintext that also contains secrets, use `attacker_influenced` to disable compression.
+///
+/// # Example
+/// ```
+/// let key = SymmetricKey::generate(Algorithm::XChaCha20Poly1305);
+///
+/// let sealed = Pipeline::new()
+///     .compress(cck_compress::Algorithm::Deflate, cck_compress::Level::Best)
+///     .armor()
+///     .seal(&key, &[], b"hello")
+///     .unwrap();
+///
+/// let message = Pipeline::open(&key, &[], &sealed).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Pipeline {
+    compression: Option<(cck_compress::Algorithm, Level)>,
+    attacker_influenced: bool,
+    armor: bool,
+}
+
+impl Pipeline {
+    /// Creates a pipeline without compression and armor.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compress the message with the given algorithm and level before encryption.
+    ///
+    /// Ignored if the plaintext is `attacker_influenced`.
+    pub fn compress(mut self, algorithm: cck_compress::Algorithm, level: Level) -> Self {
+        self.compression = Some((algorithm, level));
+        self
+    }
+
+    /// Mark the plaintext as attacker-influenced: compression is disabled.
+    pub fn attacker_influenced(mut self) -> Self {
+        self.attacker_influenced = true;
+        self
+    }
+
+    /// Armor the container as a PEM block (label: `CCK MESSAGE`).
+    pub fn armor(mut self) -> Self {
+        self.armor = true;
+        self
+    }
+
+    /// Returns the compression that `seal` applies.
+    pub fn compression(&self) -> Option<(cck_compress::Algorithm, Level)> {
+        if self.attacker_influenced {
+            None
+        } else {
+            self.compression
+        }
+    }
+
+    /// Seals the given message.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to use.
+    ///
+    /// * `associated_data` - The associated data to use.
+    ///
+    /// * `message` - The message to seal.
+    pub fn seal(
+        &self,
+        key: &SymmetricKey,
+        associated_data: &[u8],
+        message: &[u8],
+    ) -> Result<Vec<u8>> {
+        let (compression_id, payload) = match self.compression() {
+            Some((algorithm, level)) => (
+                algorithm.id(),
+                cck_compress::compress_vec_with(algorithm, level, message)?,
+            ),
+            None => (PIPELINE_COMPRESSION_NONE, message.to_vec()),
+        };
+
+        let header = header(compression_id);
+
+        let envelope = key.seal(&header_associated_data(&header, associated_data), &payload)?;
+
+        let mut container = Vec::with_capacity(PIPELINE_HEADER_SIZE + envelope.len());
+
+        container.extend_from_slice(&header);
+
+        container.extend_from_slice(&envelope);
+
+        if self.armor {
+            Ok(pem::encode_string(pem::PEM_LABEL_MESSAGE, &container)?.into_bytes())
+        } else {
+            Ok(container)
+        }
+    }
+
+    /// Opens the given container.
+    ///
+    /// The armor, the AEAD algorithm and the compression are read from the container.
+    ///
+    /// The payload is decompressed with the default limits (`cck_compress::Limits::default`).
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to use.
+    ///
+    /// * `associated_data` - The associated data to use.
+    ///
+    /// * `sealed` - The container, armored or not.
+    pub fn open(key: &SymmetricKey, associated_data: &[u8], sealed: &[u8]) -> Result<Vec<u8>> {
+        let decoded;
+
+        let container = if sealed.starts_with(b"-----BEGIN ") {
+            decoded = pem::decode_vec(pem::PEM_LABEL_MESSAGE, sealed)?;
+            &decoded[..]
+        } else {
+            sealed
+        };
+
+        if container.len() < PIPELINE_HEADER_SIZE {
+            Err(Error)?
+        }
+
+        let (header, envelope) = container.split_at(PIPELINE_HEADER_SIZE);
+
+        if &header[..PIPELINE_MAGIC.len()] != PIPELINE_MAGIC
+            || header[PIPELINE_MAGIC.len()] != PIPELINE_VERSION
+        {
+            Err(Error)?
+        }
+
+        let payload = key.open(&header_associated_data(header, associated_data), envelope)?;
+
+        match header[PIPELINE_HEADER_SIZE - 1] {
+            PIPELINE_COMPRESSION_NONE => Ok(payload),
+            id => cck_compress::decompress_vec_with(cck_compress::Algorithm::from_id(id)?, payload),
+        }
+    }
+}
+
+/// magic || version || compression id
+fn header(compression_id: u8) -> [u8; PIPELINE_HEADER_SIZE] {
+    let mut header = [0u8; PIPELINE_HEADER_SIZE];
+
+    header[..PIPELINE_MAGIC.len()].copy_from_slice(PIPELINE_MAGIC);
+
+    header[PIPELINE_MAGIC.len()] = PIPELINE_VERSION;
+
+    header[PIPELINE_HEADER_SIZE - 1] = compression_id;
+
+    header
+}
+
+/// header || associated data
+fn header_associated_data(header: &[u8], associated_data: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(header.len() + associated_data.len());
+
+    bytes.extend_from_slice(header);
+
+    bytes.extend_from_slice(associated_data);
+
+    bytes
+}
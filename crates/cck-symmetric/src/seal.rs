@@ -0,0 +1,217 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use super::{aead_decrypt_in_place, aead_encrypt_in_place, aes, chacha, Algorithm, Buffer};
+use cck_common::{Error, Result};
+
+/// Nonce counter
+///
+/// Produces the nonces 0, 1, 2, ... (big-endian, left-padded with zeros) for one key.
+///
+/// A counter must never be restarted for the same key.
+#[derive(Debug, Default)]
+pub struct NonceCounter {
+    next: u64,
+}
+
+impl NonceCounter {
+    /// Creates a counter that starts at the given value.
+    pub fn new(start: u64) -> Self {
+        Self { next: start }
+    }
+
+    fn fill(&mut self, nonce: &mut [u8]) -> Result<()> {
+        let counter = self.next.to_be_bytes();
+
+        self.next = self.next.checked_add(1).ok_or(Error)?;
+
+        nonce.fill(0);
+
+        let len = nonce.len();
+
+        nonce[len - counter.len()..].copy_from_slice(&counter);
+
+        Ok(())
+    }
+}
+
+/// Seals the given message with a random nonce.
+///
+/// Envelope: algorithm id (1 byte) || nonce || cipher
+///
+/// The algorithm id is authenticated together with the associated data.
+///
+/// Random 12-byte nonces should not be used for more than 2^32 messages per key. Prefer `Algorithm::XChaCha20Poly1305`, or use `seal_with_counter`.
+///
+/// # Arguments
+///
+/// * `algorithm` - The AEAD algorithm to use.
+///
+/// * `key` - The key to use. Must be `algorithm.key_len()` bytes.
+///
+/// * `associated_data` - The associated data to use.
+///
+/// * `message` - The message to seal.
+///
+/// # Example
+/// ```
+/// let envelope = cck_symmetric::seal(cck_symmetric::Algorithm::XChaCha20Poly1305, &[0u8; 32], &[], b"hello").unwrap();
+///
+/// let message = cck_symmetric::open(&[0u8; 32], &[], &envelope).unwrap();
+/// ```
+pub fn seal(
+    algorithm: Algorithm,
+    key: &[u8],
+    associated_data: &[u8],
+    message: &[u8],
+) -> Result<Vec<u8>> {
+    let nonce = cck_rand::gen_32();
+
+    seal_with_nonce(
+        algorithm,
+        key,
+        &nonce[..algorithm.nonce_len()],
+        associated_data,
+        message,
+    )
+}
+
+/// Seals the given message with the next nonce of the given counter.
+///
+/// Envelope: algorithm id (1 byte) || nonce || cipher
+///
+/// # Example
+/// ```
+/// let mut counter = cck_symmetric::NonceCounter::default();
+///
+/// let envelope = cck_symmetric::seal_with_counter(&mut counter, cck_symmetric::Algorithm::Aes256Gcm, &[0u8; 32], &[], b"hello").unwrap();
+/// ```
+pub fn seal_with_counter(
+    counter: &mut NonceCounter,
+    algorithm: Algorithm,
+    key: &[u8],
+    associated_data: &[u8],
+    message: &[u8],
+) -> Result<Vec<u8>> {
+    let mut nonce = [0u8; 32];
+
+    let nonce = &mut nonce[..algorithm.nonce_len()];
+
+    counter.fill(nonce)?;
+
+    seal_with_nonce(algorithm, key, nonce, associated_data, message)
+}
+
+/// Opens the given envelope.
+///
+/// The algorithm and nonce are read from the envelope.
+///
+/// # Arguments
+///
+/// * `key` - The key to use.
+///
+/// * `associated_data` - The associated data to use.
+///
+/// * `envelope` - The envelope to open.
+pub fn open(key: &[u8], associated_data: &[u8], envelope: &[u8]) -> Result<Vec<u8>> {
+    let (&id, rest) = envelope.split_first().ok_or(Error)?;
+
+    let algorithm = Algorithm::from_id(id)?;
+
+    if rest.len() < algorithm.nonce_len() {
+        Err(Error)?
+    }
+
+    let (nonce, cipher) = rest.split_at(algorithm.nonce_len());
+
+    let mut buffer = cipher.to_vec();
+
+    apply(
+        algorithm,
+        key,
+        nonce,
+        &header_associated_data(algorithm, associated_data),
+        &mut buffer,
+        false,
+    )?;
+
+    Ok(buffer)
+}
+
+fn seal_with_nonce(
+    algorithm: Algorithm,
+    key: &[u8],
+    nonce: &[u8],
+    associated_data: &[u8],
+    message: &[u8],
+) -> Result<Vec<u8>> {
+    let mut buffer = message.to_vec();
+
+    apply(
+        algorithm,
+        key,
+        nonce,
+        &header_associated_data(algorithm, associated_data),
+        &mut buffer,
+        true,
+    )?;
+
+    let mut envelope = Vec::with_capacity(1 + nonce.len() + buffer.len());
+
+    envelope.push(algorithm.id());
+
+    envelope.extend_from_slice(nonce);
+
+    envelope.extend_from_slice(&buffer);
+
+    Ok(envelope)
+}
+
+/// algorithm id || associated data
+fn header_associated_data(algorithm: Algorithm, associated_data: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(1 + associated_data.len());
+
+    bytes.push(algorithm.id());
+
+    bytes.extend_from_slice(associated_data);
+
+    bytes
+}
+
+fn apply(
+    algorithm: Algorithm,
+    key: &[u8],
+    nonce: &[u8],
+    associated_data: &[u8],
+    buffer: &mut dyn Buffer,
+    encrypt: bool,
+) -> Result<()> {
+    if key.len() != algorithm.key_len() {
+        Err(Error)?
+    }
+
+    macro_rules! apply {
+        ($new:path) => {{
+            let aead = $new(key.try_into().map_err(|_| Error)?);
+
+            if encrypt {
+                aead_encrypt_in_place(&aead, nonce, associated_data, buffer)
+            } else {
+                aead_decrypt_in_place(&aead, nonce, associated_data, buffer)
+            }
+        }};
+    }
+
+    match algorithm {
+        Algorithm::Aes128Gcm => apply!(aes::aes_128_gcm),
+        Algorithm::Aes192Gcm => apply!(aes::aes_192_gcm),
+        Algorithm::Aes256Gcm => apply!(aes::aes_256_gcm),
+        Algorithm::ChaCha20Poly1305 => apply!(chacha::chacha20poly1305),
+        Algorithm::XChaCha20Poly1305 => apply!(chacha::xchacha20poly1305),
+        Algorithm::Aes128GcmSiv => apply!(aes::aes_128_gcm_siv),
+        Algorithm::Aes256GcmSiv => apply!(aes::aes_256_gcm_siv),
+        Algorithm::Aes128Siv => apply!(aes::aes_128_siv),
+        Algorithm::Aes256Siv => apply!(aes::aes_256_siv),
+    }
+}
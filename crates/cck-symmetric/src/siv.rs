@@ -0,0 +1,94 @@
+use aead::Buffer;
+use cck_common::{
+    size::{SIZE_32, SIZE_64},
+    Error, Result,
+};
+
+use super::aead::KeyInit;
+
+/// AES-SIV (RFC 5297) with a 256-bit key (two AES-128 keys).
+///
+/// A nonce-misuse-resistant AEAD. Reusing a nonce only reveals whether two messages (with the same associated data) are equal.
+///
+/// As `AeadInPlace`, the nonce (16 bytes) is the last header of S2V and the synthetic IV (tag) is prepended: SIV || cipher.
+pub type Aes128Siv = aes_siv::Aes128SivAead;
+
+/// AES-SIV (RFC 5297) with a 512-bit key (two AES-256 keys).
+///
+/// See `Aes128Siv`.
+pub type Aes256Siv = aes_siv::Aes256SivAead;
+
+/// Deterministic encryption (RFC 5297, no nonce) with AES-128-SIV.
+///
+/// The same key, headers and message always produce the same output, so equal messages can be recognized.
+/// Use it only where that is acceptable (e.g. records looked up by their ciphertext); otherwise use `Aes128Siv` with a nonce.
+///
+/// Output: SIV (16 bytes) || cipher
+///
+/// # Arguments
+///
+/// * `key` - The key to use.
+///
+/// * `headers` - The associated data, authenticated in order.
+///
+/// * `buffer` - The message to encrypt.
+pub fn aes_128_siv_deterministic_encrypt_in_place(
+    key: &[u8; SIZE_32],
+    headers: &[&[u8]],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    aes_siv::siv::Aes128Siv::new_from_slice(key)
+        .map_err(|_| Error)?
+        .encrypt_in_place(headers, buffer)
+        .map_err(|_| Error)
+}
+
+/// Deterministic decryption (RFC 5297, no nonce) with AES-128-SIV.
+///
+/// If the SIV does not match, the buffer is left unchanged.
+///
+/// # Arguments
+///
+/// * `key` - The key to use.
+///
+/// * `headers` - The associated data, authenticated in order.
+///
+/// * `buffer` - The message(SIV || cipher) to decrypt.
+pub fn aes_128_siv_deterministic_decrypt_in_place(
+    key: &[u8; SIZE_32],
+    headers: &[&[u8]],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    aes_siv::siv::Aes128Siv::new_from_slice(key)
+        .map_err(|_| Error)?
+        .decrypt_in_place(headers, buffer)
+        .map_err(|_| Error)
+}
+
+/// Deterministic encryption (RFC 5297, no nonce) with AES-256-SIV.
+///
+/// See `aes_128_siv_deterministic_encrypt_in_place`.
+pub fn aes_256_siv_deterministic_encrypt_in_place(
+    key: &[u8; SIZE_64],
+    headers: &[&[u8]],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    aes_siv::siv::Aes256Siv::new_from_slice(key)
+        .map_err(|_| Error)?
+        .encrypt_in_place(headers, buffer)
+        .map_err(|_| Error)
+}
+
+/// Deterministic decryption (RFC 5297, no nonce) with AES-256-SIV.
+///
+/// See `aes_128_siv_deterministic_decrypt_in_place`.
+pub fn aes_256_siv_deterministic_decrypt_in_place(
+    key: &[u8; SIZE_64],
+    headers: &[&[u8]],
+    buffer: &mut dyn Buffer,
+) -> Result<()> {
+    aes_siv::siv::Aes256Siv::new_from_slice(key)
+        .map_err(|_| Error)?
+        .decrypt_in_place(headers, buffer)
+        .map_err(|_| Error)
+}
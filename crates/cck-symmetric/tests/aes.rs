@@ -14,7 +14,6 @@ fn aes_192_gcm_decrypt_in_place() {}
 #[test]
 fn aes_256_gcm_decrypt_in_place() {}
 
-
 /*
     Encrypt in place
 */
@@ -27,3 +26,24 @@ fn aes_192_gcm_encrypt_in_place() {}
 
 #[test]
 fn aes_256_gcm_encrypt_in_place() {}
+
+#[test]
+fn aes_256_gcm_encrypt_in_place_nonce_err() {
+    let mut buffer: cck_symmetric::ArrayVec<u8, 256> = cck_symmetric::ArrayVec::new();
+
+    buffer
+        .try_extend_from_slice(&[104, 101, 108, 108, 111])
+        .unwrap();
+
+    // error: nonce size (12 bytes expected)
+    assert_eq!(
+        cck_symmetric::aead_encrypt_in_place(
+            &cck_symmetric::aes::aes_256_gcm(&[0u8; 32]),
+            &[0u8; 24],
+            &[],
+            &mut buffer,
+        )
+        .is_err(),
+        true
+    );
+}
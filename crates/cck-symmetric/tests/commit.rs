@@ -0,0 +1,152 @@
+// e.g.
+// cargo test --package cck-symmetric --test commit --  --nocapture
+// cargo test --features=alloc --package cck-symmetric --test commit --  --nocapture
+
+#[test]
+fn aead_commit_in_place() {
+    let mut buffer: cck_symmetric::ArrayVec<u8, 256> = cck_symmetric::ArrayVec::new();
+
+    // hello: [104, 101, 108, 108, 111]
+    buffer
+        .try_extend_from_slice(&[104, 101, 108, 108, 111])
+        .unwrap();
+
+    cck_symmetric::aead_commit_encrypt_in_place::<cck_symmetric::chacha::XChaCha20Poly1305>(
+        &[0u8; 32],
+        &[0u8; 24],
+        &[],
+        &mut buffer,
+    )
+    .unwrap();
+
+    // commitment (32) + message (5) + tag (16)
+    assert_eq!(buffer.len(), 53);
+
+    cck_symmetric::aead_commit_decrypt_in_place::<cck_symmetric::chacha::XChaCha20Poly1305>(
+        &[0u8; 32],
+        &[0u8; 24],
+        &[],
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(&buffer[..], &[104, 101, 108, 108, 111]);
+}
+
+#[test]
+fn aead_commit_in_place_err() {
+    let mut buffer: cck_symmetric::ArrayVec<u8, 256> = cck_symmetric::ArrayVec::new();
+
+    buffer
+        .try_extend_from_slice(&[104, 101, 108, 108, 111])
+        .unwrap();
+
+    cck_symmetric::aead_commit_encrypt_in_place::<cck_symmetric::aes::Aes256Gcm>(
+        &[0u8; 32],
+        &[0u8; 12],
+        &[],
+        &mut buffer,
+    )
+    .unwrap();
+
+    let cipher = buffer.clone();
+
+    // error: commitment does not match the key
+    assert_eq!(
+        cck_symmetric::aead_commit_decrypt_in_place::<cck_symmetric::aes::Aes256Gcm>(
+            &[1u8; 32],
+            &[0u8; 12],
+            &[],
+            &mut buffer,
+        )
+        .is_err(),
+        true
+    );
+
+    assert_eq!(buffer, cipher);
+
+    // error: tag does not match (the commitment does)
+    assert_eq!(
+        cck_symmetric::aead_commit_decrypt_in_place::<cck_symmetric::aes::Aes256Gcm>(
+            &[0u8; 32],
+            &[0u8; 12],
+            b"other",
+            &mut buffer,
+        )
+        .is_err(),
+        true
+    );
+
+    assert_eq!(buffer, cipher);
+
+    // error: wrong key length
+    assert_eq!(
+        cck_symmetric::aead_commit_decrypt_in_place::<cck_symmetric::aes::Aes256Gcm>(
+            &[0u8; 16],
+            &[0u8; 12],
+            &[],
+            &mut buffer,
+        )
+        .is_err(),
+        true
+    );
+
+    assert_eq!(buffer, cipher);
+
+    // error: too short
+    assert_eq!(
+        cck_symmetric::aead_commit_decrypt_in_place::<cck_symmetric::aes::Aes256Gcm>(
+            &[0u8; 32],
+            &[0u8; 12],
+            &[],
+            &mut cck_symmetric::ArrayVec::<u8, 256>::new(),
+        )
+        .is_err(),
+        true
+    );
+
+    cck_symmetric::aead_commit_decrypt_in_place::<cck_symmetric::aes::Aes256Gcm>(
+        &[0u8; 32],
+        &[0u8; 12],
+        &[],
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(&buffer[..], &[104, 101, 108, 108, 111]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn aead_commit() {
+    let cipher = cck_symmetric::aead_commit_encrypt::<cck_symmetric::aes::Aes128Gcm>(
+        &[0u8; 16],
+        &[0u8; 12],
+        &[],
+        b"hello",
+    )
+    .unwrap();
+
+    assert_eq!(
+        cck_symmetric::aead_commit_decrypt::<cck_symmetric::aes::Aes128Gcm>(
+            &[0u8; 16],
+            &[0u8; 12],
+            &[],
+            &cipher,
+        )
+        .unwrap(),
+        b"hello"
+    );
+
+    // error: wrong nonce length
+    assert_eq!(
+        cck_symmetric::aead_commit_decrypt::<cck_symmetric::aes::Aes128Gcm>(
+            &[0u8; 16],
+            &[0u8; 24],
+            &[],
+            &cipher,
+        )
+        .is_err(),
+        true
+    );
+}
@@ -0,0 +1,201 @@
+// e.g.
+// cargo test --features=alloc --package cck-symmetric --test envelope --  --nocapture
+
+#[cfg(feature = "alloc")]
+#[test]
+fn wrap_key() {
+    let kek = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::Aes256Gcm);
+
+    let data_key = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    let wrapped =
+        cck_symmetric::wrap_key(cck_symmetric::WrappingKey::Symmetric(&kek), &data_key).unwrap();
+
+    assert_eq!(wrapped.kind(), cck_symmetric::WrapKind::Symmetric);
+
+    assert_eq!(wrapped.kek_id(), &kek.id());
+
+    let decoded = cck_symmetric::WrappedKey::decode(&wrapped.encode()).unwrap();
+
+    assert_eq!(decoded, wrapped);
+
+    assert_eq!(
+        cck_symmetric::unwrap_key(cck_symmetric::UnwrappingKey::Symmetric(&kek), &decoded).unwrap(),
+        data_key
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn wrap_key_err() {
+    let kek = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    let other = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    let data_key = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    let wrapped =
+        cck_symmetric::wrap_key(cck_symmetric::WrappingKey::Symmetric(&kek), &data_key).unwrap();
+
+    // wrong kek
+    assert_eq!(
+        cck_symmetric::unwrap_key(cck_symmetric::UnwrappingKey::Symmetric(&other), &wrapped)
+            .is_err(),
+        true
+    );
+
+    // tampered header
+    let mut encoded = wrapped.encode();
+
+    encoded[1] ^= 1;
+
+    let tampered = cck_symmetric::WrappedKey::decode(&encoded).unwrap();
+
+    assert_eq!(
+        cck_symmetric::unwrap_key(cck_symmetric::UnwrappingKey::Symmetric(&kek), &tampered)
+            .is_err(),
+        true
+    );
+
+    // unknown kind
+    encoded[0] = 0xff;
+
+    assert_eq!(cck_symmetric::WrappedKey::decode(&encoded).is_err(), true);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn wrap_key_x25519() {
+    let private_key = cck_asymmetric::x25519::gen_private_key();
+
+    let public_key = cck_asymmetric::x25519::gen_public_key(&private_key);
+
+    let data_key = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    let wrapped =
+        cck_symmetric::wrap_key(cck_symmetric::WrappingKey::X25519(&public_key), &data_key)
+            .unwrap();
+
+    assert_eq!(wrapped.kind(), cck_symmetric::WrapKind::X25519);
+
+    assert_eq!(
+        cck_symmetric::unwrap_key(cck_symmetric::UnwrappingKey::X25519(&private_key), &wrapped)
+            .unwrap(),
+        data_key
+    );
+
+    // The private key is not printed.
+    assert_eq!(
+        format!("{:?}", cck_symmetric::UnwrappingKey::X25519(&private_key)),
+        "X25519(..)"
+    );
+
+    let other = cck_asymmetric::x25519::gen_private_key();
+
+    assert_eq!(
+        cck_symmetric::unwrap_key(cck_symmetric::UnwrappingKey::X25519(&other), &wrapped).is_err(),
+        true
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn envelope_encrypt() {
+    let kek = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    let object = cck_symmetric::envelope_encrypt(
+        cck_symmetric::WrappingKey::Symmetric(&kek),
+        b"associated data",
+        b"hello",
+    )
+    .unwrap();
+
+    assert_eq!(
+        cck_symmetric::envelope_decrypt(
+            cck_symmetric::UnwrappingKey::Symmetric(&kek),
+            b"associated data",
+            &object
+        )
+        .unwrap(),
+        b"hello"
+    );
+
+    assert_eq!(
+        cck_symmetric::envelope_decrypt(
+            cck_symmetric::UnwrappingKey::Symmetric(&kek),
+            b"other",
+            &object
+        )
+        .is_err(),
+        true
+    );
+
+    assert_eq!(
+        cck_symmetric::envelope_decrypt(
+            cck_symmetric::UnwrappingKey::Symmetric(&kek),
+            b"associated data",
+            &object[..3]
+        )
+        .is_err(),
+        true
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn envelope_rewrap() {
+    let old_kek = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    let private_key = cck_asymmetric::x25519::gen_private_key();
+
+    let public_key = cck_asymmetric::x25519::gen_public_key(&private_key);
+
+    let object = cck_symmetric::envelope_encrypt(
+        cck_symmetric::WrappingKey::Symmetric(&old_kek),
+        &[],
+        b"hello",
+    )
+    .unwrap();
+
+    let rewrapped = cck_symmetric::envelope_rewrap(
+        cck_symmetric::UnwrappingKey::Symmetric(&old_kek),
+        cck_symmetric::WrappingKey::X25519(&public_key),
+        &object,
+    )
+    .unwrap();
+
+    // The payload is untouched.
+    let old_len = cck_symmetric::envelope_wrapped_key(&object)
+        .unwrap()
+        .encode()
+        .len()
+        + cck_symmetric::ENVELOPE_HEADER_LEN_SIZE;
+
+    let new_len = cck_symmetric::envelope_wrapped_key(&rewrapped)
+        .unwrap()
+        .encode()
+        .len()
+        + cck_symmetric::ENVELOPE_HEADER_LEN_SIZE;
+
+    assert_eq!(object[old_len..], rewrapped[new_len..]);
+
+    assert_eq!(
+        cck_symmetric::envelope_decrypt(
+            cck_symmetric::UnwrappingKey::X25519(&private_key),
+            &[],
+            &rewrapped
+        )
+        .unwrap(),
+        b"hello"
+    );
+
+    assert_eq!(
+        cck_symmetric::envelope_decrypt(
+            cck_symmetric::UnwrappingKey::Symmetric(&old_kek),
+            &[],
+            &rewrapped
+        )
+        .is_err(),
+        true
+    );
+}
@@ -0,0 +1,371 @@
+// e.g.
+// cargo test --features=hazmat --package cck-symmetric --test hazmat --  --nocapture
+
+#[cfg(feature = "hazmat")]
+#[test]
+fn aes_128_ctr_apply_keystream() {
+    // NIST SP 800-38A F.5.1
+    let mut buffer = [
+        107, 193, 190, 226, 46, 64, 159, 150, 233, 61, 126, 17, 115, 147, 23, 42, 174, 45, 138, 87,
+        30, 3, 172, 156, 158, 183, 111, 172, 69, 175, 142, 81, 48, 200, 28, 70, 163, 92, 228, 17,
+        229, 251, 193, 25, 26, 10, 82, 239, 246, 159, 36, 69, 223, 79, 155, 23, 173, 43, 65, 123,
+        230, 108, 55, 16,
+    ];
+
+    cck_symmetric::hazmat::aes_128_ctr_apply_keystream(
+        &[
+            43, 126, 21, 22, 40, 174, 210, 166, 171, 247, 21, 136, 9, 207, 79, 60,
+        ],
+        &[
+            240, 241, 242, 243, 244, 245, 246, 247, 248, 249, 250, 251, 252, 253, 254, 255,
+        ],
+        &mut buffer,
+    );
+
+    assert_eq!(
+        buffer,
+        [
+            135, 77, 97, 145, 182, 32, 227, 38, 27, 239, 104, 100, 153, 13, 182, 206, 152, 6, 246,
+            107, 121, 112, 253, 255, 134, 23, 24, 123, 185, 255, 253, 255, 90, 228, 223, 62, 219,
+            213, 211, 94, 91, 79, 9, 2, 13, 176, 62, 171, 30, 3, 29, 218, 47, 190, 3, 209, 121, 33,
+            112, 160, 243, 0, 156, 238
+        ]
+    );
+
+    cck_symmetric::hazmat::aes_128_ctr_apply_keystream(
+        &[
+            43, 126, 21, 22, 40, 174, 210, 166, 171, 247, 21, 136, 9, 207, 79, 60,
+        ],
+        &[
+            240, 241, 242, 243, 244, 245, 246, 247, 248, 249, 250, 251, 252, 253, 254, 255,
+        ],
+        &mut buffer,
+    );
+
+    assert_eq!(
+        buffer,
+        [
+            107, 193, 190, 226, 46, 64, 159, 150, 233, 61, 126, 17, 115, 147, 23, 42, 174, 45, 138,
+            87, 30, 3, 172, 156, 158, 183, 111, 172, 69, 175, 142, 81, 48, 200, 28, 70, 163, 92,
+            228, 17, 229, 251, 193, 25, 26, 10, 82, 239, 246, 159, 36, 69, 223, 79, 155, 23, 173,
+            43, 65, 123, 230, 108, 55, 16
+        ]
+    );
+}
+
+#[cfg(feature = "hazmat")]
+#[test]
+fn aes_128_cbc_encrypt_in_place() {
+    // NIST SP 800-38A F.2.1, followed by a full block of PKCS#7 padding
+    let mut buffer: cck_symmetric::ArrayVec<u8, 256> = cck_symmetric::ArrayVec::new();
+
+    buffer
+        .try_extend_from_slice(&[
+            107, 193, 190, 226, 46, 64, 159, 150, 233, 61, 126, 17, 115, 147, 23, 42, 174, 45, 138,
+            87, 30, 3, 172, 156, 158, 183, 111, 172, 69, 175, 142, 81, 48, 200, 28, 70, 163, 92,
+            228, 17, 229, 251, 193, 25, 26, 10, 82, 239, 246, 159, 36, 69, 223, 79, 155, 23, 173,
+            43, 65, 123, 230, 108, 55, 16,
+        ])
+        .unwrap();
+
+    cck_symmetric::hazmat::aes_128_cbc_encrypt_in_place(
+        &[
+            43, 126, 21, 22, 40, 174, 210, 166, 171, 247, 21, 136, 9, 207, 79, 60,
+        ],
+        &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(buffer.len(), 80);
+
+    assert_eq!(
+        &buffer[..64],
+        &[
+            118, 73, 171, 172, 129, 25, 178, 70, 206, 233, 142, 155, 18, 233, 25, 125, 80, 134,
+            203, 155, 80, 114, 25, 238, 149, 219, 17, 58, 145, 118, 120, 178, 115, 190, 214, 184,
+            227, 193, 116, 59, 113, 22, 230, 158, 34, 34, 149, 22, 63, 241, 202, 161, 104, 31, 172,
+            9, 18, 14, 202, 48, 117, 134, 225, 167
+        ]
+    );
+
+    cck_symmetric::hazmat::aes_128_cbc_decrypt_in_place(
+        &[
+            43, 126, 21, 22, 40, 174, 210, 166, 171, 247, 21, 136, 9, 207, 79, 60,
+        ],
+        &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(
+        &buffer[..],
+        &[
+            107, 193, 190, 226, 46, 64, 159, 150, 233, 61, 126, 17, 115, 147, 23, 42, 174, 45, 138,
+            87, 30, 3, 172, 156, 158, 183, 111, 172, 69, 175, 142, 81, 48, 200, 28, 70, 163, 92,
+            228, 17, 229, 251, 193, 25, 26, 10, 82, 239, 246, 159, 36, 69, 223, 79, 155, 23, 173,
+            43, 65, 123, 230, 108, 55, 16
+        ]
+    );
+}
+
+#[cfg(feature = "hazmat")]
+#[test]
+fn aes_256_cbc_decrypt_in_place_err() {
+    let mut buffer: cck_symmetric::ArrayVec<u8, 256> = cck_symmetric::ArrayVec::new();
+
+    buffer.try_extend_from_slice(b"hello").unwrap();
+
+    cck_symmetric::hazmat::aes_256_cbc_encrypt_in_place(&[0u8; 32], &[0u8; 16], &mut buffer)
+        .unwrap();
+
+    assert_eq!(buffer.len(), 16);
+
+    // wrong length
+    let mut short: cck_symmetric::ArrayVec<u8, 256> = cck_symmetric::ArrayVec::new();
+
+    short.try_extend_from_slice(&buffer[..15]).unwrap();
+
+    assert_eq!(
+        cck_symmetric::hazmat::aes_256_cbc_decrypt_in_place(&[0u8; 32], &[0u8; 16], &mut short)
+            .is_err(),
+        true
+    );
+
+    // bad padding: the same error, and the buffer is cleared
+    let mut iv = [0u8; 16];
+
+    iv[15] ^= 0x0b ^ 0x0c;
+
+    assert_eq!(
+        cck_symmetric::hazmat::aes_256_cbc_decrypt_in_place(&[0u8; 32], &iv, &mut buffer).is_err(),
+        true
+    );
+
+    assert_eq!(buffer.len(), 0);
+}
+
+#[cfg(feature = "hazmat")]
+#[test]
+fn chacha20_apply_keystream() {
+    // RFC 8439 2.4.2
+    let key: [u8; 32] = core::array::from_fn(|i| i as u8);
+
+    let mut buffer = *b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+    cck_symmetric::hazmat::chacha20_apply_keystream(
+        &key,
+        &[0, 0, 0, 0, 0, 0, 0, 74, 0, 0, 0, 0],
+        1,
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(
+        buffer,
+        [
+            110, 46, 53, 154, 37, 104, 249, 128, 65, 186, 7, 40, 221, 13, 105, 129, 233, 126, 122,
+            236, 29, 67, 96, 194, 10, 39, 175, 204, 253, 159, 174, 11, 249, 27, 101, 197, 82, 71,
+            51, 171, 143, 89, 61, 171, 205, 98, 179, 87, 22, 57, 214, 36, 230, 81, 82, 171, 143,
+            83, 12, 53, 159, 8, 97, 216, 7, 202, 13, 191, 80, 13, 106, 97, 86, 163, 142, 8, 138,
+            34, 182, 94, 82, 188, 81, 77, 22, 204, 248, 6, 129, 140, 233, 26, 183, 121, 55, 54, 90,
+            249, 11, 191, 116, 163, 91, 230, 180, 11, 142, 237, 242, 120, 94, 66, 135, 77
+        ]
+    );
+}
+
+#[cfg(feature = "hazmat")]
+#[test]
+fn xchacha20_apply_keystream() {
+    let mut buffer = *b"hello";
+
+    cck_symmetric::hazmat::xchacha20_apply_keystream(&[1u8; 32], &[2u8; 24], 0, &mut buffer)
+        .unwrap();
+
+    assert_ne!(&buffer, b"hello");
+
+    cck_symmetric::hazmat::xchacha20_apply_keystream(&[1u8; 32], &[2u8; 24], 0, &mut buffer)
+        .unwrap();
+
+    assert_eq!(&buffer, b"hello");
+}
+
+#[cfg(feature = "hazmat")]
+#[test]
+fn chacha20_apply_keystream_err() {
+    let mut buffer = [0u8; 65];
+
+    // error: the keystream runs past the last block counter
+    assert_eq!(
+        cck_symmetric::hazmat::chacha20_apply_keystream(
+            &[0u8; 32],
+            &[0u8; 12],
+            u32::MAX,
+            &mut buffer
+        )
+        .is_err(),
+        true
+    );
+
+    assert_eq!(buffer, [0u8; 65]);
+
+    assert_eq!(
+        cck_symmetric::hazmat::xchacha20_apply_keystream(
+            &[0u8; 32],
+            &[0u8; 24],
+            u32::MAX,
+            &mut buffer
+        )
+        .is_err(),
+        true
+    );
+
+    assert_eq!(buffer, [0u8; 65]);
+
+    // One block before the end is fine.
+    cck_symmetric::hazmat::chacha20_apply_keystream(
+        &[0u8; 32],
+        &[0u8; 12],
+        u32::MAX - 1,
+        &mut buffer[..64],
+    )
+    .unwrap();
+}
+
+#[cfg(feature = "hazmat")]
+#[test]
+fn aes_128_cbc_hmac_sha256_in_place() {
+    let mut buffer = cck_symmetric::ArrayVec::<u8, 64>::new();
+
+    // hello: [104, 101, 108, 108, 111]
+    buffer
+        .try_extend_from_slice(&[104, 101, 108, 108, 111])
+        .unwrap();
+
+    cck_symmetric::hazmat::aes_128_cbc_hmac_sha256_encrypt_in_place(
+        &[0u8; 16],
+        &[1u8; 32],
+        &[2u8; 16],
+        &mut buffer,
+    )
+    .unwrap();
+
+    // cipher (16) + tag (32)
+    assert_eq!(buffer.len(), 48);
+
+    cck_symmetric::hazmat::aes_128_cbc_hmac_sha256_decrypt_in_place(
+        &[0u8; 16],
+        &[1u8; 32],
+        &[2u8; 16],
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(&buffer[..], &[104, 101, 108, 108, 111]);
+}
+
+#[cfg(feature = "hazmat")]
+#[test]
+fn aes_256_cbc_hmac_sha256_decrypt_in_place_err() {
+    let mut buffer = cck_symmetric::ArrayVec::<u8, 64>::new();
+
+    buffer
+        .try_extend_from_slice(&[104, 101, 108, 108, 111])
+        .unwrap();
+
+    cck_symmetric::hazmat::aes_256_cbc_hmac_sha256_encrypt_in_place(
+        &[0u8; 32],
+        &[1u8; 32],
+        &[2u8; 16],
+        &mut buffer,
+    )
+    .unwrap();
+
+    let cipher = buffer.clone();
+
+    // error: modified cipher
+    buffer[0] ^= 1;
+
+    assert_eq!(
+        cck_symmetric::hazmat::aes_256_cbc_hmac_sha256_decrypt_in_place(
+            &[0u8; 32],
+            &[1u8; 32],
+            &[2u8; 16],
+            &mut buffer,
+        )
+        .is_err(),
+        true
+    );
+
+    buffer[0] ^= 1;
+
+    assert_eq!(buffer, cipher);
+
+    // error: modified tag
+    buffer[47] ^= 1;
+
+    assert_eq!(
+        cck_symmetric::hazmat::aes_256_cbc_hmac_sha256_decrypt_in_place(
+            &[0u8; 32],
+            &[1u8; 32],
+            &[2u8; 16],
+            &mut buffer,
+        )
+        .is_err(),
+        true
+    );
+
+    buffer[47] ^= 1;
+
+    assert_eq!(buffer, cipher);
+
+    // error: modified IV
+    assert_eq!(
+        cck_symmetric::hazmat::aes_256_cbc_hmac_sha256_decrypt_in_place(
+            &[0u8; 32],
+            &[1u8; 32],
+            &[3u8; 16],
+            &mut buffer,
+        )
+        .is_err(),
+        true
+    );
+
+    assert_eq!(buffer, cipher);
+
+    // error: wrong MAC key
+    assert_eq!(
+        cck_symmetric::hazmat::aes_256_cbc_hmac_sha256_decrypt_in_place(
+            &[0u8; 32],
+            &[0u8; 32],
+            &[2u8; 16],
+            &mut buffer,
+        )
+        .is_err(),
+        true
+    );
+
+    assert_eq!(buffer, cipher);
+
+    // error: shorter than the tag
+    assert_eq!(
+        cck_symmetric::hazmat::aes_256_cbc_hmac_sha256_decrypt_in_place(
+            &[0u8; 32],
+            &[1u8; 32],
+            &[2u8; 16],
+            &mut cck_symmetric::ArrayVec::<u8, 64>::new(),
+        )
+        .is_err(),
+        true
+    );
+
+    cck_symmetric::hazmat::aes_256_cbc_hmac_sha256_decrypt_in_place(
+        &[0u8; 32],
+        &[1u8; 32],
+        &[2u8; 16],
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(&buffer[..], &[104, 101, 108, 108, 111]);
+}
@@ -0,0 +1,93 @@
+// e.g.
+// cargo test --package cck-symmetric --test key --  --nocapture
+
+#[test]
+fn symmetric_key_generate() {
+    let key = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::Aes128Gcm);
+
+    assert_eq!(key.algorithm(), cck_symmetric::Algorithm::Aes128Gcm);
+
+    assert_eq!(key.as_bytes().len(), 16);
+
+    assert_ne!(
+        key,
+        cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::Aes128Gcm)
+    );
+}
+
+#[test]
+fn symmetric_key_id() {
+    let key =
+        cck_symmetric::SymmetricKey::from_bytes(cck_symmetric::Algorithm::Aes256Gcm, &[0u8; 32])
+            .unwrap();
+
+    assert_eq!(
+        key.id(),
+        cck_hash::blake3::derive_key(
+            cck_symmetric::SYMMETRIC_KEY_ID_CONTEXT,
+            &[0u8; 32],
+            &[cck_symmetric::Algorithm::Aes256Gcm.id()]
+        )
+    );
+
+    // the algorithm is part of the id
+    assert_ne!(
+        key.id(),
+        cck_symmetric::SymmetricKey::from_bytes(
+            cck_symmetric::Algorithm::XChaCha20Poly1305,
+            &[0u8; 32]
+        )
+        .unwrap()
+        .id()
+    );
+}
+
+#[test]
+fn symmetric_key_pem() {
+    let key = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::Aes256Siv);
+
+    let mut buffer = [0u8; 1024];
+
+    let pem = key.to_pem(&mut buffer).unwrap();
+
+    assert_eq!(pem.starts_with("-----BEGIN CCK SYMMETRIC KEY-----"), true);
+
+    assert_eq!(cck_symmetric::SymmetricKey::from_pem(pem).unwrap(), key);
+}
+
+#[test]
+fn symmetric_key_base64() {
+    let key = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::ChaCha20Poly1305);
+
+    let mut buffer = [0u8; 1024];
+
+    let b64 = key.to_base64(&mut buffer).unwrap();
+
+    assert_eq!(cck_symmetric::SymmetricKey::from_base64(b64).unwrap(), key);
+}
+
+#[test]
+fn symmetric_key_err() {
+    // error: key size
+    assert_eq!(
+        cck_symmetric::SymmetricKey::from_bytes(cck_symmetric::Algorithm::Aes256Gcm, &[0u8; 16])
+            .is_err(),
+        true
+    );
+
+    // error: unknown algorithm
+    assert_eq!(
+        cck_symmetric::SymmetricKey::decode(&[0xff; 17]).is_err(),
+        true
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn symmetric_key_seal() {
+    let key = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    let envelope = key.seal(&[], b"hello").unwrap();
+
+    assert_eq!(key.open(&[], &envelope).unwrap(), b"hello");
+}
@@ -0,0 +1,89 @@
+// e.g.
+// cargo test --features=compress --package cck-symmetric --test pipeline --  --nocapture
+
+#[cfg(feature = "compress")]
+#[test]
+fn pipeline() {
+    let key = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::Aes256Gcm);
+
+    // 32 symbols: compresses, but not beyond 2:1.
+    let message: Vec<u8> = (0..1024)
+        .scan(1u32, |state, _| {
+            *state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+
+            Some(b'a' + (*state >> 27) as u8)
+        })
+        .collect();
+
+    let pipeline = cck_symmetric::Pipeline::new()
+        .compress(cck_compress::Algorithm::Deflate, cck_compress::Level::Best);
+
+    let sealed = pipeline.seal(&key, b"associated data", &message).unwrap();
+
+    assert_eq!(&sealed[..4], cck_symmetric::PIPELINE_MAGIC);
+
+    assert_eq!(sealed[5], cck_compress::Algorithm::Deflate.id());
+
+    assert_eq!(sealed[6], cck_symmetric::Algorithm::Aes256Gcm.id());
+
+    assert!(sealed.len() < message.len());
+
+    assert_eq!(
+        cck_symmetric::Pipeline::open(&key, b"associated data", &sealed).unwrap(),
+        message
+    );
+
+    assert_eq!(
+        cck_symmetric::Pipeline::open(&key, b"other", &sealed).is_err(),
+        true
+    );
+
+    // The compression id is authenticated.
+    let mut tampered = sealed.clone();
+
+    tampered[5] = cck_symmetric::PIPELINE_COMPRESSION_NONE;
+
+    assert_eq!(
+        cck_symmetric::Pipeline::open(&key, b"associated data", &tampered).is_err(),
+        true
+    );
+}
+
+#[cfg(feature = "compress")]
+#[test]
+fn pipeline_armor() {
+    let key = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    let sealed = cck_symmetric::Pipeline::new()
+        .armor()
+        .seal(&key, &[], b"hello")
+        .unwrap();
+
+    assert!(sealed.starts_with(b"-----BEGIN CCK MESSAGE-----"));
+
+    assert_eq!(
+        cck_symmetric::Pipeline::open(&key, &[], &sealed).unwrap(),
+        b"hello"
+    );
+}
+
+#[cfg(feature = "compress")]
+#[test]
+fn pipeline_attacker_influenced() {
+    let key = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    let pipeline = cck_symmetric::Pipeline::new()
+        .compress(cck_compress::Algorithm::Deflate, cck_compress::Level::Best)
+        .attacker_influenced();
+
+    assert_eq!(pipeline.compression(), None);
+
+    let sealed = pipeline.seal(&key, &[], &[b'a'; 1024]).unwrap();
+
+    assert_eq!(sealed[5], cck_symmetric::PIPELINE_COMPRESSION_NONE);
+
+    assert_eq!(
+        cck_symmetric::Pipeline::open(&key, &[], &sealed).unwrap(),
+        [b'a'; 1024]
+    );
+}
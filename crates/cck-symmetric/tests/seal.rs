@@ -0,0 +1,120 @@
+// e.g.
+// cargo test --features=alloc --package cck-symmetric --test seal --  --nocapture
+
+#[cfg(feature = "alloc")]
+#[test]
+fn seal() {
+    let envelope = cck_symmetric::seal(
+        cck_symmetric::Algorithm::XChaCha20Poly1305,
+        &[0u8; 32],
+        b"associated data",
+        b"hello",
+    )
+    .unwrap();
+
+    // id (1) + nonce (24) + message (5) + tag (16)
+    assert_eq!(envelope.len(), 46);
+
+    assert_eq!(
+        envelope[0],
+        cck_symmetric::Algorithm::XChaCha20Poly1305.id()
+    );
+
+    assert_eq!(
+        cck_symmetric::open(&[0u8; 32], b"associated data", &envelope).unwrap(),
+        b"hello"
+    );
+
+    // random nonce
+    assert_ne!(
+        envelope,
+        cck_symmetric::seal(
+            cck_symmetric::Algorithm::XChaCha20Poly1305,
+            &[0u8; 32],
+            b"associated data",
+            b"hello",
+        )
+        .unwrap()
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn seal_with_counter() {
+    let mut counter = cck_symmetric::NonceCounter::default();
+
+    let first = cck_symmetric::seal_with_counter(
+        &mut counter,
+        cck_symmetric::Algorithm::Aes256Gcm,
+        &[0u8; 32],
+        &[],
+        b"hello",
+    )
+    .unwrap();
+
+    let second = cck_symmetric::seal_with_counter(
+        &mut counter,
+        cck_symmetric::Algorithm::Aes256Gcm,
+        &[0u8; 32],
+        &[],
+        b"hello",
+    )
+    .unwrap();
+
+    assert_eq!(&first[1..13], &[0u8; 12]);
+
+    assert_eq!(&second[1..13], &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+    assert_eq!(
+        cck_symmetric::open(&[0u8; 32], &[], &second).unwrap(),
+        b"hello"
+    );
+
+    // error: counter exhausted
+    assert_eq!(
+        cck_symmetric::seal_with_counter(
+            &mut cck_symmetric::NonceCounter::new(u64::MAX),
+            cck_symmetric::Algorithm::Aes256Gcm,
+            &[0u8; 32],
+            &[],
+            b"hello",
+        )
+        .is_err(),
+        true
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn open_err() {
+    let mut envelope = cck_symmetric::seal(
+        cck_symmetric::Algorithm::Aes128Gcm,
+        &[0u8; 16],
+        &[],
+        b"hello",
+    )
+    .unwrap();
+
+    // error: key size
+    assert_eq!(
+        cck_symmetric::open(&[0u8; 32], &[], &envelope).is_err(),
+        true
+    );
+
+    // error: associated data
+    assert_eq!(
+        cck_symmetric::open(&[0u8; 16], b"associated data", &envelope).is_err(),
+        true
+    );
+
+    // error: unknown algorithm
+    assert_eq!(cck_symmetric::open(&[0u8; 16], &[], &[0xff]).is_err(), true);
+
+    // error: algorithm id is authenticated
+    envelope[0] = cck_symmetric::Algorithm::Aes128GcmSiv.id();
+
+    assert_eq!(
+        cck_symmetric::open(&[0u8; 16], &[], &envelope).is_err(),
+        true
+    );
+}
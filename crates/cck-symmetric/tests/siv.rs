@@ -0,0 +1,324 @@
+// e.g.
+// cargo test --package cck-symmetric --test siv --  --nocapture
+// cargo test --package cck-symmetric --test siv -- aes_128_siv_deterministic --nocapture
+
+#[test]
+fn aes_128_siv_deterministic() {
+    // RFC 5297 A.1
+    let key = [
+        255, 254, 253, 252, 251, 250, 249, 248, 247, 246, 245, 244, 243, 242, 241, 240, 240, 241,
+        242, 243, 244, 245, 246, 247, 248, 249, 250, 251, 252, 253, 254, 255,
+    ];
+
+    let associated_data: &[u8] = &[
+        16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38,
+        39,
+    ];
+
+    let mut buffer: cck_symmetric::ArrayVec<u8, 256> = cck_symmetric::ArrayVec::new();
+
+    buffer
+        .try_extend_from_slice(&[
+            17, 34, 51, 68, 85, 102, 119, 136, 153, 170, 187, 204, 221, 238,
+        ])
+        .unwrap();
+
+    cck_symmetric::siv::aes_128_siv_deterministic_encrypt_in_place(
+        &key,
+        &[associated_data],
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(
+        &buffer[..],
+        &[
+            133, 99, 45, 7, 198, 232, 243, 127, 149, 10, 205, 50, 10, 46, 204, 147, 64, 192, 43,
+            150, 144, 196, 220, 4, 218, 239, 127, 106, 254, 92
+        ]
+    );
+
+    // error: associated data
+    let cipher = buffer.clone();
+
+    assert_eq!(
+        cck_symmetric::siv::aes_128_siv_deterministic_decrypt_in_place(
+            &key,
+            &[b"other"],
+            &mut buffer
+        )
+        .is_err(),
+        true
+    );
+
+    assert_eq!(buffer, cipher);
+
+    cck_symmetric::siv::aes_128_siv_deterministic_decrypt_in_place(
+        &key,
+        &[associated_data],
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(
+        &buffer[..],
+        &[17, 34, 51, 68, 85, 102, 119, 136, 153, 170, 187, 204, 221, 238]
+    );
+}
+
+#[test]
+fn aes_128_siv_nonce() {
+    // RFC 5297 A.2: two associated data headers, then the nonce
+    let key = [
+        127, 126, 125, 124, 123, 122, 121, 120, 119, 118, 117, 116, 115, 114, 113, 112, 64, 65, 66,
+        67, 68, 69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79,
+    ];
+
+    let associated_data: &[u8] = &[
+        0, 17, 34, 51, 68, 85, 102, 119, 136, 153, 170, 187, 204, 221, 238, 255, 222, 173, 218,
+        218, 222, 173, 218, 218, 255, 238, 221, 204, 187, 170, 153, 136, 119, 102, 85, 68, 51, 34,
+        17, 0,
+    ];
+
+    let nonce: &[u8] = &[
+        9, 249, 17, 2, 157, 116, 227, 91, 216, 65, 86, 197, 99, 86, 136, 192,
+    ];
+
+    let mut buffer: cck_symmetric::ArrayVec<u8, 256> = cck_symmetric::ArrayVec::new();
+
+    buffer
+        .try_extend_from_slice(b"this is some plaintext to encrypt using SIV-AES")
+        .unwrap();
+
+    cck_symmetric::siv::aes_128_siv_deterministic_encrypt_in_place(
+        &key,
+        &[
+            associated_data,
+            &[16, 32, 48, 64, 80, 96, 112, 128, 144, 160],
+            nonce,
+        ],
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(
+        &buffer[..],
+        &[
+            123, 219, 110, 59, 67, 38, 103, 235, 6, 244, 209, 75, 255, 47, 189, 15, 203, 144, 15,
+            47, 221, 190, 64, 67, 38, 96, 25, 101, 200, 137, 191, 23, 219, 167, 124, 235, 9, 79,
+            166, 99, 183, 163, 247, 72, 186, 138, 248, 41, 234, 100, 173, 84, 74, 39, 46, 156, 72,
+            91, 98, 163, 253, 92, 13
+        ]
+    );
+
+    // As an AEAD: one associated data header, then the nonce (RustCrypto AEADs test vector)
+    let mut buffer: cck_symmetric::ArrayVec<u8, 256> = cck_symmetric::ArrayVec::new();
+
+    buffer
+        .try_extend_from_slice(b"this is some plaintext to encrypt using SIV-AES")
+        .unwrap();
+
+    cck_symmetric::aead_encrypt_in_place(
+        &cck_symmetric::aes::aes_128_siv(&key),
+        nonce,
+        associated_data,
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(
+        &buffer[..],
+        &[
+            133, 130, 94, 34, 233, 12, 242, 221, 218, 44, 84, 141, 199, 193, 182, 49, 13, 205, 172,
+            160, 206, 191, 157, 198, 203, 144, 88, 63, 91, 241, 80, 110, 2, 205, 72, 131, 43, 0,
+            228, 229, 152, 178, 178, 42, 83, 230, 25, 157, 77, 240, 193, 102, 106, 53, 160, 67, 59,
+            37, 13, 193, 52, 215, 118
+        ]
+    );
+
+    cck_symmetric::aead_decrypt_in_place(
+        &cck_symmetric::aes::aes_128_siv(&key),
+        nonce,
+        associated_data,
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(
+        &buffer[..],
+        b"this is some plaintext to encrypt using SIV-AES"
+    );
+}
+
+#[test]
+fn aes_256_siv() {
+    // deterministic (RustCrypto AEADs test vector)
+    let key = [
+        255, 254, 253, 252, 251, 250, 249, 248, 247, 246, 245, 244, 243, 242, 241, 240, 111, 110,
+        109, 108, 107, 106, 105, 104, 103, 102, 101, 100, 99, 98, 97, 96, 240, 241, 242, 243, 244,
+        245, 246, 247, 248, 249, 250, 251, 252, 253, 254, 255, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10,
+        11, 12, 13, 14, 15,
+    ];
+
+    let mut buffer: cck_symmetric::ArrayVec<u8, 256> = cck_symmetric::ArrayVec::new();
+
+    buffer
+        .try_extend_from_slice(&[
+            17, 34, 51, 68, 85, 102, 119, 136, 153, 170, 187, 204, 221, 238,
+        ])
+        .unwrap();
+
+    cck_symmetric::siv::aes_256_siv_deterministic_encrypt_in_place(
+        &key,
+        &[&[
+            16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37,
+            38, 39,
+        ]],
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(
+        &buffer[..],
+        &[
+            241, 37, 39, 76, 89, 128, 101, 207, 194, 107, 14, 113, 87, 80, 41, 8, 139, 3, 82, 23,
+            227, 128, 202, 200, 145, 158, 232, 0, 193, 38
+        ]
+    );
+
+    // nonce-based: two associated data headers, then the nonce (RustCrypto AEADs test vector)
+    let key = [
+        127, 126, 125, 124, 123, 122, 121, 120, 119, 118, 117, 116, 115, 114, 113, 112, 111, 110,
+        109, 108, 107, 106, 105, 104, 103, 102, 101, 100, 99, 98, 97, 96, 64, 65, 66, 67, 68, 69,
+        70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80, 81, 82, 83, 84, 85, 86, 87, 88, 89, 90, 91, 91,
+        93, 94, 95,
+    ];
+
+    let associated_data: &[u8] = &[
+        0, 17, 34, 51, 68, 85, 102, 119, 136, 153, 170, 187, 204, 221, 238, 255, 222, 173, 218,
+        218, 222, 173, 218, 218, 255, 238, 221, 204, 187, 170, 153, 136, 119, 102, 85, 68, 51, 34,
+        17, 0,
+    ];
+
+    let nonce: &[u8] = &[
+        9, 249, 17, 2, 157, 116, 227, 91, 216, 65, 86, 197, 99, 86, 136, 192,
+    ];
+
+    let mut buffer: cck_symmetric::ArrayVec<u8, 256> = cck_symmetric::ArrayVec::new();
+
+    buffer
+        .try_extend_from_slice(b"this is some plaintext to encrypt using SIV-AES")
+        .unwrap();
+
+    cck_symmetric::siv::aes_256_siv_deterministic_encrypt_in_place(
+        &key,
+        &[
+            associated_data,
+            &[16, 32, 48, 64, 80, 96, 112, 128, 144, 160],
+            nonce,
+        ],
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(
+        &buffer[..],
+        &[
+            133, 184, 22, 115, 16, 3, 141, 183, 220, 70, 146, 192, 40, 28, 163, 88, 104, 24, 27,
+            39, 98, 243, 194, 79, 46, 250, 95, 184, 12, 177, 67, 81, 108, 230, 196, 52, 184, 152,
+            166, 253, 142, 185, 138, 65, 136, 66, 245, 31, 102, 252, 103, 222, 67, 172, 24, 90,
+            102, 221, 114, 71, 91, 187, 8
+        ]
+    );
+
+    // As an AEAD, the nonce is the last header after the associated data.
+    let mut expected: cck_symmetric::ArrayVec<u8, 256> = cck_symmetric::ArrayVec::new();
+
+    expected.try_extend_from_slice(b"hello").unwrap();
+
+    cck_symmetric::siv::aes_256_siv_deterministic_encrypt_in_place(
+        &key,
+        &[associated_data, nonce],
+        &mut expected,
+    )
+    .unwrap();
+
+    let mut buffer: cck_symmetric::ArrayVec<u8, 256> = cck_symmetric::ArrayVec::new();
+
+    buffer.try_extend_from_slice(b"hello").unwrap();
+
+    cck_symmetric::aead_encrypt_in_place(
+        &cck_symmetric::aes::aes_256_siv(&key),
+        nonce,
+        associated_data,
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(buffer, expected);
+
+    // error: tampered, the buffer is left unchanged
+    buffer[20] ^= 1;
+
+    let cipher = buffer.clone();
+
+    assert_eq!(
+        cck_symmetric::aead_decrypt_in_place(
+            &cck_symmetric::aes::aes_256_siv(&key),
+            nonce,
+            associated_data,
+            &mut buffer,
+        )
+        .is_err(),
+        true
+    );
+
+    assert_eq!(buffer, cipher);
+}
+
+#[test]
+fn aes_128_gcm_siv_encrypt_in_place() {
+    let mut buffer: cck_symmetric::ArrayVec<u8, 256> = cck_symmetric::ArrayVec::new();
+
+    buffer
+        .try_extend_from_slice(&[104, 101, 108, 108, 111])
+        .unwrap();
+
+    cck_symmetric::aead_encrypt_in_place(
+        &cck_symmetric::aes::aes_128_gcm_siv(&[0u8; 16]),
+        &[0u8; 12],
+        &[],
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(
+        &buffer[..],
+        &[
+            59, 225, 134, 242, 126, 23, 242, 47, 195, 163, 155, 45, 118, 246, 62, 97, 211, 209,
+            156, 56, 177
+        ]
+    );
+}
+
+#[test]
+fn aes_256_gcm_siv_decrypt_in_place() {
+    let mut buffer: cck_symmetric::ArrayVec<u8, 256> = cck_symmetric::ArrayVec::new();
+
+    buffer
+        .try_extend_from_slice(&[
+            94, 194, 203, 194, 40, 43, 130, 250, 40, 207, 248, 122, 78, 48, 234, 18, 118, 108, 53,
+            65, 56,
+        ])
+        .unwrap();
+
+    cck_symmetric::aead_decrypt_in_place(
+        &cck_symmetric::aes::aes_256_gcm_siv(&[0u8; 32]),
+        &[0u8; 12],
+        &[],
+        &mut buffer,
+    )
+    .unwrap();
+
+    assert_eq!(&buffer[..], &[104, 101, 108, 108, 111]);
+}
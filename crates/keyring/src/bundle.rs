@@ -0,0 +1,423 @@
+use crate::user::User;
+
+use cck_asymmetric::{Expiry, Key, KeyType, PrivateKey, PublicKey};
+use cck_common::size::{SIZE_32, SIZE_64};
+use cck_format::pem;
+
+/// The magic bytes of an encoded bundle.
+pub const BUNDLE_MAGIC: &[u8; 4] = b"CCKB";
+
+/// The version of the encoded bundle.
+pub const BUNDLE_VERSION: u8 = 0x01;
+
+/// The domain of the self-signature: the message signed by the primary key starts with it.
+const CERTIFICATION_DOMAIN: &[u8] = b"CCK USER ID\0";
+
+/// Bundle
+///
+/// A portable identity ("certificate"): a user with their public keys,
+/// the primary signing key (Ed25519) and subkeys with their binding signatures by the primary key,
+/// and a self-signature of the user identity (id, name, email) and every key (primary, key type, expiry, public key)
+/// by the primary key.
+///
+/// Exported by `Ring::export_bundle`, armored by `to_pem` (label: `CCK CERTIFICATE`),
+/// and verified by `Ring::import_bundle` before it is inserted.
+///
+/// # Example
+/// ```
+/// let bundle = ring.export_bundle(user)?;
+///
+/// let pem = bundle.to_pem()?;
+///
+/// other_ring.import_bundle(&Bundle::from_pem(pem)?)?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bundle {
+    pub(super) user: User,
+    pub(super) public_keys: Vec<PublicKey>,
+    pub(super) self_signature: Vec<u8>,
+}
+
+impl Bundle {
+    /// Creates a bundle of the given user, signed by the primary key.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The user.
+    ///
+    /// * `primary_key` - The primary signing key (Ed25519) of the user.
+    ///
+    /// * `subkeys` - The subkeys, signed by the primary key (see `PrivateKey::derive_key`).
+    ///
+    /// # Errors
+    /// If the primary key is not a primary Ed25519 key, or a subkey is primary or not signed by the primary key.
+    pub fn new(
+        user: impl Into<User>,
+        primary_key: &PrivateKey,
+        subkeys: Vec<PublicKey>,
+    ) -> cck_common::Result<Self> {
+        let user = user.into();
+
+        if !primary_key.is_primary()
+            || !matches!(primary_key.key_type(), KeyType::Ed25519)
+            || subkeys.iter().any(|subkey| subkey.is_primary())
+        {
+            Err(cck_common::Error)?
+        }
+
+        let mut public_keys = vec![primary_key.public_key()];
+
+        subkeys
+            .iter()
+            .try_for_each(|subkey| verify_key(&public_keys[0], subkey))?;
+
+        public_keys.extend(subkeys);
+
+        let self_signature = cck_asymmetric::ed25519::sign(
+            primary_key
+                .as_bytes()
+                .try_into()
+                .map_err(|_| cck_common::Error)?,
+            &self_signature_message(&user, &public_keys)?,
+        )?
+        .to_vec();
+
+        Ok(Self {
+            user,
+            public_keys,
+            self_signature,
+        })
+    }
+
+    /// Creates a bundle from its parts, e.g. received from elsewhere. It is verified on import, or by `verify`.
+    pub fn from_parts(
+        user: impl Into<User>,
+        public_keys: Vec<PublicKey>,
+        self_signature: Vec<u8>,
+    ) -> Self {
+        Self {
+            user: user.into(),
+            public_keys,
+            self_signature,
+        }
+    }
+
+    /// Get the user
+    pub fn user(&self) -> &User {
+        &self.user
+    }
+
+    /// Get the public keys
+    pub fn public_keys(&self) -> &[PublicKey] {
+        &self.public_keys
+    }
+
+    /// Get the self-signature of the user identity and the keys by the primary key
+    pub fn self_signature(&self) -> &[u8] {
+        &self.self_signature
+    }
+
+    /// Get the primary key
+    ///
+    /// # Errors
+    /// If there is not exactly one primary key, or it is not a signing key (Ed25519).
+    pub fn primary_key(&self) -> cck_common::Result<&PublicKey> {
+        let mut primary_keys = self
+            .public_keys
+            .iter()
+            .filter(|public_key| public_key.is_primary());
+
+        let primary_key = primary_keys.next().ok_or(cck_common::Error)?;
+
+        if primary_keys.next().is_some() || !matches!(primary_key.key_type(), KeyType::Ed25519) {
+            Err(cck_common::Error)?
+        }
+
+        Ok(primary_key)
+    }
+
+    /// Verify the bundle
+    ///
+    /// # Errors
+    /// If there is no single primary key, the self-signature is invalid (e.g. the user, a key type or an expiry changed),
+    /// a fingerprint does not match its key, or a subkey is not signed by the primary key.
+    pub fn verify(&self) -> cck_common::Result<()> {
+        let primary_key = self.primary_key()?;
+
+        self.public_keys
+            .iter()
+            .try_for_each(|public_key| verify_key(primary_key, public_key))?;
+
+        verify_signature(
+            primary_key,
+            &self_signature_message(&self.user, &self.public_keys)?,
+            &self.self_signature,
+        )
+    }
+
+    /// Encode the bundle.
+    ///
+    /// Format: magic || version || id || name || email || self-signature (64 bytes) || key count (1 byte) || keys
+    ///
+    /// Each text is its length (2 bytes, big endian) || UTF-8 bytes.
+    ///
+    /// Each key: primary (1 byte) || key type (1 byte) || expiry (8 digits) || public key (32 bytes) ||
+    /// signature flag (1 byte) || signature (64 bytes, if the flag is 1).
+    ///
+    /// The fingerprints are not encoded: they are computed from the public keys on decode.
+    ///
+    /// # Errors
+    /// If a text is longer than 65535 bytes, there are more than 255 keys, or a key or signature has an invalid size.
+    pub fn encode(&self) -> cck_common::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(BUNDLE_MAGIC);
+
+        bytes.push(BUNDLE_VERSION);
+
+        for text in [self.user.id(), self.user.name(), self.user.email()] {
+            write_text(&mut bytes, text)?;
+        }
+
+        if self.self_signature.len() != SIZE_64 {
+            Err(cck_common::Error)?
+        }
+
+        bytes.extend_from_slice(&self.self_signature);
+
+        bytes.push(
+            self.public_keys
+                .len()
+                .try_into()
+                .map_err(|_| cck_common::Error)?,
+        );
+
+        for public_key in &self.public_keys {
+            write_key(&mut bytes, public_key)?;
+
+            match public_key.signature() {
+                Some(signature) if signature.len() == SIZE_64 => {
+                    bytes.push(1);
+                    bytes.extend_from_slice(signature);
+                }
+                Some(_) => Err(cck_common::Error)?,
+                None => bytes.push(0),
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    /// Decode a bundle (see `encode`). The bundle is not verified.
+    ///
+    /// # Errors
+    /// If the bytes are not an encoded bundle, or the user id, name or email is invalid.
+    pub fn decode(bytes: &[u8]) -> cck_common::Result<Self> {
+        let mut reader = Reader(bytes);
+
+        if reader.take(BUNDLE_MAGIC.len())? != BUNDLE_MAGIC || reader.take(1)? != [BUNDLE_VERSION] {
+            Err(cck_common::Error)?
+        }
+
+        let id = reader.text()?;
+        let name = reader.text()?;
+        let email = reader.text()?;
+
+        let user = User::from_parts(id, name, email)?;
+
+        let self_signature = reader.take(SIZE_64)?.to_vec();
+
+        let count = reader.take(1)?[0];
+
+        let mut public_keys = Vec::with_capacity(count.into());
+
+        for _ in 0..count {
+            let primary = match reader.take(1)?[0] {
+                0 => false,
+                1 => true,
+                _ => Err(cck_common::Error)?,
+            };
+
+            let key_type = match reader.take(1)?[0] {
+                0 => KeyType::Ed25519,
+                1 => KeyType::X25519,
+                _ => Err(cck_common::Error)?,
+            };
+
+            let expiry = reader.take(8)?;
+
+            if expiry.iter().any(|digit| *digit > 9) {
+                Err(cck_common::Error)?
+            }
+
+            let expiry = Expiry::from((
+                expiry[0], expiry[1], expiry[2], expiry[3], expiry[4], expiry[5], expiry[6],
+                expiry[7],
+            ));
+
+            let public_key = reader.take(SIZE_32)?.to_vec();
+
+            let signature = match reader.take(1)?[0] {
+                0 => None,
+                1 => Some(reader.take(SIZE_64)?.to_vec()),
+                _ => Err(cck_common::Error)?,
+            };
+
+            let fingerprint = cck_hash::blake3::digest(&public_key, &[]).to_vec();
+
+            public_keys.push(<PublicKey as Key>::from(
+                primary,
+                key_type,
+                expiry,
+                public_key,
+                fingerprint,
+                signature,
+            ));
+        }
+
+        if !reader.0.is_empty() {
+            Err(cck_common::Error)?
+        }
+
+        Ok(Self {
+            user,
+            public_keys,
+            self_signature,
+        })
+    }
+
+    /// Encode the bundle as a PEM block (label: `CCK CERTIFICATE`).
+    pub fn to_pem(&self) -> cck_common::Result<String> {
+        pem::encode_string(pem::PEM_LABEL_CCK_CERTIFICATE, &self.encode()?)
+    }
+
+    /// Decode a bundle from a PEM block (label: `CCK CERTIFICATE`). The bundle is not verified.
+    pub fn from_pem(pem: impl AsRef<[u8]>) -> cck_common::Result<Self> {
+        Self::decode(&pem::decode_vec(pem::PEM_LABEL_CCK_CERTIFICATE, pem)?)
+    }
+}
+
+/// The certification message of a user identity: domain || id || name || email || fingerprint of the primary key.
+///
+/// Each text is its length (8 bytes, big endian) || UTF-8 bytes.
+pub(crate) fn certification_message(user: &User, fingerprint: &[u8]) -> Vec<u8> {
+    let mut message = CERTIFICATION_DOMAIN.to_vec();
+
+    for text in [user.id(), user.name(), user.email()] {
+        message.extend_from_slice(&(text.len() as u64).to_be_bytes());
+        message.extend_from_slice(text.as_bytes());
+    }
+
+    message.extend_from_slice(fingerprint);
+
+    message
+}
+
+/// The message of the self-signature of a bundle: the certification message of the user (see `certification_message`)
+/// || each key: primary (1 byte) || key type (1 byte) || expiry (8 digits) || public key (32 bytes).
+fn self_signature_message(user: &User, public_keys: &[PublicKey]) -> cck_common::Result<Vec<u8>> {
+    let primary_key = public_keys
+        .iter()
+        .find(|public_key| public_key.is_primary())
+        .ok_or(cck_common::Error)?;
+
+    let mut message = certification_message(user, primary_key.fingerprint());
+
+    for public_key in public_keys {
+        write_key(&mut message, public_key)?;
+    }
+
+    Ok(message)
+}
+
+/// Verify a key of a bundle: its fingerprint and, for a subkey, its binding signature by the primary key.
+fn verify_key(primary_key: &PublicKey, public_key: &PublicKey) -> cck_common::Result<()> {
+    if public_key.as_bytes().len() != SIZE_32
+        || public_key.fingerprint() != cck_hash::blake3::digest(public_key.as_bytes(), &[])
+    {
+        Err(cck_common::Error)?
+    }
+
+    if public_key.is_primary() {
+        return Ok(());
+    }
+
+    verify_signature(
+        primary_key,
+        public_key.as_bytes(),
+        public_key.signature().ok_or(cck_common::Error)?,
+    )
+}
+
+/// Verify an Ed25519 signature of the given message by the given key.
+pub(crate) fn verify_signature(
+    public_key: &PublicKey,
+    message: &[u8],
+    signature: &[u8],
+) -> cck_common::Result<()> {
+    cck_asymmetric::ed25519::verify(
+        public_key
+            .as_bytes()
+            .try_into()
+            .map_err(|_| cck_common::Error)?,
+        message,
+        signature.try_into().map_err(|_| cck_common::Error)?,
+    )
+}
+
+/// Write a key: primary (1 byte) || key type (1 byte) || expiry (8 digits) || public key (32 bytes).
+fn write_key(bytes: &mut Vec<u8>, public_key: &PublicKey) -> cck_common::Result<()> {
+    bytes.push(public_key.is_primary() as u8);
+
+    bytes.push(match public_key.key_type() {
+        KeyType::Ed25519 => 0,
+        KeyType::X25519 => 1,
+    });
+
+    let expiry = public_key.expiry();
+    let (y0, y1, y2, y3) = expiry.year();
+    let (m0, m1) = expiry.month();
+    let (d0, d1) = expiry.day();
+
+    bytes.extend_from_slice(&[y0, y1, y2, y3, m0, m1, d0, d1]);
+
+    if public_key.as_bytes().len() != SIZE_32 {
+        Err(cck_common::Error)?
+    }
+
+    bytes.extend_from_slice(public_key.as_bytes());
+
+    Ok(())
+}
+
+fn write_text(bytes: &mut Vec<u8>, text: &str) -> cck_common::Result<()> {
+    let len: u16 = text.len().try_into().map_err(|_| cck_common::Error)?;
+
+    bytes.extend_from_slice(&len.to_be_bytes());
+
+    bytes.extend_from_slice(text.as_bytes());
+
+    Ok(())
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> cck_common::Result<&'a [u8]> {
+        if self.0.len() < len {
+            Err(cck_common::Error)?
+        }
+
+        let (bytes, rest) = self.0.split_at(len);
+
+        self.0 = rest;
+
+        Ok(bytes)
+    }
+
+    fn text(&mut self) -> cck_common::Result<String> {
+        let len = u16::from_be_bytes(self.take(2)?.try_into().map_err(|_| cck_common::Error)?);
+
+        String::from_utf8(self.take(len.into())?.to_vec()).map_err(|_| cck_common::Error)
+    }
+}
@@ -0,0 +1,306 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use crate::bundle::verify_signature;
+
+use cck_asymmetric::{Key, KeyType, PrivateKey, PublicKey};
+
+/// The domain of a certification: the message signed by the issuer starts with it.
+const CERTIFICATION_DOMAIN: &[u8] = b"CCK KEY CERTIFICATION\0";
+
+/// The weight a key needs to be valid: one certification by a fully trusted introducer,
+/// or two by marginally trusted introducers.
+const VALIDITY_THRESHOLD: u32 = 2;
+
+/// TrustLevel is an enum that represents how much the issuer of a certification trusts the certified key
+/// to certify other keys (to be an "introducer").
+///
+/// - *None: the key is certified, but its certifications are ignored.*
+///
+/// - *Marginal: two certifications by marginally trusted keys make a key valid.*
+///
+/// - *Full: one certification by a fully trusted key makes a key valid.*
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TrustLevel {
+    #[default]
+    None,
+    Marginal,
+    Full,
+}
+
+impl TrustLevel {
+    pub fn from_string(string: String) -> cck_common::Result<Self> {
+        match string {
+            string if string.eq_ignore_ascii_case("None") => Ok(Self::None),
+            string if string.eq_ignore_ascii_case("Marginal") => Ok(Self::Marginal),
+            string if string.eq_ignore_ascii_case("Full") => Ok(Self::Full),
+            _ => Err(cck_common::Error),
+        }
+    }
+
+    /// The weight of a certification by an introducer with this trust level.
+    fn weight(&self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Marginal => 1,
+            Self::Full => VALIDITY_THRESHOLD,
+        }
+    }
+
+    fn as_byte(&self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Marginal => 1,
+            Self::Full => 2,
+        }
+    }
+}
+
+impl Display for TrustLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => f.write_str("None"),
+            Self::Marginal => f.write_str("Marginal"),
+            Self::Full => f.write_str("Full"),
+        }
+    }
+}
+
+/// Certification
+///
+/// A signature by a primary key (the issuer) over another key's fingerprint and user id, with a trust level:
+/// the issuer vouches that the key belongs to the user, and trusts the key to certify others (see `TrustLevel`).
+///
+/// Stored by `Ring::insert_certification`, and used by `Ring::is_key_valid` to compute the validity of a key
+/// from a set of trusted roots.
+///
+/// # Example
+/// ```
+/// let certification = Certification::new(&alice_primary_key, bob.fingerprint(), bob.id(), TrustLevel::Full)?;
+///
+/// ring.insert_certification(&certification, &alice_primary_key.public_key())?;
+///
+/// let valid = ring.is_key_valid(bob.fingerprint(), &[alice_primary_key.fingerprint()], 2)?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certification {
+    pub(super) issuer: Vec<u8>,
+    pub(super) fingerprint: Vec<u8>,
+    pub(super) user_id: String,
+    pub(super) trust_level: TrustLevel,
+    pub(super) signature: Vec<u8>,
+}
+
+impl Certification {
+    /// Creates a certification of the key and user id, signed by the issuer.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer` - The primary signing key (Ed25519) of the issuer.
+    ///
+    /// * `fingerprint` - The fingerprint of the certified key.
+    ///
+    /// * `user_id` - The user id of the certified key.
+    ///
+    /// * `trust_level` - The trust of the issuer in the certified key as an introducer.
+    ///
+    /// # Errors
+    /// If the issuer is not a primary Ed25519 key, or certifies itself.
+    pub fn new(
+        issuer: &PrivateKey,
+        fingerprint: &[u8],
+        user_id: impl Into<String>,
+        trust_level: TrustLevel,
+    ) -> cck_common::Result<Self> {
+        if !issuer.is_primary()
+            || !matches!(issuer.key_type(), KeyType::Ed25519)
+            || issuer.fingerprint() == fingerprint
+        {
+            Err(cck_common::Error)?
+        }
+
+        let user_id = user_id.into();
+
+        let signature = cck_asymmetric::ed25519::sign(
+            issuer
+                .as_bytes()
+                .try_into()
+                .map_err(|_| cck_common::Error)?,
+            &certification_message(fingerprint, &user_id, trust_level),
+        )?
+        .to_vec();
+
+        Ok(Self {
+            issuer: issuer.fingerprint().to_vec(),
+            fingerprint: fingerprint.to_vec(),
+            user_id,
+            trust_level,
+            signature,
+        })
+    }
+
+    /// Creates a certification from its parts, e.g. received from elsewhere. It is verified on insert, or by `verify`.
+    pub fn from_parts(
+        issuer: Vec<u8>,
+        fingerprint: Vec<u8>,
+        user_id: impl Into<String>,
+        trust_level: TrustLevel,
+        signature: Vec<u8>,
+    ) -> Self {
+        Self {
+            issuer,
+            fingerprint,
+            user_id: user_id.into(),
+            trust_level,
+            signature,
+        }
+    }
+
+    /// Get the fingerprint of the issuer
+    pub fn issuer(&self) -> &[u8] {
+        &self.issuer
+    }
+
+    /// Get the fingerprint of the certified key
+    pub fn fingerprint(&self) -> &[u8] {
+        &self.fingerprint
+    }
+
+    /// Get the user id of the certified key
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    /// Get the trust level
+    pub fn trust_level(&self) -> TrustLevel {
+        self.trust_level
+    }
+
+    /// Get the signature by the issuer
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+
+    /// Verify the certification
+    ///
+    /// # Errors
+    /// If the issuer is not a primary Ed25519 key with the fingerprint of the issuer,
+    /// or the signature is invalid.
+    pub fn verify(&self, issuer: &PublicKey) -> cck_common::Result<()> {
+        if !issuer.is_primary()
+            || !matches!(issuer.key_type(), KeyType::Ed25519)
+            || issuer.fingerprint() != self.issuer
+            || issuer.fingerprint() != cck_hash::blake3::digest(issuer.as_bytes(), &[])
+            || self.issuer == self.fingerprint
+        {
+            Err(cck_common::Error)?
+        }
+
+        verify_signature(
+            issuer,
+            &certification_message(&self.fingerprint, &self.user_id, self.trust_level),
+            &self.signature,
+        )
+    }
+}
+
+/// The message of a certification: domain || fingerprint || user id || trust level (1 byte).
+///
+/// The fingerprint and user id are their length (8 bytes, big endian) || bytes.
+fn certification_message(fingerprint: &[u8], user_id: &str, trust_level: TrustLevel) -> Vec<u8> {
+    let mut message = CERTIFICATION_DOMAIN.to_vec();
+
+    for bytes in [fingerprint, user_id.as_bytes()] {
+        message.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+        message.extend_from_slice(bytes);
+    }
+
+    message.push(trust_level.as_byte());
+
+    message
+}
+
+/// The introducers of a key not yet valid, the trust level they give it, and the weight of the fully trusted ones.
+#[derive(Default)]
+struct Introduced<'a> {
+    issuers: HashSet<&'a [u8]>,
+    weight: u32,
+    full_weight: u32,
+    trust_level: TrustLevel,
+}
+
+/// Is the key valid: a root, or reachable from the roots through at most `max_depth` certifications.
+///
+/// The roots are fully trusted introducers. At each depth, a key becomes valid when the weights of the
+/// introducers (valid at a lower depth) that certify it reach `VALIDITY_THRESHOLD`, and it is then trusted
+/// as an introducer with the highest trust level of these certifications, capped at `Marginal` unless the fully
+/// trusted introducers alone reach `VALIDITY_THRESHOLD`: keys made valid by marginal introducers are at most
+/// marginal introducers, and another certification never lowers the trust level.
+pub(crate) fn is_key_valid(
+    certifications: &[Certification],
+    fingerprint: &[u8],
+    roots: &[impl AsRef<[u8]>],
+    max_depth: u32,
+) -> bool {
+    let mut valid: HashMap<&[u8], TrustLevel> = roots
+        .iter()
+        .map(|root| (root.as_ref(), TrustLevel::Full))
+        .collect();
+
+    for _ in 0..max_depth {
+        if valid.contains_key(fingerprint) {
+            break;
+        }
+
+        let mut introduced: HashMap<&[u8], Introduced> = HashMap::new();
+
+        for certification in certifications {
+            // A valid key trusted with `None` is not an introducer.
+            let introducer = match valid.get(certification.issuer.as_slice()) {
+                Some(introducer) if *introducer > TrustLevel::None => *introducer,
+                _ => continue,
+            };
+
+            if valid.contains_key(certification.fingerprint.as_slice()) {
+                continue;
+            }
+
+            let introduced = introduced.entry(&certification.fingerprint).or_default();
+
+            // Several user ids of the same key, certified by the same issuer, count once.
+            if introduced.issuers.insert(&certification.issuer) {
+                introduced.weight += introducer.weight();
+
+                if introducer == TrustLevel::Full {
+                    introduced.full_weight += introducer.weight();
+                }
+            }
+
+            introduced.trust_level = introduced.trust_level.max(certification.trust_level);
+        }
+
+        let mut found = false;
+
+        for (key, introduced) in introduced {
+            if introduced.weight >= VALIDITY_THRESHOLD {
+                let cap = if introduced.full_weight >= VALIDITY_THRESHOLD {
+                    TrustLevel::Full
+                } else {
+                    TrustLevel::Marginal
+                };
+
+                valid.insert(key, introduced.trust_level.min(cap));
+
+                found = true;
+            }
+        }
+
+        if !found {
+            break;
+        }
+    }
+
+    valid.contains_key(fingerprint)
+}
@@ -0,0 +1,204 @@
+use crate::{
+    bundle::{certification_message, verify_signature},
+    user::User,
+};
+
+use cck_asymmetric::{Key, KeyType, PrivateKey, PublicKey};
+use cck_format::hex;
+
+/// The domain of a revocation: the message signed by the primary key starts with it.
+const REVOCATION_DOMAIN: &[u8] = b"CCK USER ID REVOCATION\0";
+
+/// Identity
+///
+/// A user identity (name, email) bound to a primary signing key (Ed25519):
+/// the user id is derived from the fingerprint of the primary key, the name and the email,
+/// and certified by a signature of the primary key.
+///
+/// A primary key can carry several identities (e.g. a work and a personal email),
+/// each with its own certification, and each revoked independently by `revoke`.
+///
+/// # Example
+/// ```
+/// let work = Identity::new(&primary_key, "name", "name@work.com")?;
+///
+/// let personal = Identity::new(&primary_key, "name", "name@home.org")?;
+///
+/// ring.insert_identity(&work, &primary_key.public_key())?;
+///
+/// ring.insert_identity(&personal, &primary_key.public_key())?;
+///
+/// ring.insert_private_key(work.user().clone(), primary_key)?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub(super) user: User,
+    pub(super) fingerprint: Vec<u8>,
+    pub(super) certification: Vec<u8>,
+    pub(super) revocation: Option<Vec<u8>>,
+}
+
+impl Identity {
+    /// Creates an identity certified by the primary key.
+    ///
+    /// # Errors
+    /// If the primary key is not a primary Ed25519 key, or the email syntax is invalid.
+    pub fn new(
+        primary_key: &PrivateKey,
+        name: impl Into<String>,
+        email: impl Into<String>,
+    ) -> cck_common::Result<Self> {
+        validate_primary_key(primary_key)?;
+
+        let name = name.into();
+        let email = email.into();
+
+        let fingerprint = primary_key.fingerprint().to_vec();
+
+        let user = User::from_parts(identity_id(&fingerprint, &name, &email), name, email)?;
+
+        let certification = sign(primary_key, &certification_message(&user, &fingerprint))?;
+
+        Ok(Self {
+            user,
+            fingerprint,
+            certification,
+            revocation: None,
+        })
+    }
+
+    /// Creates an identity from its parts, e.g. received from elsewhere. It is verified on insert, or by `verify`.
+    pub fn from_parts(
+        user: impl Into<User>,
+        fingerprint: Vec<u8>,
+        certification: Vec<u8>,
+        revocation: Option<Vec<u8>>,
+    ) -> Self {
+        Self {
+            user: user.into(),
+            fingerprint,
+            certification,
+            revocation,
+        }
+    }
+
+    /// Get the user
+    pub fn user(&self) -> &User {
+        &self.user
+    }
+
+    /// Get the user id
+    pub fn id(&self) -> &str {
+        self.user.id()
+    }
+
+    /// Get the fingerprint of the primary key
+    pub fn fingerprint(&self) -> &[u8] {
+        &self.fingerprint
+    }
+
+    /// Get the certification of the identity by the primary key
+    pub fn certification(&self) -> &[u8] {
+        &self.certification
+    }
+
+    /// Get the revocation of the identity by the primary key, if revoked
+    pub fn revocation(&self) -> Option<&[u8]> {
+        self.revocation.as_deref()
+    }
+
+    /// Is the identity revoked
+    pub fn is_revoked(&self) -> bool {
+        self.revocation.is_some()
+    }
+
+    /// Revoke the identity
+    ///
+    /// The other identities of the primary key are unchanged.
+    ///
+    /// # Errors
+    /// If the primary key is not the primary key of the identity.
+    pub fn revoke(&mut self, primary_key: &PrivateKey) -> cck_common::Result<&mut Self> {
+        validate_primary_key(primary_key)?;
+
+        if primary_key.fingerprint() != self.fingerprint {
+            Err(cck_common::Error)?
+        }
+
+        self.revocation = Some(sign(primary_key, &self.revocation_message())?);
+
+        Ok(self)
+    }
+
+    /// Verify the identity
+    ///
+    /// # Errors
+    /// If the primary key is not a primary Ed25519 key with the fingerprint of the identity,
+    /// the user id is not derived from the fingerprint, name and email,
+    /// or the certification or revocation is not signed by the primary key.
+    pub fn verify(&self, primary_key: &PublicKey) -> cck_common::Result<()> {
+        if !primary_key.is_primary()
+            || !matches!(primary_key.key_type(), KeyType::Ed25519)
+            || primary_key.fingerprint() != self.fingerprint
+            || primary_key.fingerprint() != cck_hash::blake3::digest(primary_key.as_bytes(), &[])
+            || self.user.id() != identity_id(&self.fingerprint, self.user.name(), self.user.email())
+        {
+            Err(cck_common::Error)?
+        }
+
+        verify_signature(
+            primary_key,
+            &certification_message(&self.user, &self.fingerprint),
+            &self.certification,
+        )?;
+
+        match &self.revocation {
+            Some(revocation) => {
+                verify_signature(primary_key, &self.revocation_message(), revocation)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// The message of a revocation: domain || message of the certification.
+    fn revocation_message(&self) -> Vec<u8> {
+        let mut message = REVOCATION_DOMAIN.to_vec();
+
+        message.extend_from_slice(&certification_message(&self.user, &self.fingerprint));
+
+        message
+    }
+}
+
+/// The user id of an identity: the hex digest of the fingerprint of the primary key, the name and the email.
+///
+/// Each text is its length (8 bytes, big endian) || UTF-8 bytes.
+fn identity_id(fingerprint: &[u8], name: &str, email: &str) -> String {
+    let mut bytes = fingerprint.to_vec();
+
+    for text in [name, email] {
+        bytes.extend_from_slice(&(text.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(text.as_bytes());
+    }
+
+    hex::encode_string(&cck_hash::blake3::digest(&bytes, &[]))
+}
+
+fn validate_primary_key(primary_key: &PrivateKey) -> cck_common::Result<()> {
+    if !primary_key.is_primary() || !matches!(primary_key.key_type(), KeyType::Ed25519) {
+        Err(cck_common::Error)?
+    }
+
+    Ok(())
+}
+
+fn sign(primary_key: &PrivateKey, message: &[u8]) -> cck_common::Result<Vec<u8>> {
+    Ok(cck_asymmetric::ed25519::sign(
+        primary_key
+            .as_bytes()
+            .try_into()
+            .map_err(|_| cck_common::Error)?,
+        message,
+    )?
+    .to_vec())
+}
@@ -0,0 +1,227 @@
+use rusqlite as sqlite;
+
+use crate::sql;
+
+use cck_asymmetric::{Expiry, KeyType};
+
+/// The order of the keys returned by a `KeyQuery`.
+///
+/// Keys with the same value are ordered by fingerprint. By expiry, the keys that never expire come last
+/// (first if descending), as in `KeyQuery::expires_after`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyOrder {
+    Fingerprint,
+    KeyType,
+    Expiry,
+    Name,
+    Email,
+}
+
+impl KeyOrder {
+    fn columns(&self) -> &'static [&'static str] {
+        match self {
+            Self::Fingerprint => &["k.fingerprint"],
+            Self::KeyType => &["k.key_type"],
+            Self::Expiry => &[sql::SQL_KEY_QUERY_NEVER_EXPIRES, "k.expiry"],
+            Self::Name => &["u.name"],
+            Self::Email => &["u.email"],
+        }
+    }
+}
+
+impl Default for KeyOrder {
+    /// Default: `Fingerprint`
+    fn default() -> Self {
+        Self::Fingerprint
+    }
+}
+
+/// KeyQuery
+///
+/// Filters composed into a single parameterized query by `Ring::query_private_keys` and `Ring::query_public_keys`.
+///
+/// All filters must match. Without filters, all the keys match.
+///
+/// # Example
+/// ```
+/// let query = KeyQuery::new()
+///     .fingerprint_prefix("3fa1")
+///     .key_type(KeyType::Ed25519)
+///     .email_contains("@example.com")
+///     .order_by(KeyOrder::Expiry)
+///     .limit(10);
+///
+/// let public_keys = ring.query_public_keys(&query).unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyQuery {
+    fingerprint_prefix: Option<String>,
+    key_type: Option<KeyType>,
+    expires_after: Option<Expiry>,
+    expires_before: Option<Expiry>,
+    primary: Option<bool>,
+    name_contains: Option<String>,
+    email_contains: Option<String>,
+    order: KeyOrder,
+    descending: bool,
+    limit: Option<u32>,
+    offset: u32,
+}
+
+impl KeyQuery {
+    /// Creates a query that matches all the keys, ordered by fingerprint.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The fingerprint starts with the given hex digits (case-insensitive), e.g. a short id typed by a user.
+    ///
+    /// The query fails if the prefix is not hex.
+    pub fn fingerprint_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.fingerprint_prefix = Some(prefix.into().to_ascii_uppercase());
+        self
+    }
+
+    /// The key type matches.
+    pub fn key_type(mut self, key_type: KeyType) -> Self {
+        self.key_type = Some(key_type);
+        self
+    }
+
+    /// The key expires on or after the given date. A key that never expires matches.
+    pub fn expires_after(mut self, expiry: Expiry) -> Self {
+        self.expires_after = Some(expiry);
+        self
+    }
+
+    /// The key expires on or before the given date. A key that never expires does not match.
+    pub fn expires_before(mut self, expiry: Expiry) -> Self {
+        self.expires_before = Some(expiry);
+        self
+    }
+
+    /// The key is primary, or not.
+    pub fn primary(mut self, primary: bool) -> Self {
+        self.primary = Some(primary);
+        self
+    }
+
+    /// The name of the user contains the given text (ASCII case-insensitive).
+    pub fn name_contains(mut self, name: impl Into<String>) -> Self {
+        self.name_contains = Some(name.into());
+        self
+    }
+
+    /// The email of the user contains the given text (ASCII case-insensitive).
+    pub fn email_contains(mut self, email: impl Into<String>) -> Self {
+        self.email_contains = Some(email.into());
+        self
+    }
+
+    /// Order the keys by the given value, ascending.
+    pub fn order_by(mut self, order: KeyOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Order the keys descending.
+    pub fn descending(mut self) -> Self {
+        self.descending = true;
+        self
+    }
+
+    /// Return at most the given number of keys.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Skip the given number of keys.
+    pub fn offset(mut self, offset: u32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Build the query on the given select (`sql::SQL_SELECT_FROM_*_KEYS_JOIN_USERS`) and its parameters.
+    ///
+    /// Only the parameters carry values: the statement is built from constants.
+    ///
+    /// # Errors
+    /// If the fingerprint prefix is not hex.
+    pub(crate) fn sql(
+        &self,
+        select: &str,
+    ) -> cck_common::Result<(String, Vec<sqlite::types::Value>)> {
+        let mut conditions = Vec::new();
+        let mut params = Vec::new();
+
+        if let Some(prefix) = &self.fingerprint_prefix {
+            if !prefix.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+                Err(cck_common::Error)?
+            }
+
+            conditions.push(sql::SQL_KEY_QUERY_FINGERPRINT_PREFIX);
+            params.push(sqlite::types::Value::Integer(prefix.len() as i64));
+            params.push(sqlite::types::Value::Text(prefix.clone()));
+        }
+
+        if let Some(key_type) = &self.key_type {
+            conditions.push(sql::SQL_KEY_QUERY_KEY_TYPE);
+            params.push(sqlite::types::Value::Text(key_type.to_string()));
+        }
+
+        if let Some(expiry) = &self.expires_after {
+            conditions.push(sql::SQL_KEY_QUERY_EXPIRES_AFTER);
+            params.push(sqlite::types::Value::Text(expiry.to_string()));
+        }
+
+        if let Some(expiry) = &self.expires_before {
+            conditions.push(sql::SQL_KEY_QUERY_EXPIRES_BEFORE);
+            params.push(sqlite::types::Value::Text(expiry.to_string()));
+        }
+
+        if let Some(primary) = self.primary {
+            conditions.push(sql::SQL_KEY_QUERY_PRIMARY);
+            params.push(sqlite::types::Value::Integer(primary as i64));
+        }
+
+        if let Some(name) = &self.name_contains {
+            conditions.push(sql::SQL_KEY_QUERY_NAME_CONTAINS);
+            params.push(sqlite::types::Value::Text(name.clone()));
+        }
+
+        if let Some(email) = &self.email_contains {
+            conditions.push(sql::SQL_KEY_QUERY_EMAIL_CONTAINS);
+            params.push(sqlite::types::Value::Text(email.clone()));
+        }
+
+        let mut statement = select.to_string();
+
+        if !conditions.is_empty() {
+            statement.push_str(" WHERE ");
+            statement.push_str(&conditions.join(" AND "));
+        }
+
+        let direction = if self.descending { "DESC" } else { "ASC" };
+
+        let order = self
+            .order
+            .columns()
+            .iter()
+            .chain(["k.fingerprint"].iter())
+            .map(|column| format!("{} {}", column, direction))
+            .collect::<Vec<String>>();
+
+        statement.push_str(&format!(" ORDER BY {} ", order.join(", ")));
+
+        statement.push_str(sql::SQL_KEY_QUERY_LIMIT_OFFSET);
+        params.push(sqlite::types::Value::Integer(
+            self.limit.map_or(-1, i64::from),
+        ));
+        params.push(sqlite::types::Value::Integer(self.offset.into()));
+
+        statement.push(';');
+
+        Ok((statement, params))
+    }
+}
@@ -0,0 +1,124 @@
+use std::fmt::Display;
+
+/// KnownKey
+///
+/// The fingerprint pinned for a peer's user id the first time their key was seen (trust on first use).
+///
+/// Pinned by `Ring::pin_key`, changed only by `Ring::accept_new_known_key` or `Ring::replace_known_key`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownKey {
+    pub(super) user_id: String,
+    pub(super) fingerprint: Vec<u8>,
+    pub(super) pinned_at: String,
+}
+
+impl KnownKey {
+    /// Get the user id
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    /// Get the pinned fingerprint
+    pub fn fingerprint(&self) -> &[u8] {
+        &self.fingerprint
+    }
+
+    /// Get the date and time (UTC, `YYYY-MM-DD HH:MM:SS`) the fingerprint was pinned
+    pub fn pinned_at(&self) -> &str {
+        &self.pinned_at
+    }
+}
+
+/// KeyStatus is an enum that represents a presented fingerprint compared with the pinned one.
+///
+/// - *New: no fingerprint is pinned for the user id.*
+///
+/// - *Known: the fingerprint is the pinned one.*
+///
+/// - *Changed: another fingerprint is pinned for the user id: warn, or fail.*
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyStatus {
+    New,
+    Known,
+    Changed { pinned: Vec<u8> },
+}
+
+/// KnownKeyAction is an enum that represents a change of the table known_keys.
+///
+/// - *Pin: a fingerprint was pinned the first time it was seen.*
+///
+/// - *AcceptNew: a changed fingerprint was accepted.*
+///
+/// - *Replace: the pinned fingerprint was replaced by another one.*
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KnownKeyAction {
+    Pin,
+    AcceptNew,
+    Replace,
+}
+
+impl KnownKeyAction {
+    pub fn from_string(string: String) -> cck_common::Result<Self> {
+        match string {
+            string if string.eq_ignore_ascii_case("Pin") => Ok(Self::Pin),
+            string if string.eq_ignore_ascii_case("AcceptNew") => Ok(Self::AcceptNew),
+            string if string.eq_ignore_ascii_case("Replace") => Ok(Self::Replace),
+            _ => Err(cck_common::Error),
+        }
+    }
+}
+
+impl Display for KnownKeyAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pin => f.write_str("Pin"),
+            Self::AcceptNew => f.write_str("AcceptNew"),
+            Self::Replace => f.write_str("Replace"),
+        }
+    }
+}
+
+/// KnownKeyAudit
+///
+/// A record of a change of the table known_keys: who changed which fingerprint of a user id, and when.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownKeyAudit {
+    pub(super) user_id: String,
+    pub(super) action: KnownKeyAction,
+    pub(super) old_fingerprint: Option<Vec<u8>>,
+    pub(super) new_fingerprint: Vec<u8>,
+    pub(super) changed_by: String,
+    pub(super) changed_at: String,
+}
+
+impl KnownKeyAudit {
+    /// Get the user id
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
+
+    /// Get the action
+    pub fn action(&self) -> KnownKeyAction {
+        self.action
+    }
+
+    /// Get the fingerprint pinned before the change, if any
+    pub fn old_fingerprint(&self) -> Option<&[u8]> {
+        self.old_fingerprint.as_deref()
+    }
+
+    /// Get the fingerprint pinned by the change
+    pub fn new_fingerprint(&self) -> &[u8] {
+        &self.new_fingerprint
+    }
+
+    /// Get who made the change
+    pub fn changed_by(&self) -> &str {
+        &self.changed_by
+    }
+
+    /// Get the date and time (UTC, `YYYY-MM-DD HH:MM:SS`) of the change
+    pub fn changed_at(&self) -> &str {
+        &self.changed_at
+    }
+}
@@ -1,5 +1,19 @@
 mod user;
 mod ring;
 mod sql;
+mod query;
+mod transaction;
+mod bundle;
+mod key_query;
+mod identity;
+mod certification;
+mod known_key;
 pub use user::*;
-pub use ring::*;
\ No newline at end of file
+pub use ring::*;
+pub use transaction::*;
+pub use bundle::*;
+pub use key_query::*;
+pub use identity::*;
+pub use certification::*;
+pub use known_key::*;
+pub use sql::SCHEMA_VERSION;
\ No newline at end of file
@@ -0,0 +1,599 @@
+use rusqlite as sqlite;
+
+use crate::{
+    bundle::Bundle,
+    certification::{Certification, TrustLevel},
+    identity::Identity,
+    known_key::{KeyStatus, KnownKey, KnownKeyAction, KnownKeyAudit},
+    sql,
+    user::User,
+};
+
+use cck_asymmetric::{Expiry, Fingerprint, Key, KeyId, KeyType, PrivateKey, PublicKey};
+use cck_common::size::SIZE_32;
+use cck_symmetric::{Algorithm, SymmetricKey};
+
+/*
+    Queries shared by `Ring` and `Transaction`
+*/
+
+/// Insert a new user into the table users
+pub(crate) fn insert_user(
+    conn: &sqlite::Connection,
+    user: impl Into<User>,
+) -> cck_common::Result<()> {
+    let user = user.into();
+
+    conn.execute(
+        sql::SQL_INSERT_INTO_USERS,
+        sqlite::params![user.id(), user.name(), user.email()],
+    )
+    .map_err(|_| cck_common::Error)?;
+
+    Ok(())
+}
+
+/// Insert a new private_key into the table private_keys
+pub(crate) fn insert_private_key(
+    conn: &sqlite::Connection,
+    user: impl Into<User>,
+    private_key: impl Into<PrivateKey>,
+) -> cck_common::Result<()> {
+    let user = user.into();
+    let private_key = private_key.into();
+
+    conn.execute(
+        sql::SQL_INSERT_INTO_PRIVATE_KEYS,
+        sqlite::params![
+            user.id(),
+            private_key.is_primary() as i32,
+            private_key.key_type().to_string(),
+            private_key.expiry().to_string(),
+            private_key.as_bytes(),
+            private_key.fingerprint(),
+            private_key.signature()
+        ],
+    )
+    .map_err(|_| cck_common::Error)?;
+
+    Ok(())
+}
+
+/// Insert a new public_key into the table public_keys
+pub(crate) fn insert_public_key(
+    conn: &sqlite::Connection,
+    user: impl Into<User>,
+    public_key: impl Into<PublicKey>,
+) -> cck_common::Result<()> {
+    let user = user.into();
+    let public_key = public_key.into();
+    conn.execute(
+        sql::SQL_INSERT_INTO_PUBLIC_KEYS,
+        sqlite::params![
+            user.id(),
+            public_key.is_primary() as i32,
+            public_key.key_type().to_string(),
+            public_key.expiry().to_string(),
+            public_key.as_bytes(),
+            public_key.fingerprint(),
+            public_key.signature()
+        ],
+    )
+    .map_err(|_| cck_common::Error)?;
+
+    Ok(())
+}
+
+/// Insert a new symmetric_key into the table symmetric_keys
+pub(crate) fn insert_symmetric_key(
+    conn: &sqlite::Connection,
+    symmetric_key: &SymmetricKey,
+) -> cck_common::Result<()> {
+    conn.execute(
+        sql::SQL_INSERT_INTO_SYMMETRIC_KEYS,
+        sqlite::params![
+            symmetric_key.id(),
+            symmetric_key.algorithm().to_string(),
+            symmetric_key.as_bytes()
+        ],
+    )
+    .map_err(|_| cck_common::Error)?;
+
+    Ok(())
+}
+
+/// Insert a new identity into the tables users and identities
+///
+/// The identity is verified with the primary key before it is inserted (see `Identity::verify`).
+pub(crate) fn insert_identity(
+    conn: &sqlite::Connection,
+    identity: &Identity,
+    primary_key: &PublicKey,
+) -> cck_common::Result<()> {
+    identity.verify(primary_key)?;
+
+    insert_user(conn, identity.user().clone())?;
+
+    conn.execute(
+        sql::SQL_INSERT_INTO_IDENTITIES,
+        sqlite::params![
+            identity.id(),
+            identity.fingerprint(),
+            identity.certification(),
+            identity.revocation()
+        ],
+    )
+    .map_err(|_| cck_common::Error)?;
+
+    Ok(())
+}
+
+/// Insert a new certification into the table certifications
+///
+/// The certification is verified with the issuer before it is inserted (see `Certification::verify`).
+pub(crate) fn insert_certification(
+    conn: &sqlite::Connection,
+    certification: &Certification,
+    issuer: &PublicKey,
+) -> cck_common::Result<()> {
+    certification.verify(issuer)?;
+
+    conn.execute(
+        sql::SQL_INSERT_INTO_CERTIFICATIONS,
+        sqlite::params![
+            certification.issuer(),
+            certification.fingerprint(),
+            certification.user_id(),
+            certification.trust_level().to_string(),
+            certification.signature()
+        ],
+    )
+    .map_err(|_| cck_common::Error)?;
+
+    Ok(())
+}
+
+/// Insert a new bundle into the table bundles
+///
+/// The bundle is stored as signed, so that it can be exported again (see `Ring::export_bundle`).
+pub(crate) fn insert_bundle(conn: &sqlite::Connection, bundle: &Bundle) -> cck_common::Result<()> {
+    conn.execute(
+        sql::SQL_INSERT_INTO_BUNDLES,
+        sqlite::params![bundle.user().id(), bundle.encode()?],
+    )
+    .map_err(|_| cck_common::Error)?;
+
+    Ok(())
+}
+
+/// Get a bundle from the table bundles where the user id matches
+pub(crate) fn get_bundle_where_user_id_matches(
+    conn: &sqlite::Connection,
+    id: impl Into<String>,
+) -> cck_common::Result<Bundle> {
+    let bytes: Vec<u8> = conn
+        .query_row(
+            sql::SQL_SELECT_FROM_BUNDLES_WHERE_USER_ID,
+            sqlite::params![id.into()],
+            |row| row.get(0),
+        )
+        .map_err(|_| cck_common::Error)?;
+
+    Bundle::decode(&bytes)
+}
+
+/// Get a user from the table users where the id matches
+pub(crate) fn get_user_where_id_matches(
+    conn: &sqlite::Connection,
+    id: impl Into<String>,
+) -> cck_common::Result<User> {
+    conn.query_row(
+        sql::SQL_SELECT_FROM_USERS_WHERE_ID,
+        sqlite::params![id.into()],
+        user_from_row,
+    )
+    .map_err(|_| cck_common::Error)
+}
+
+/// Get a user from the table users where the email matches
+pub(crate) fn get_user_where_email_matches(
+    conn: &sqlite::Connection,
+    email: impl Into<String>,
+) -> cck_common::Result<User> {
+    conn.query_row(
+        sql::SQL_SELECT_FROM_USERS_WHERE_EMAIL,
+        sqlite::params![email.into()],
+        user_from_row,
+    )
+    .map_err(|_| cck_common::Error)
+}
+
+/// Get a user from the table users where the name matches
+pub(crate) fn get_users_where_name_matches(
+    conn: &sqlite::Connection,
+    name: impl Into<String>,
+) -> cck_common::Result<Vec<User>> {
+    query_all(
+        conn,
+        sql::SQL_SELECT_FROM_USERS_WHERE_NAME,
+        sqlite::params![name.into()],
+        user_from_row,
+    )
+}
+
+/// Get a private_key from the table private_keys where the user and fingerprint matches
+pub(crate) fn get_private_key_where_user_and_fingerprint_matches(
+    conn: &sqlite::Connection,
+    user: impl Into<User>,
+    fingerprint: &[u8],
+) -> cck_common::Result<PrivateKey> {
+    conn.query_row(
+        sql::SQL_SELECT_FROM_PRIVATE_KEYS_WHERE_USER_ID_AND_FINGERPRINT,
+        sqlite::params![user.into().id(), fingerprint],
+        key_from_row,
+    )
+    .map_err(|_| cck_common::Error)
+}
+
+/// Get a private_key from the table private_keys where the fingerprint matches
+pub(crate) fn get_private_key_where_fingerprint_matches(
+    conn: &sqlite::Connection,
+    fingerprint: &[u8],
+) -> cck_common::Result<PrivateKey> {
+    conn.query_row(
+        sql::SQL_SELECT_FROM_PRIVATE_KEYS_WHERE_FINGERPRINT,
+        sqlite::params![fingerprint],
+        key_from_row,
+    )
+    .map_err(|_| cck_common::Error)
+}
+
+/// Get the private_keys from the table private_keys where the user matches
+pub(crate) fn get_private_keys_where_user_matches(
+    conn: &sqlite::Connection,
+    user: impl Into<User>,
+) -> cck_common::Result<Vec<PrivateKey>> {
+    query_all(
+        conn,
+        sql::SQL_SELECT_FROM_PRIVATE_KEYS_WHERE_USER_ID,
+        sqlite::params![user.into().id()],
+        key_from_row,
+    )
+}
+
+/// Get a public_key from the table public_keys where the user and fingerprint matches
+pub(crate) fn get_public_key_where_user_and_fingerprint_matches(
+    conn: &sqlite::Connection,
+    user: impl Into<User>,
+    fingerprint: &[u8],
+) -> cck_common::Result<PublicKey> {
+    conn.query_row(
+        sql::SQL_SELECT_FROM_PUBLIC_KEYS_WHERE_USER_ID_AND_FINGERPRINT,
+        sqlite::params![user.into().id(), fingerprint],
+        key_from_row,
+    )
+    .map_err(|_| cck_common::Error)
+}
+
+/// Get the public_keys from the table public_keys where the user matches
+pub(crate) fn get_public_keys_where_user_matches(
+    conn: &sqlite::Connection,
+    user: impl Into<User>,
+) -> cck_common::Result<Vec<PublicKey>> {
+    query_all(
+        conn,
+        sql::SQL_SELECT_FROM_PUBLIC_KEYS_WHERE_USER_ID,
+        sqlite::params![user.into().id()],
+        key_from_row,
+    )
+}
+
+/// Get a public_key from the table public_keys where the fingerprint matches
+pub(crate) fn get_public_key_where_fingerprint_matches(
+    conn: &sqlite::Connection,
+    fingerprint: &[u8],
+) -> cck_common::Result<PublicKey> {
+    conn.query_row(
+        sql::SQL_SELECT_FROM_PUBLIC_KEYS_WHERE_FINGERPRINT,
+        sqlite::params![fingerprint],
+        key_from_row,
+    )
+    .map_err(|_| cck_common::Error)
+}
+
+/// Get a symmetric_key from the table symmetric_keys where the id matches
+pub(crate) fn get_symmetric_key_where_id_matches(
+    conn: &sqlite::Connection,
+    id: &[u8],
+) -> cck_common::Result<SymmetricKey> {
+    let mut stmt = conn
+        .prepare(sql::SQL_SELECT_FROM_SYMMETRIC_KEYS_WHERE_ID)
+        .map_err(|_| cck_common::Error)?;
+
+    let mut rows = stmt
+        .query_map(sqlite::params![id], |row| {
+            Ok((row.get::<_, String>(1)?, row.get::<_, Vec<u8>>(2)?))
+        })
+        .map_err(|_| cck_common::Error)?;
+
+    let (algorithm, symmetric_key) = rows
+        .next()
+        .ok_or(cck_common::Error)?
+        .map_err(|_| cck_common::Error)?;
+
+    SymmetricKey::from_bytes(algorithm.parse::<Algorithm>()?, &symmetric_key)
+}
+
+/// Get an identity from the tables identities and users where the user id matches
+pub(crate) fn get_identity_where_id_matches(
+    conn: &sqlite::Connection,
+    id: impl Into<String>,
+) -> cck_common::Result<Identity> {
+    conn.query_row(
+        sql::SQL_SELECT_FROM_IDENTITIES_WHERE_USER_ID,
+        sqlite::params![id.into()],
+        identity_from_row,
+    )
+    .map_err(|_| cck_common::Error)
+}
+
+/// Get the identities from the tables identities and users where the fingerprint of the primary key matches
+pub(crate) fn get_identities_where_fingerprint_matches(
+    conn: &sqlite::Connection,
+    fingerprint: &[u8],
+) -> cck_common::Result<Vec<Identity>> {
+    query_all(
+        conn,
+        sql::SQL_SELECT_FROM_IDENTITIES_WHERE_FINGERPRINT,
+        sqlite::params![fingerprint],
+        identity_from_row,
+    )
+}
+
+/// Get the certifications from the table certifications where the certified fingerprint matches
+pub(crate) fn get_certifications_where_fingerprint_matches(
+    conn: &sqlite::Connection,
+    fingerprint: &[u8],
+) -> cck_common::Result<Vec<Certification>> {
+    query_all(
+        conn,
+        sql::SQL_SELECT_FROM_CERTIFICATIONS_WHERE_FINGERPRINT,
+        sqlite::params![fingerprint],
+        certification_from_row,
+    )
+}
+
+/// Get the certifications from the table certifications where the issuer matches
+pub(crate) fn get_certifications_where_issuer_matches(
+    conn: &sqlite::Connection,
+    issuer: &[u8],
+) -> cck_common::Result<Vec<Certification>> {
+    query_all(
+        conn,
+        sql::SQL_SELECT_FROM_CERTIFICATIONS_WHERE_ISSUER,
+        sqlite::params![issuer],
+        certification_from_row,
+    )
+}
+
+/// Get the known key from the table known_keys where the user id matches, if any
+pub(crate) fn get_known_key_where_user_id_matches(
+    conn: &sqlite::Connection,
+    user_id: &str,
+) -> cck_common::Result<Option<KnownKey>> {
+    let mut stmt = conn
+        .prepare(sql::SQL_SELECT_FROM_KNOWN_KEYS_WHERE_USER_ID)
+        .map_err(|_| cck_common::Error)?;
+
+    let mut rows = stmt
+        .query_map(sqlite::params![user_id], known_key_from_row)
+        .map_err(|_| cck_common::Error)?;
+
+    rows.next().transpose().map_err(|_| cck_common::Error)
+}
+
+/// Compare the fingerprint with the known key of the user id
+pub(crate) fn known_key_status(
+    conn: &sqlite::Connection,
+    user_id: &str,
+    fingerprint: &[u8],
+) -> cck_common::Result<KeyStatus> {
+    Ok(match get_known_key_where_user_id_matches(conn, user_id)? {
+        None => KeyStatus::New,
+        Some(known_key) if known_key.fingerprint() == fingerprint => KeyStatus::Known,
+        Some(known_key) => KeyStatus::Changed {
+            pinned: known_key.fingerprint,
+        },
+    })
+}
+
+/// Insert a new change into the table known_keys_audit
+///
+/// # Errors
+/// If who made the change is empty, or the insert fails.
+pub(crate) fn insert_known_key_audit(
+    conn: &sqlite::Connection,
+    user_id: &str,
+    action: KnownKeyAction,
+    old_fingerprint: Option<&[u8]>,
+    new_fingerprint: &[u8],
+    changed_by: &str,
+) -> cck_common::Result<()> {
+    if changed_by.is_empty() {
+        Err(cck_common::Error)?
+    }
+
+    conn.execute(
+        sql::SQL_INSERT_INTO_KNOWN_KEYS_AUDIT,
+        sqlite::params![
+            user_id,
+            action.to_string(),
+            old_fingerprint,
+            new_fingerprint,
+            changed_by
+        ],
+    )
+    .map_err(|_| cck_common::Error)?;
+
+    Ok(())
+}
+
+/// Get the keys where the fingerprint matches a textual form (see `Fingerprint::parse`),
+/// or the key id matches (see `KeyId::parse`)
+///
+/// # Arguments
+///
+/// * `select_fingerprint` - The select of the keys where the fingerprint matches.
+///
+/// * `select_key_id` - The select of the keys where the key id matches.
+///
+/// # Errors
+/// If the text is neither a fingerprint nor a key id.
+pub(crate) fn get_keys_where_fingerprint_text_matches<K: Key>(
+    conn: &sqlite::Connection,
+    text: &str,
+    select_fingerprint: &str,
+    select_key_id: &str,
+) -> cck_common::Result<Vec<K>> {
+    if let Ok(fingerprint) = Fingerprint::parse(text) {
+        return query_all(
+            conn,
+            select_fingerprint,
+            sqlite::params![fingerprint.as_bytes()],
+            key_from_row,
+        );
+    }
+
+    let key_id = KeyId::parse(text)?;
+
+    query_all(
+        conn,
+        select_key_id,
+        sqlite::params![key_id.as_bytes()],
+        key_from_row,
+    )
+}
+
+/// Map a row (id, name, email) of the table users to a user.
+pub(crate) fn user_from_row(row: &sqlite::Row) -> sqlite::Result<User> {
+    Ok(User {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        email: row.get(2)?,
+    })
+}
+
+/// Map a row (id, name, email, fingerprint, certification, revocation) of the tables users and identities to an identity.
+pub(crate) fn identity_from_row(row: &sqlite::Row) -> sqlite::Result<Identity> {
+    Ok(Identity {
+        user: user_from_row(row)?,
+        fingerprint: row.get(3)?,
+        certification: row.get(4)?,
+        revocation: row.get(5)?,
+    })
+}
+
+/// Map a row (issuer, fingerprint, user_id, trust_level, signature) of the table certifications to a certification.
+pub(crate) fn certification_from_row(row: &sqlite::Row) -> sqlite::Result<Certification> {
+    let trust_level = TrustLevel::from_string(row.get(3)?).map_err(|_| {
+        sqlite::Error::FromSqlConversionFailure(
+            3,
+            sqlite::types::Type::Text,
+            "invalid trust_level".to_string().into(),
+        )
+    })?;
+
+    Ok(Certification {
+        issuer: row.get(0)?,
+        fingerprint: row.get(1)?,
+        user_id: row.get(2)?,
+        trust_level,
+        signature: row.get(4)?,
+    })
+}
+
+/// Map a row (user_id, fingerprint, pinned_at) of the table known_keys to a known key.
+pub(crate) fn known_key_from_row(row: &sqlite::Row) -> sqlite::Result<KnownKey> {
+    Ok(KnownKey {
+        user_id: row.get(0)?,
+        fingerprint: row.get(1)?,
+        pinned_at: row.get(2)?,
+    })
+}
+
+/// Map a row (user_id, action, old_fingerprint, new_fingerprint, changed_by, changed_at) of the table known_keys_audit to a change.
+pub(crate) fn known_key_audit_from_row(row: &sqlite::Row) -> sqlite::Result<KnownKeyAudit> {
+    let action = KnownKeyAction::from_string(row.get(1)?).map_err(|_| {
+        sqlite::Error::FromSqlConversionFailure(
+            1,
+            sqlite::types::Type::Text,
+            "invalid action".to_string().into(),
+        )
+    })?;
+
+    Ok(KnownKeyAudit {
+        user_id: row.get(0)?,
+        action,
+        old_fingerprint: row.get(2)?,
+        new_fingerprint: row.get(3)?,
+        changed_by: row.get(4)?,
+        changed_at: row.get(5)?,
+    })
+}
+
+/// Map a row of the table private_keys or public_keys to a key.
+///
+/// Columns: user_id, is_primary, key_type, expiry, private_key | public_key, fingerprint, signature
+///
+/// # Errors
+/// If a column has an unexpected type, or the key type, expiry or key size is invalid.
+pub(crate) fn key_from_row<K: Key>(row: &sqlite::Row) -> sqlite::Result<K> {
+    let invalid = |column: usize, message: &str| {
+        sqlite::Error::FromSqlConversionFailure(
+            column,
+            sqlite::types::Type::Text,
+            message.to_string().into(),
+        )
+    };
+
+    let primary = match row.get::<_, i64>(1)? {
+        0 => false,
+        1 => true,
+        _ => Err(invalid(1, "invalid is_primary"))?,
+    };
+
+    let key_type = KeyType::from_string(row.get(2)?).map_err(|_| invalid(2, "invalid key_type"))?;
+
+    let expiry = Expiry::from_string(row.get(3)?).map_err(|_| invalid(3, "invalid expiry"))?;
+
+    let key: Vec<u8> = row.get(4)?;
+
+    // Ed25519 and X25519 keys are 32 bytes.
+    if key.len() != SIZE_32 {
+        Err(invalid(4, "invalid key size"))?
+    }
+
+    Ok(K::from(
+        primary,
+        key_type,
+        expiry,
+        key,
+        row.get(5)?,
+        row.get(6)?,
+    ))
+}
+
+/// Run a query and map all the rows.
+pub(crate) fn query_all<T>(
+    conn: &sqlite::Connection,
+    sql: &str,
+    params: impl sqlite::Params,
+    f: impl FnMut(&sqlite::Row) -> sqlite::Result<T>,
+) -> cck_common::Result<Vec<T>> {
+    let mut stmt = conn.prepare(sql).map_err(|_| cck_common::Error)?;
+
+    let rows = stmt.query_map(params, f).map_err(|_| cck_common::Error)?;
+
+    rows.collect::<sqlite::Result<Vec<T>>>()
+        .map_err(|_| cck_common::Error)
+}
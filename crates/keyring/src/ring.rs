@@ -3,11 +3,18 @@ use std::path::Path;
 use rusqlite as sqlite;
 
 use crate::{
-    sql::{self, SQL_INSERT_INTO_PRIVATE_KEYS, SQL_INSERT_INTO_PUBLIC_KEYS},
+    bundle::Bundle,
+    certification::{self, Certification},
+    identity::Identity,
+    key_query::KeyQuery,
+    known_key::{KeyStatus, KnownKey, KnownKeyAction, KnownKeyAudit},
+    query, sql,
+    transaction::Transaction,
     user::User,
 };
 
-use cck_asymmetric::{Expiry, Key, KeyType, PrivateKey, PublicKey};
+use cck_asymmetric::{Expiry, Key, PrivateKey, PublicKey};
+use cck_symmetric::{SymmetricKey, UnwrappingKey, WrappedKey, WrappingKey};
 
 /// RingBuilder
 pub struct RingBuilder(Ring);
@@ -62,17 +69,27 @@ pub struct Ring(sqlite::Connection);
 
 impl Ring {
     /// Open database.
+    ///
+    /// The foreign keys are enforced on the connection.
     pub fn open(path: &Path) -> cck_common::Result<Self> {
-        Ok(Self(
-            sqlite::Connection::open(path).map_err(|_| cck_common::Error)?,
-        ))
+        Self::with_connection(sqlite::Connection::open(path).map_err(|_| cck_common::Error)?)
     }
 
     /// Open database in memory.
     pub fn open_in_memory() -> cck_common::Result<Self> {
-        Ok(Self(
-            sqlite::Connection::open_in_memory().map_err(|_| cck_common::Error)?,
-        ))
+        Self::with_connection(sqlite::Connection::open_in_memory().map_err(|_| cck_common::Error)?)
+    }
+
+    fn with_connection(conn: sqlite::Connection) -> cck_common::Result<Self> {
+        conn.execute_batch(sql::SQL_PRAGMA_FOREIGN_KEYS_ON)
+            .map_err(|_| cck_common::Error)?;
+
+        Ok(Self(conn))
+    }
+
+    /// Returns the schema version of the database (`SCHEMA_VERSION` once built by `RingBuilder`).
+    pub fn schema_version(&self) -> cck_common::Result<u32> {
+        schema_version(&self.0)
     }
 
     /// Close database
@@ -84,22 +101,96 @@ impl Ring {
         self.0.close().map_err(|_| cck_common::Error)
     }
 
+    /*
+        Transaction
+    */
+
+    /// Run the given closure in a transaction
+    ///
+    /// Committed if the closure returns `Ok`, rolled back otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// ring.transaction(|tx| {
+    ///     tx.insert_user(user.clone())?;
+    ///
+    ///     tx.insert_private_key(user.clone(), private_key.clone())
+    /// })?;
+    /// ```
+    ///
+    /// # Errors
+    /// If the closure returns an error, or the commit fails.
+    pub fn transaction<T>(
+        &mut self,
+        f: impl FnOnce(&mut Transaction) -> cck_common::Result<T>,
+    ) -> cck_common::Result<T> {
+        let mut tx = Transaction(self.0.transaction().map_err(|_| cck_common::Error)?);
+
+        // Rolled back on drop if the closure fails.
+        let value = f(&mut tx)?;
+
+        tx.0.commit().map_err(|_| cck_common::Error)?;
+
+        Ok(value)
+    }
+
+    /// Import a bundle: the user, the public keys and the bundle with its signatures, in a single transaction
+    ///
+    /// # Errors
+    /// If the bundle is invalid (see `Bundle::verify`), the user or a key already exists:
+    /// nothing is imported.
+    pub fn import_bundle(&mut self, bundle: &Bundle) -> cck_common::Result<()> {
+        self.transaction(|tx| tx.import_bundle(bundle))
+    }
+
+    /// Export a user with their public keys as a bundle, signed by their primary private key
+    ///
+    /// The subkeys are the other keys of the user, from the tables public_keys and private_keys.
+    ///
+    /// Without the primary private key, the bundle of the user imported by `import_bundle` is exported as signed.
+    ///
+    /// # Errors
+    /// If the user does not exist, has neither a primary private key nor an imported bundle,
+    /// or a subkey is not signed by the primary key (see `Bundle::new`).
+    pub fn export_bundle(&self, user: impl Into<User>) -> cck_common::Result<Bundle> {
+        let user = self.get_user_where_id_matches(user.into().id())?;
+
+        let private_keys = self.get_private_keys_where_user_matches(user.clone())?;
+
+        let primary_key = match private_keys
+            .iter()
+            .find(|private_key| private_key.is_primary())
+        {
+            Some(primary_key) => primary_key,
+            None => return query::get_bundle_where_user_id_matches(&self.0, user.id()),
+        };
+
+        let mut subkeys: Vec<PublicKey> = Vec::new();
+
+        let public_keys = self.get_public_keys_where_user_matches(user.clone())?;
+
+        let private_public_keys = private_keys.iter().map(|private_key| private_key.public_key());
+
+        for public_key in public_keys.into_iter().chain(private_public_keys) {
+            if public_key.fingerprint() != primary_key.fingerprint()
+                && subkeys
+                    .iter()
+                    .all(|subkey| subkey.fingerprint() != public_key.fingerprint())
+            {
+                subkeys.push(public_key);
+            }
+        }
+
+        Bundle::new(user, primary_key, subkeys)
+    }
+
     /*
         Insert
     */
 
     /// Insert a new user into the table users
     pub fn insert_user(&mut self, user: impl Into<User>) -> cck_common::Result<()> {
-        let user = user.into();
-
-        self.0
-            .execute(
-                sql::SQL_INSERT_INTO_USERS,
-                sqlite::params![user.id(), user.name(), user.email()],
-            )
-            .map_err(|_| cck_common::Error)?;
-
-        Ok(())
+        query::insert_user(&self.0, user)
     }
 
     /// Insert a new private_key into the table private_keys
@@ -108,25 +199,7 @@ impl Ring {
         user: impl Into<User>,
         private_key: impl Into<PrivateKey>,
     ) -> cck_common::Result<()> {
-        let user = user.into();
-        let private_key = private_key.into();
-
-        self.0
-            .execute(
-                SQL_INSERT_INTO_PRIVATE_KEYS,
-                sqlite::params![
-                    user.id(),
-                    private_key.is_primary() as i32,
-                    private_key.key_type().to_string(),
-                    private_key.expiry().to_string(),
-                    private_key.as_bytes(),
-                    private_key.fingerprint(),
-                    private_key.signature()
-                ],
-            )
-            .map_err(|_| cck_common::Error)?;
-
-        Ok(())
+        query::insert_private_key(&self.0, user, private_key)
     }
 
     /// Insert a new public_key into the table public_keys
@@ -135,24 +208,36 @@ impl Ring {
         user: impl Into<User>,
         public_key: impl Into<PublicKey>,
     ) -> cck_common::Result<()> {
-        let user = user.into();
-        let public_key = public_key.into();
-        self.0
-            .execute(
-                SQL_INSERT_INTO_PUBLIC_KEYS,
-                sqlite::params![
-                    user.id(),
-                    public_key.is_primary() as i32,
-                    public_key.key_type().to_string(),
-                    public_key.expiry().to_string(),
-                    public_key.as_bytes(),
-                    public_key.fingerprint(),
-                    public_key.signature()
-                ],
-            )
-            .map_err(|_| cck_common::Error)?;
+        query::insert_public_key(&self.0, user, public_key)
+    }
 
-        Ok(())
+    /// Insert a new symmetric_key into the table symmetric_keys
+    pub fn insert_symmetric_key(&mut self, symmetric_key: &SymmetricKey) -> cck_common::Result<()> {
+        query::insert_symmetric_key(&self.0, symmetric_key)
+    }
+
+    /// Insert a new identity into the tables users and identities, in a single transaction
+    ///
+    /// # Errors
+    /// If the identity is invalid (see `Identity::verify`), or its user already exists: nothing is inserted.
+    pub fn insert_identity(
+        &mut self,
+        identity: &Identity,
+        primary_key: &PublicKey,
+    ) -> cck_common::Result<()> {
+        self.transaction(|tx| tx.insert_identity(identity, primary_key))
+    }
+
+    /// Insert a new certification into the table certifications
+    ///
+    /// # Errors
+    /// If the certification is invalid (see `Certification::verify`), or already exists.
+    pub fn insert_certification(
+        &mut self,
+        certification: &Certification,
+        issuer: &PublicKey,
+    ) -> cck_common::Result<()> {
+        query::insert_certification(&self.0, certification, issuer)
     }
 
     /*
@@ -161,341 +246,796 @@ impl Ring {
 
     /// Get a user from the table users where the id matches
     pub fn get_user_where_id_matches(&self, id: impl Into<String>) -> cck_common::Result<User> {
-        let id = id.into();
+        query::get_user_where_id_matches(&self.0, id)
+    }
 
-        let mut stmt = self
-            .0
-            .prepare(sql::SQL_SELECT_FROM_USERS_WHERE_ID)
-            .map_err(|_| cck_common::Error)?;
+    /// Get a user from the table users where the email matches
+    pub fn get_user_where_email_matches(
+        &self,
+        email: impl Into<String>,
+    ) -> cck_common::Result<User> {
+        query::get_user_where_email_matches(&self.0, email)
+    }
 
-        let mut rows = stmt
-            .query_map(sqlite::params![id], |row| {
-                Ok(User {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    email: row.get(2)?,
-                })
-            })
-            .map_err(|_| cck_common::Error)?;
+    /// Get a user from the table users where the name matches
+    pub fn get_users_where_name_matches(
+        &self,
+        name: impl Into<String>,
+    ) -> cck_common::Result<Vec<User>> {
+        query::get_users_where_name_matches(&self.0, name)
+    }
 
-        let user = rows
-            .next()
-            .ok_or(cck_common::Error)?
-            .map_err(|_| cck_common::Error)?;
+    /// Get a private_key from the table private_keys where the user and fingerprint matches
+    pub fn get_private_key_where_user_and_fingerprint_matches(
+        &self,
+        user: impl Into<User>,
+        fingerprint: &[u8],
+    ) -> cck_common::Result<PrivateKey> {
+        query::get_private_key_where_user_and_fingerprint_matches(&self.0, user, fingerprint)
+    }
 
-        Ok(user)
+    /// Get a private_key from the table private_keys where the fingerprint matches
+    pub fn get_private_key_where_fingerprint_matches(
+        &self,
+        fingerprint: &[u8],
+    ) -> cck_common::Result<PrivateKey> {
+        query::get_private_key_where_fingerprint_matches(&self.0, fingerprint)
     }
 
-    /// Get a user from the table users where the email matches
-    pub fn get_user_where_email_matches(&self, email: impl Into<String>) -> cck_common::Result<User> {
-        let email = email.into();
+    /// Get the private_keys from the table private_keys where the user matches
+    pub fn get_private_keys_where_user_matches(
+        &self,
+        user: impl Into<User>,
+    ) -> cck_common::Result<Vec<PrivateKey>> {
+        query::get_private_keys_where_user_matches(&self.0, user)
+    }
 
-        let mut stmt = self
-            .0
-            .prepare(sql::SQL_SELECT_FROM_USERS_WHERE_EMAIL)
-            .map_err(|_| cck_common::Error)?;
+    /// Get a public_key from the table public_keys where the user and fingerprint matches
+    pub fn get_public_key_where_user_and_fingerprint_matches(
+        &self,
+        user: impl Into<User>,
+        fingerprint: &[u8],
+    ) -> cck_common::Result<PublicKey> {
+        query::get_public_key_where_user_and_fingerprint_matches(&self.0, user, fingerprint)
+    }
 
-        let mut rows = stmt
-            .query_map(sqlite::params![email], |row| {
-                Ok(User {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    email: row.get(2)?,
-                })
-            })
-            .map_err(|_| cck_common::Error)?;
+    /// Get the public_keys from the table public_keys where the user matches
+    pub fn get_public_keys_where_user_matches(
+        &self,
+        user: impl Into<User>,
+    ) -> cck_common::Result<Vec<PublicKey>> {
+        query::get_public_keys_where_user_matches(&self.0, user)
+    }
 
-        let user = rows
-            .next()
-            .ok_or(cck_common::Error)?
-            .map_err(|_| cck_common::Error)?;
+    /// Get a public_key from the table public_keys where the fingerprint matches
+    pub fn get_public_key_where_fingerprint_matches(
+        &self,
+        fingerprint: &[u8],
+    ) -> cck_common::Result<PublicKey> {
+        query::get_public_key_where_fingerprint_matches(&self.0, fingerprint)
+    }
 
-        Ok(user)
+    /// Get the private_keys from the table private_keys where the fingerprint matches a textual form
+    ///
+    /// The text is a fingerprint as hex (grouped or not), words or base64 (see `Fingerprint::parse`),
+    /// or a key id (see `KeyId::parse`), which may match several keys.
+    ///
+    /// # Example
+    /// ```
+    /// let private_keys = ring.get_private_keys_where_fingerprint_text_matches("89AB CDEF 0123 4567")?;
+    /// ```
+    ///
+    /// # Errors
+    /// If the text is neither a fingerprint nor a key id.
+    pub fn get_private_keys_where_fingerprint_text_matches(
+        &self,
+        text: impl AsRef<str>,
+    ) -> cck_common::Result<Vec<PrivateKey>> {
+        query::get_keys_where_fingerprint_text_matches(
+            &self.0,
+            text.as_ref(),
+            sql::SQL_SELECT_FROM_PRIVATE_KEYS_WHERE_FINGERPRINT,
+            sql::SQL_SELECT_FROM_PRIVATE_KEYS_WHERE_KEY_ID,
+        )
     }
 
-    /// Get a user from the table users where the name matches
-    pub fn get_users_where_name_matches(&self, name: impl Into<String>) -> cck_common::Result<Vec<User>> {
-        let name = name.into();
+    /// Get the public_keys from the table public_keys where the fingerprint matches a textual form
+    ///
+    /// See `get_private_keys_where_fingerprint_text_matches`.
+    ///
+    /// # Errors
+    /// If the text is neither a fingerprint nor a key id.
+    pub fn get_public_keys_where_fingerprint_text_matches(
+        &self,
+        text: impl AsRef<str>,
+    ) -> cck_common::Result<Vec<PublicKey>> {
+        query::get_keys_where_fingerprint_text_matches(
+            &self.0,
+            text.as_ref(),
+            sql::SQL_SELECT_FROM_PUBLIC_KEYS_WHERE_FINGERPRINT,
+            sql::SQL_SELECT_FROM_PUBLIC_KEYS_WHERE_KEY_ID,
+        )
+    }
 
-        let mut users = Vec::new();
+    /// Get a symmetric_key from the table symmetric_keys where the id matches
+    pub fn get_symmetric_key_where_id_matches(
+        &self,
+        id: &[u8],
+    ) -> cck_common::Result<SymmetricKey> {
+        query::get_symmetric_key_where_id_matches(&self.0, id)
+    }
 
-        let mut stmt = self
-            .0
-            .prepare(sql::SQL_SELECT_FROM_USERS_WHERE_NAME)
-            .map_err(|_| cck_common::Error)?;
+    /// Get an identity from the table identities where the user id matches
+    pub fn get_identity_where_id_matches(
+        &self,
+        id: impl Into<String>,
+    ) -> cck_common::Result<Identity> {
+        query::get_identity_where_id_matches(&self.0, id)
+    }
 
-        let mut rows = stmt
-            .query_map(sqlite::params![name], |row| {
-                Ok(User {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    email: row.get(2)?,
-                })
-            })
-            .map_err(|_| cck_common::Error)?;
+    /// Get the identities from the table identities where the fingerprint of the primary key matches, revoked or not
+    pub fn get_identities_where_fingerprint_matches(
+        &self,
+        fingerprint: &[u8],
+    ) -> cck_common::Result<Vec<Identity>> {
+        query::get_identities_where_fingerprint_matches(&self.0, fingerprint)
+    }
 
-        while let Some(user) = rows.next() {
-            users.push(user.map_err(|_| cck_common::Error)?);
-        }
+    /// Get the certifications from the table certifications where the certified fingerprint matches
+    pub fn get_certifications_where_fingerprint_matches(
+        &self,
+        fingerprint: &[u8],
+    ) -> cck_common::Result<Vec<Certification>> {
+        query::get_certifications_where_fingerprint_matches(&self.0, fingerprint)
+    }
 
-        Ok(users)
+    /// Get the certifications from the table certifications where the issuer matches
+    pub fn get_certifications_where_issuer_matches(
+        &self,
+        issuer: &[u8],
+    ) -> cck_common::Result<Vec<Certification>> {
+        query::get_certifications_where_issuer_matches(&self.0, issuer)
     }
 
-    /// Get a private_key from the table private_keys where the user and fingerpring matches
-    pub fn get_private_key_where_user_and_fingerprint_matches(
+    /// Is the key valid, given the trusted roots (fingerprints of primary keys) and the certifications of the ring
+    ///
+    /// A root is valid. Another key is valid if it is certified by one fully trusted introducer, or two marginally
+    /// trusted introducers, themselves valid through at most `max_depth` certifications from the roots (see `TrustLevel`).
+    /// A key made valid by marginal introducers is at most a marginal introducer, whatever the certifications say.
+    ///
+    /// # Example
+    /// ```
+    /// let valid = ring.is_key_valid(public_key.fingerprint(), &[my_primary_key.fingerprint()], 3)?;
+    /// ```
+    pub fn is_key_valid(
         &self,
-        user: impl Into<User>,
-        fingerpring: impl Into<String>,
-    ) -> cck_common::Result<PrivateKey> {
+        fingerprint: &[u8],
+        roots: &[impl AsRef<[u8]>],
+        max_depth: u32,
+    ) -> cck_common::Result<bool> {
+        let certifications = query::query_all(
+            &self.0,
+            sql::SQL_SELECT_FROM_CERTIFICATIONS,
+            [],
+            query::certification_from_row,
+        )?;
+
+        Ok(certification::is_key_valid(
+            &certifications,
+            fingerprint,
+            roots,
+            max_depth,
+        ))
+    }
+
+    /*
+        List
+    */
+
+    /// List the users from the table users, ordered by id
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of users.
+    ///
+    /// * `offset` - The number of users to skip.
+    pub fn list_users(&self, limit: u32, offset: u32) -> cck_common::Result<Vec<User>> {
+        query::query_all(
+            &self.0,
+            sql::SQL_SELECT_FROM_USERS,
+            sqlite::params![limit, offset],
+            query::user_from_row,
+        )
+    }
+
+    /// List the private_keys from the table private_keys, ordered by user and fingerprint
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of keys.
+    ///
+    /// * `offset` - The number of keys to skip.
+    pub fn list_private_keys(&self, limit: u32, offset: u32) -> cck_common::Result<Vec<PrivateKey>> {
+        query::query_all(
+            &self.0,
+            sql::SQL_SELECT_FROM_PRIVATE_KEYS,
+            sqlite::params![limit, offset],
+            query::key_from_row,
+        )
+    }
+
+    /// List the public_keys from the table public_keys, ordered by user and fingerprint
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of keys.
+    ///
+    /// * `offset` - The number of keys to skip.
+    pub fn list_public_keys(&self, limit: u32, offset: u32) -> cck_common::Result<Vec<PublicKey>> {
+        query::query_all(
+            &self.0,
+            sql::SQL_SELECT_FROM_PUBLIC_KEYS,
+            sqlite::params![limit, offset],
+            query::key_from_row,
+        )
+    }
+
+    /*
+        Query
+    */
+
+    /// Get the private_keys from the table private_keys that match the query
+    ///
+    /// # Errors
+    /// If the query is invalid (see `KeyQuery::fingerprint_prefix`), or a row is invalid.
+    pub fn query_private_keys(&self, query: &KeyQuery) -> cck_common::Result<Vec<PrivateKey>> {
+        let (statement, params) = query.sql(sql::SQL_SELECT_FROM_PRIVATE_KEYS_JOIN_USERS)?;
+
+        query::query_all(
+            &self.0,
+            &statement,
+            sqlite::params_from_iter(params),
+            query::key_from_row,
+        )
+    }
+
+    /// Get the public_keys from the table public_keys that match the query
+    ///
+    /// # Errors
+    /// If the query is invalid (see `KeyQuery::fingerprint_prefix`), or a row is invalid.
+    pub fn query_public_keys(&self, query: &KeyQuery) -> cck_common::Result<Vec<PublicKey>> {
+        let (statement, params) = query.sql(sql::SQL_SELECT_FROM_PUBLIC_KEYS_JOIN_USERS)?;
+
+        query::query_all(
+            &self.0,
+            &statement,
+            sqlite::params_from_iter(params),
+            query::key_from_row,
+        )
+    }
+
+    /*
+        Update
+    */
+
+    /// Update the name and email of a user in the table users where the id matches
+    ///
+    /// The user of an identity can't be updated: its name and email are certified by the primary key,
+    /// so create a new `Identity` (and revoke the old one) instead.
+    ///
+    /// # Errors
+    /// If the user does not exist, the user has an identity, or the update fails.
+    pub fn update_user(&mut self, user: impl Into<User>) -> cck_common::Result<()> {
         let user = user.into();
-        let fingerpring = fingerpring.into();
 
-        let mut stmt = self
-            .0
-            .prepare(sql::SQL_SELECT_FROM_PRIVATE_KEYS_WHERE_USER_ID_AND_FINGERPRINT)
-            .map_err(|_| cck_common::Error)?;
+        let tx = self.0.transaction().map_err(|_| cck_common::Error)?;
 
-        let mut rows = stmt
-            .query_map(sqlite::params![user.id(), fingerpring], |row| {
-                Ok(<PrivateKey as cck_asymmetric::Key>::from(
-                    match row.get(1)? {
-                        0 => false,
-                        _ => true,
-                    },
-                    KeyType::from_string(row.get(1)?).unwrap(),
-                    Expiry::from_string(row.get(2)?).unwrap(),
-                    row.get(3)?,
-                    row.get(4)?,
-                    match row.get(5) {
-                        Err(_) => None,
-                        Ok(signature) => Some(signature),
-                    },
-                ))
-            })
+        let count = tx
+            .execute(
+                sql::SQL_UPDATE_USERS_WHERE_ID,
+                sqlite::params![user.name(), user.email(), user.id()],
+            )
             .map_err(|_| cck_common::Error)?;
 
-        let private_key = rows
-            .next()
-            .ok_or(cck_common::Error)?
-            .map_err(|_| cck_common::Error)?;
+        if count == 0 {
+            Err(cck_common::Error)?
+        }
 
-        Ok(private_key)
+        tx.commit().map_err(|_| cck_common::Error)
     }
 
-    /// Get a private_key from the table private_keys where the fingerpring matches
-    pub fn get_private_key_where_fingerprint_matches(
-        &self,
-        fingerpring: impl Into<String>,
-    ) -> cck_common::Result<PrivateKey> {
-        let fingerpring = fingerpring.into();
+    /// Update the expiry of a key in the tables private_keys and public_keys where the fingerprint matches
+    ///
+    /// # Errors
+    /// If there is no key with the fingerprint, or the update fails.
+    pub fn update_key_expiry(
+        &mut self,
+        fingerprint: &[u8],
+        expiry: &Expiry,
+    ) -> cck_common::Result<()> {
+        let tx = self.0.transaction().map_err(|_| cck_common::Error)?;
 
-        let mut stmt = self
-            .0
-            .prepare(sql::SQL_SELECT_FROM_PRIVATE_KEYS_WHERE_FINGERPRINT)
-            .map_err(|_| cck_common::Error)?;
+        let mut count = 0;
 
-        let mut rows = stmt
-            .query_map(sqlite::params![fingerpring], |row| {
-                Ok(<PrivateKey as cck_asymmetric::Key>::from(
-                    match row.get(1)? {
-                        0 => false,
-                        _ => true,
-                    },
-                    KeyType::from_string(row.get(1)?).unwrap(),
-                    Expiry::from_string(row.get(2)?).unwrap(),
-                    row.get(3)?,
-                    row.get(4)?,
-                    match row.get(5) {
-                        Err(_) => None,
-                        Ok(signature) => Some(signature),
-                    },
-                ))
-            })
-            .map_err(|_| cck_common::Error)?;
+        for statement in [
+            sql::SQL_UPDATE_PRIVATE_KEYS_SET_EXPIRY_WHERE_FINGERPRINT,
+            sql::SQL_UPDATE_PUBLIC_KEYS_SET_EXPIRY_WHERE_FINGERPRINT,
+        ] {
+            count += tx
+                .execute(statement, sqlite::params![expiry.to_string(), fingerprint])
+                .map_err(|_| cck_common::Error)?;
+        }
 
-        let private_key = rows
-            .next()
-            .ok_or(cck_common::Error)?
-            .map_err(|_| cck_common::Error)?;
+        if count == 0 {
+            Err(cck_common::Error)?
+        }
 
-        Ok(private_key)
+        tx.commit().map_err(|_| cck_common::Error)
     }
 
-    /// Get a private_key from the table private_keys where the user matches
-    pub fn get_private_keys_where_user_matches(
-        &self,
+    /// Set the primary key of a user in the tables private_keys and public_keys
+    ///
+    /// The other keys of the user are no longer primary.
+    ///
+    /// # Errors
+    /// If the user has no signing key (Ed25519) with the fingerprint, or the update fails.
+    pub fn set_primary_key(
+        &mut self,
         user: impl Into<User>,
-    ) -> cck_common::Result<Vec<PrivateKey>> {
+        fingerprint: &[u8],
+    ) -> cck_common::Result<()> {
         let user = user.into();
 
-        let mut private_keys = Vec::new();
+        let tx = self.0.transaction().map_err(|_| cck_common::Error)?;
 
-        let mut stmt = self
-            .0
-            .prepare(sql::SQL_SELECT_FROM_PRIVATE_KEYS_WHERE_USER_ID)
-            .map_err(|_| cck_common::Error)?;
+        for statement in [
+            sql::SQL_UPDATE_PRIVATE_KEYS_UNSET_PRIMARY_WHERE_USER_ID,
+            sql::SQL_UPDATE_PUBLIC_KEYS_UNSET_PRIMARY_WHERE_USER_ID,
+        ] {
+            tx.execute(statement, sqlite::params![user.id()])
+                .map_err(|_| cck_common::Error)?;
+        }
+
+        let mut count = 0;
+
+        for statement in [
+            sql::SQL_UPDATE_PRIVATE_KEYS_SET_PRIMARY_WHERE_USER_ID_AND_FINGERPRINT,
+            sql::SQL_UPDATE_PUBLIC_KEYS_SET_PRIMARY_WHERE_USER_ID_AND_FINGERPRINT,
+        ] {
+            count += tx
+                .execute(statement, sqlite::params![user.id(), fingerprint])
+                .map_err(|_| cck_common::Error)?;
+        }
+
+        // Rolled back on drop: the previous primary key is kept.
+        if count == 0 {
+            Err(cck_common::Error)?
+        }
+
+        tx.commit().map_err(|_| cck_common::Error)
+    }
+
+    /// Revoke an identity in the table identities where the user id matches
+    ///
+    /// The other identities of the primary key are unchanged.
+    ///
+    /// # Errors
+    /// If the identity does not exist, is already revoked, or the primary key is not its primary key.
+    pub fn revoke_identity(
+        &mut self,
+        id: impl Into<String>,
+        primary_key: &PrivateKey,
+    ) -> cck_common::Result<Identity> {
+        let tx = self.0.transaction().map_err(|_| cck_common::Error)?;
+
+        let mut identity = query::get_identity_where_id_matches(&tx, id)?;
 
-        let mut rows = stmt
-            .query_map(sqlite::params![user.id()], |row| {
-                Ok(<PrivateKey as cck_asymmetric::Key>::from(
-                    match row.get(1)? {
-                        0 => false,
-                        _ => true,
-                    },
-                    KeyType::from_string(row.get(2)?).unwrap(),
-                    Expiry::from_string(row.get(3)?).unwrap(),
-                    row.get(4)?,
-                    row.get(5)?,
-                    match row.get(6) {
-                        Err(_) => None,
-                        Ok(signature) => Some(signature),
-                    },
-                ))
-            })
+        if identity.is_revoked() {
+            Err(cck_common::Error)?
+        }
+
+        identity.revoke(primary_key)?;
+
+        let count = tx
+            .execute(
+                sql::SQL_UPDATE_IDENTITIES_SET_REVOCATION_WHERE_USER_ID,
+                sqlite::params![identity.revocation(), identity.id()],
+            )
             .map_err(|_| cck_common::Error)?;
 
-        while let Some(private_key) = rows.next() {
-            private_keys.push(private_key.map_err(|_| cck_common::Error)?);
+        if count == 0 {
+            Err(cck_common::Error)?
         }
 
-        Ok(private_keys)
+        tx.commit().map_err(|_| cck_common::Error)?;
+
+        Ok(identity)
     }
 
-    /// Get a public_key from the table public_keys where the user and fingerpring matches
-    pub fn get_public_key_where_user_and_fingerprint_matches(
-        &self,
-        user: impl Into<User>,
-        fingerpring: impl Into<String>,
-    ) -> cck_common::Result<PublicKey> {
+    /*
+        Delete
+    */
+
+    /// Delete a user from the table users, with its keys
+    ///
+    /// # Errors
+    /// If the user does not exist, or the delete fails.
+    pub fn delete_user(&mut self, user: impl Into<User>) -> cck_common::Result<()> {
         let user = user.into();
-        let fingerpring = fingerpring.into();
 
-        let mut stmt = self
-            .0
-            .prepare(sql::SQL_SELECT_FROM_PUBLIC_KEYS_WHERE_USER_ID_AND_FINGERPRINT)
-            .map_err(|_| cck_common::Error)?;
+        let tx = self.0.transaction().map_err(|_| cck_common::Error)?;
 
-        let mut rows = stmt
-            .query_map(sqlite::params![user.id(), fingerpring], |row| {
-                Ok(<PublicKey as cck_asymmetric::Key>::from(
-                    match row.get(1)? {
-                        0 => false,
-                        _ => true,
-                    },
-                    KeyType::from_string(row.get(2)?).unwrap(),
-                    Expiry::from_string(row.get(3)?).unwrap(),
-                    row.get(4)?,
-                    row.get(5)?,
-                    match row.get(6) {
-                        Err(_) => None,
-                        Ok(signature) => Some(signature),
-                    },
-                ))
-            })
+        // The keys are deleted by the foreign keys (ON DELETE CASCADE).
+        let count = tx
+            .execute(sql::SQL_DELETE_FROM_USERS_WHERE_ID, sqlite::params![user.id()])
             .map_err(|_| cck_common::Error)?;
 
-        let public_key = rows
-            .next()
-            .ok_or(cck_common::Error)?
-            .map_err(|_| cck_common::Error)?;
+        if count == 0 {
+            Err(cck_common::Error)?
+        }
 
-        Ok(public_key)
+        tx.commit().map_err(|_| cck_common::Error)
     }
 
-    /// Get a public_key from the table public_keys where the user matches
-    pub fn get_public_keys_where_user_matches(
-        &self,
-        user: impl Into<User>,
-    ) -> cck_common::Result<Vec<PublicKey>> {
-        let user = user.into();
+    /// Delete a key from the tables private_keys and public_keys where the fingerprint matches
+    ///
+    /// # Errors
+    /// If there is no key with the fingerprint, or the delete fails.
+    pub fn delete_key_by_fingerprint(&mut self, fingerprint: &[u8]) -> cck_common::Result<()> {
+        let tx = self.0.transaction().map_err(|_| cck_common::Error)?;
+
+        let mut count = 0;
+
+        for statement in [
+            sql::SQL_DELETE_FROM_PRIVATE_KEYS_WHERE_FINGERPRINT,
+            sql::SQL_DELETE_FROM_PUBLIC_KEYS_WHERE_FINGERPRINT,
+        ] {
+            count += tx
+                .execute(statement, sqlite::params![fingerprint])
+                .map_err(|_| cck_common::Error)?;
+        }
 
-        let mut public_keys = Vec::new();
+        if count == 0 {
+            Err(cck_common::Error)?
+        }
+
+        tx.commit().map_err(|_| cck_common::Error)
+    }
 
-        let mut stmt = self
+    /// Delete the certifications of a key by an issuer from the table certifications
+    ///
+    /// # Errors
+    /// If there is no certification of the key by the issuer, or the delete fails.
+    pub fn delete_certifications(
+        &mut self,
+        issuer: &[u8],
+        fingerprint: &[u8],
+    ) -> cck_common::Result<()> {
+        let count = self
             .0
-            .prepare(sql::SQL_SELECT_FROM_PUBLIC_KEYS_WHERE_USER_ID)
+            .execute(
+                sql::SQL_DELETE_FROM_CERTIFICATIONS_WHERE_ISSUER_AND_FINGERPRINT,
+                sqlite::params![issuer, fingerprint],
+            )
             .map_err(|_| cck_common::Error)?;
 
-        let mut rows = stmt
-            .query_map(sqlite::params![user.id()], |row| {
-                Ok(<PublicKey as cck_asymmetric::Key>::from(
-                    match row.get(1)? {
-                        0 => false,
-                        _ => true,
-                    },
-                    KeyType::from_string(row.get(2)?).unwrap(),
-                    Expiry::from_string(row.get(3)?).unwrap(),
-                    row.get(4)?,
-                    row.get(5)?,
-                    match row.get(6) {
-                        Err(_) => None,
-                        Ok(signature) => Some(signature),
-                    },
-                ))
-            })
+        if count == 0 {
+            Err(cck_common::Error)?
+        }
+
+        Ok(())
+    }
+
+    /*
+        Known keys
+    */
+
+    /// Pin the fingerprint of a user id the first time it is seen (trust on first use)
+    ///
+    /// Returns the status of the fingerprint before the call: if `New`, it is now pinned and the change is audited.
+    /// If `Changed`, nothing is changed: warn, then `accept_new_known_key` or fail.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The user id presented by the peer.
+    ///
+    /// * `fingerprint` - The fingerprint presented by the peer.
+    ///
+    /// * `changed_by` - Who pins the fingerprint, recorded in the table known_keys_audit.
+    ///
+    /// # Example
+    /// ```
+    /// match ring.pin_key(peer.id(), public_key.fingerprint(), "tooling")? {
+    ///     KeyStatus::New | KeyStatus::Known => {}
+    ///     KeyStatus::Changed { pinned } => eprintln!("the key of {} has changed", peer.id()),
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    /// If `changed_by` is empty (whatever the status), or the insert fails.
+    pub fn pin_key(
+        &mut self,
+        user_id: impl Into<String>,
+        fingerprint: &[u8],
+        changed_by: impl Into<String>,
+    ) -> cck_common::Result<KeyStatus> {
+        let user_id = user_id.into();
+
+        let changed_by = changed_by.into();
+
+        if changed_by.is_empty() {
+            Err(cck_common::Error)?
+        }
+
+        let tx = self.0.transaction().map_err(|_| cck_common::Error)?;
+
+        let status = query::known_key_status(&tx, &user_id, fingerprint)?;
+
+        if status == KeyStatus::New {
+            tx.execute(
+                sql::SQL_INSERT_INTO_KNOWN_KEYS,
+                sqlite::params![user_id, fingerprint],
+            )
             .map_err(|_| cck_common::Error)?;
 
-        while let Some(public_key) = rows.next() {
-            public_keys.push(public_key.map_err(|_| cck_common::Error)?);
+            query::insert_known_key_audit(
+                &tx,
+                &user_id,
+                KnownKeyAction::Pin,
+                None,
+                fingerprint,
+                &changed_by,
+            )?;
         }
 
-        Ok(public_keys)
+        tx.commit().map_err(|_| cck_common::Error)?;
+
+        Ok(status)
     }
 
-    /// Get a public_key from the table public_keys where the fingerpring matches
-    pub fn get_public_key_where_fingerprint_matches(
+    /// Compare the fingerprint presented for a user id with the pinned one, without pinning it
+    pub fn check_known_key(
         &self,
-        fingerpring: impl Into<String>,
-    ) -> cck_common::Result<PublicKey> {
-        let fingerpring = fingerpring.into();
+        user_id: impl Into<String>,
+        fingerprint: &[u8],
+    ) -> cck_common::Result<KeyStatus> {
+        query::known_key_status(&self.0, &user_id.into(), fingerprint)
+    }
 
-        let mut stmt = self
-            .0
-            .prepare(sql::SQL_SELECT_FROM_PUBLIC_KEYS_WHERE_FINGERPRINT)
-            .map_err(|_| cck_common::Error)?;
+    /// Verify that the fingerprint presented for a user id is the pinned one
+    ///
+    /// # Errors
+    /// If no fingerprint is pinned for the user id, or another fingerprint is pinned.
+    pub fn verify_known_key(
+        &self,
+        user_id: impl Into<String>,
+        fingerprint: &[u8],
+    ) -> cck_common::Result<()> {
+        match self.check_known_key(user_id, fingerprint)? {
+            KeyStatus::Known => Ok(()),
+            _ => Err(cck_common::Error),
+        }
+    }
 
-        let mut rows = stmt
-            .query_map(sqlite::params![fingerpring], |row| {
-                Ok(<PublicKey as cck_asymmetric::Key>::from(
-                    match row.get(1)? {
-                        0 => false,
-                        _ => true,
-                    },
-                    KeyType::from_string(row.get(2)?).unwrap(),
-                    Expiry::from_string(row.get(3)?).unwrap(),
-                    row.get(4)?,
-                    row.get(5)?,
-                    match row.get(6) {
-                        Err(_) => None,
-                        Ok(signature) => Some(signature),
-                    },
-                ))
-            })
-            .map_err(|_| cck_common::Error)?;
+    /// Accept the changed fingerprint presented for a user id: it replaces the pinned one, and the change is audited
+    ///
+    /// # Errors
+    /// If no fingerprint is pinned for the user id, the fingerprint is already pinned, or `changed_by` is empty.
+    pub fn accept_new_known_key(
+        &mut self,
+        user_id: impl Into<String>,
+        fingerprint: &[u8],
+        changed_by: impl Into<String>,
+    ) -> cck_common::Result<()> {
+        let user_id = user_id.into();
+
+        let changed_by = changed_by.into();
+
+        if changed_by.is_empty() {
+            Err(cck_common::Error)?
+        }
+
+        let pinned = match self.check_known_key(user_id.as_str(), fingerprint)? {
+            KeyStatus::Changed { pinned } => pinned,
+            _ => Err(cck_common::Error)?,
+        };
+
+        self.update_known_key(
+            &user_id,
+            KnownKeyAction::AcceptNew,
+            &pinned,
+            fingerprint,
+            &changed_by,
+        )
+    }
+
+    /// Replace the pinned fingerprint of a user id with another one (e.g. a key rotation), and audit the change
+    ///
+    /// # Errors
+    /// If `old_fingerprint` is not the pinned one, it equals `new_fingerprint`, or `changed_by` is empty.
+    pub fn replace_known_key(
+        &mut self,
+        user_id: impl Into<String>,
+        old_fingerprint: &[u8],
+        new_fingerprint: &[u8],
+        changed_by: impl Into<String>,
+    ) -> cck_common::Result<()> {
+        let changed_by = changed_by.into();
+
+        if old_fingerprint == new_fingerprint || changed_by.is_empty() {
+            Err(cck_common::Error)?
+        }
+
+        self.update_known_key(
+            &user_id.into(),
+            KnownKeyAction::Replace,
+            old_fingerprint,
+            new_fingerprint,
+            &changed_by,
+        )
+    }
 
-        let public_key = rows
-            .next()
-            .ok_or(cck_common::Error)?
+    /// Get the known key from the table known_keys where the user id matches
+    pub fn get_known_key_where_user_id_matches(
+        &self,
+        user_id: impl Into<String>,
+    ) -> cck_common::Result<KnownKey> {
+        query::get_known_key_where_user_id_matches(&self.0, &user_id.into())?
+            .ok_or(cck_common::Error)
+    }
+
+    /// Get the changes from the table known_keys_audit where the user id matches, in order
+    pub fn get_known_key_audit_where_user_id_matches(
+        &self,
+        user_id: impl Into<String>,
+    ) -> cck_common::Result<Vec<KnownKeyAudit>> {
+        query::query_all(
+            &self.0,
+            sql::SQL_SELECT_FROM_KNOWN_KEYS_AUDIT_WHERE_USER_ID,
+            sqlite::params![user_id.into()],
+            query::known_key_audit_from_row,
+        )
+    }
+
+    /// Replace the pinned fingerprint if it is still `old_fingerprint`, and audit the change
+    fn update_known_key(
+        &mut self,
+        user_id: &str,
+        action: KnownKeyAction,
+        old_fingerprint: &[u8],
+        new_fingerprint: &[u8],
+        changed_by: &str,
+    ) -> cck_common::Result<()> {
+        let tx = self.0.transaction().map_err(|_| cck_common::Error)?;
+
+        let count = tx
+            .execute(
+                sql::SQL_UPDATE_KNOWN_KEYS_SET_FINGERPRINT_WHERE_USER_ID_AND_FINGERPRINT,
+                sqlite::params![new_fingerprint, user_id, old_fingerprint],
+            )
             .map_err(|_| cck_common::Error)?;
 
-        Ok(public_key)
+        if count == 0 {
+            Err(cck_common::Error)?
+        }
+
+        query::insert_known_key_audit(
+            &tx,
+            user_id,
+            action,
+            Some(old_fingerprint),
+            new_fingerprint,
+            changed_by,
+        )?;
+
+        tx.commit().map_err(|_| cck_common::Error)
+    }
+
+    /*
+        Envelope
+    */
+
+    /// Unwrap a data key with the key-encryption key from the table symmetric_keys where the id matches the KEK id
+    pub fn unwrap_key(&self, wrapped: &WrappedKey) -> cck_common::Result<SymmetricKey> {
+        let kek = self.get_symmetric_key_where_id_matches(wrapped.kek_id())?;
+
+        cck_symmetric::unwrap_key(UnwrappingKey::Symmetric(&kek), wrapped)
+    }
+
+    /// Decrypt an envelope-encrypted object with the key-encryption key from the table symmetric_keys
+    pub fn envelope_decrypt(
+        &self,
+        associated_data: &[u8],
+        object: &[u8],
+    ) -> cck_common::Result<Vec<u8>> {
+        let wrapped = cck_symmetric::envelope_wrapped_key(object)?;
+
+        let kek = self.get_symmetric_key_where_id_matches(wrapped.kek_id())?;
+
+        cck_symmetric::envelope_decrypt(UnwrappingKey::Symmetric(&kek), associated_data, object)
+    }
+
+    /// Rotate the key-encryption key of an envelope-encrypted object to the given key from the table symmetric_keys
+    pub fn envelope_rewrap(&self, new_kek_id: &[u8], object: &[u8]) -> cck_common::Result<Vec<u8>> {
+        let wrapped = cck_symmetric::envelope_wrapped_key(object)?;
+
+        let old_kek = self.get_symmetric_key_where_id_matches(wrapped.kek_id())?;
+
+        let new_kek = self.get_symmetric_key_where_id_matches(new_kek_id)?;
+
+        cck_symmetric::envelope_rewrap(
+            UnwrappingKey::Symmetric(&old_kek),
+            WrappingKey::Symmetric(&new_kek),
+            object,
+        )
     }
 }
 
+/// Create the tables, or migrate them to `sql::SCHEMA_VERSION`.
+///
+/// Each migration runs in its own transaction, with the foreign keys checked before commit.
+///
+/// # Errors
+/// If a migration fails (the database stays at the last applied version), or the database is newer than this crate.
 fn init_tables(conn: &mut sqlite::Connection) -> cck_common::Result<()> {
-    // Create table `users` if not exists.
-    conn.execute_batch(sql::SQL_CREATE_TABLE_USERS)
+    // Create table `schema_version` if not exists.
+    conn.execute_batch(sql::SQL_CREATE_TABLE_SCHEMA_VERSION)
         .map_err(|_| cck_common::Error)?;
 
-    // Create table `private_keys` if not exists.
-    conn.execute_batch(sql::SQL_CREATE_TABLE_PRIVATE_KEYS)
+    let version = schema_version(conn)?;
+
+    if version > sql::SCHEMA_VERSION {
+        Err(cck_common::Error)?
+    }
+
+    // Tables are rebuilt by the migrations, so the foreign keys are only checked at the end of each migration.
+    conn.execute_batch(sql::SQL_PRAGMA_FOREIGN_KEYS_OFF)
         .map_err(|_| cck_common::Error)?;
 
-    // Create table `public_keys` if not exists.
-    conn.execute_batch(sql::SQL_CREATE_TABLE_PUBLIC_KEYS)
+    let result = sql::SQL_MIGRATIONS
+        .iter()
+        .filter(|(migration, _)| *migration > version)
+        .try_for_each(|(migration, statements)| migrate(conn, *migration, statements));
+
+    conn.execute_batch(sql::SQL_PRAGMA_FOREIGN_KEYS_ON)
         .map_err(|_| cck_common::Error)?;
 
-    Ok(())
+    result
+}
+
+fn migrate(
+    conn: &mut sqlite::Connection,
+    version: u32,
+    statements: &[&str],
+) -> cck_common::Result<()> {
+    let tx = conn.transaction().map_err(|_| cck_common::Error)?;
+
+    for statement in statements {
+        tx.execute_batch(statement).map_err(|_| cck_common::Error)?;
+    }
+
+    // Any row means a foreign key violation.
+    let violation = tx
+        .prepare(sql::SQL_PRAGMA_FOREIGN_KEY_CHECK)
+        .and_then(|mut stmt| stmt.exists([]))
+        .map_err(|_| cck_common::Error)?;
+
+    if violation {
+        Err(cck_common::Error)?
+    }
+
+    tx.execute(
+        sql::SQL_INSERT_INTO_SCHEMA_VERSION,
+        sqlite::params![version],
+    )
+    .map_err(|_| cck_common::Error)?;
+
+    tx.commit().map_err(|_| cck_common::Error)
+}
+
+fn schema_version(conn: &sqlite::Connection) -> cck_common::Result<u32> {
+    conn.query_row(sql::SQL_SELECT_SCHEMA_VERSION, [], |row| row.get(0))
+        .map_err(|_| cck_common::Error)
 }
 
 // fn drop_tables(conn: &sqlite::Connection) -> cck_common::Result<()> {
@@ -1,90 +1,472 @@
+/*
+    SQL statements for the database
+    Table: schema_version
+*/
+/// The schema version that `init_tables` migrates the database to
+pub const SCHEMA_VERSION: u32 = 6;
+
+/// Create the table schema_version if it does not exist
+///
+/// One row per applied migration.
+pub const SQL_CREATE_TABLE_SCHEMA_VERSION: &str =
+    "CREATE TABLE IF NOT EXISTS schema_version (version INTEGER PRIMARY KEY NOT NULL, applied_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP);";
+
+/// Select the schema version of the database, 0 if no migration has been applied
+///
+/// `SQL` - SELECT IFNULL(MAX(version), 0) FROM schema_version;
+pub const SQL_SELECT_SCHEMA_VERSION: &str = "SELECT IFNULL(MAX(version), 0) FROM schema_version;";
+
+/// Insert an applied migration into the table schema_version
+pub const SQL_INSERT_INTO_SCHEMA_VERSION: &str = "INSERT INTO schema_version (version) VALUES(?);";
+
+/// Enforce the foreign keys on the connection (SQLite does not by default)
+pub const SQL_PRAGMA_FOREIGN_KEYS_ON: &str = "PRAGMA foreign_keys = ON;";
+
+/// Stop enforcing the foreign keys on the connection
+pub const SQL_PRAGMA_FOREIGN_KEYS_OFF: &str = "PRAGMA foreign_keys = OFF;";
+
+/// List the foreign key violations
+pub const SQL_PRAGMA_FOREIGN_KEY_CHECK: &str = "PRAGMA foreign_key_check;";
+
+/*
+    SQL statements for the database
+    Migrations
+*/
+/// The migrations, in order: (version, statements)
+///
+/// A database without the table schema_version is at version 0, so a database created before the migrations
+/// (the tables of version 1 without constraints) is upgraded in place.
+pub const SQL_MIGRATIONS: &[(u32, &[&str])] = &[
+    (1, SQL_MIGRATION_1),
+    (2, SQL_MIGRATION_2),
+    (3, SQL_MIGRATION_3),
+    (4, SQL_MIGRATION_4),
+    (5, SQL_MIGRATION_5),
+    (6, SQL_MIGRATION_6),
+];
+
+/// Version 1: the tables without constraints
+pub const SQL_MIGRATION_1: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS users (id TEXT, name TEXT, email TEXT);",
+    "CREATE TABLE IF NOT EXISTS private_keys (user_id TEXT, is_primary INTEGER, key_type TEXT, expiry TEXT, private_key BLOB, fingerprint BLOB, signature BLOB);",
+    "CREATE TABLE IF NOT EXISTS public_keys (user_id TEXT, is_primary INTEGER, key_type TEXT, expiry TEXT, public_key BLOB, fingerprint BLOB, signature BLOB);",
+    "CREATE TABLE IF NOT EXISTS symmetric_keys (id BLOB, algorithm TEXT, symmetric_key BLOB);",
+];
+
+/// Version 2: primary keys, foreign keys to users(id) and indexes
+///
+/// The tables are rebuilt (SQLite cannot add constraints to a table) with the same columns in the same order.
+/// Duplicate rows are dropped, and a key whose user does not exist gets an empty user so that it is kept.
+pub const SQL_MIGRATION_2: &[&str] = &[
+    "ALTER TABLE users RENAME TO users_v1;",
+    "ALTER TABLE private_keys RENAME TO private_keys_v1;",
+    "ALTER TABLE public_keys RENAME TO public_keys_v1;",
+    "ALTER TABLE symmetric_keys RENAME TO symmetric_keys_v1;",
+    SQL_CREATE_TABLE_USERS,
+    SQL_CREATE_TABLE_PRIVATE_KEYS,
+    SQL_CREATE_TABLE_PUBLIC_KEYS,
+    SQL_CREATE_TABLE_SYMMETRIC_KEYS,
+    "INSERT OR IGNORE INTO users (id, name, email) SELECT id, IFNULL(name, ''), IFNULL(email, '') FROM users_v1 WHERE id IS NOT NULL;",
+    "INSERT OR IGNORE INTO users (id, name, email) SELECT user_id, '', '' FROM private_keys_v1 WHERE user_id IS NOT NULL UNION SELECT user_id, '', '' FROM public_keys_v1 WHERE user_id IS NOT NULL;",
+    "INSERT OR IGNORE INTO private_keys SELECT * FROM private_keys_v1 WHERE user_id IS NOT NULL AND is_primary IN (0, 1) AND key_type IS NOT NULL AND expiry IS NOT NULL AND private_key IS NOT NULL AND fingerprint IS NOT NULL;",
+    "INSERT OR IGNORE INTO public_keys SELECT * FROM public_keys_v1 WHERE user_id IS NOT NULL AND is_primary IN (0, 1) AND key_type IS NOT NULL AND expiry IS NOT NULL AND public_key IS NOT NULL AND fingerprint IS NOT NULL;",
+    "INSERT OR IGNORE INTO symmetric_keys SELECT * FROM symmetric_keys_v1 WHERE id IS NOT NULL AND algorithm IS NOT NULL AND symmetric_key IS NOT NULL;",
+    "DROP TABLE users_v1;",
+    "DROP TABLE private_keys_v1;",
+    "DROP TABLE public_keys_v1;",
+    "DROP TABLE symmetric_keys_v1;",
+    SQL_CREATE_INDEX_USERS_NAME,
+    SQL_CREATE_INDEX_USERS_EMAIL,
+    SQL_CREATE_INDEX_PRIVATE_KEYS_USER_ID,
+    SQL_CREATE_INDEX_PUBLIC_KEYS_USER_ID,
+];
+
+/// Version 3: the table identities
+pub const SQL_MIGRATION_3: &[&str] = &[
+    SQL_CREATE_TABLE_IDENTITIES,
+    SQL_CREATE_INDEX_IDENTITIES_FINGERPRINT,
+];
+
+/// Version 4: the table certifications
+pub const SQL_MIGRATION_4: &[&str] = &[
+    SQL_CREATE_TABLE_CERTIFICATIONS,
+    SQL_CREATE_INDEX_CERTIFICATIONS_FINGERPRINT,
+];
+
+/// Version 5: the tables known_keys and known_keys_audit
+pub const SQL_MIGRATION_5: &[&str] = &[
+    SQL_CREATE_TABLE_KNOWN_KEYS,
+    SQL_CREATE_TABLE_KNOWN_KEYS_AUDIT,
+    SQL_CREATE_INDEX_KNOWN_KEYS_AUDIT_USER_ID,
+];
+
+/// Version 6: the table bundles
+pub const SQL_MIGRATION_6: &[&str] = &[SQL_CREATE_TABLE_BUNDLES];
+
 /*
     SQL statements for the database
     Table: users
 */
 /// Create the table users if it does not exist
 pub const SQL_CREATE_TABLE_USERS: &str =
-    "CREATE TABLE IF NOT EXISTS users (id TEXT, name TEXT, email TEXT);";
+    "CREATE TABLE IF NOT EXISTS users (id TEXT PRIMARY KEY NOT NULL, name TEXT NOT NULL, email TEXT NOT NULL);";
+
+/// Create the index on users(name) if it does not exist
+pub const SQL_CREATE_INDEX_USERS_NAME: &str =
+    "CREATE INDEX IF NOT EXISTS users_name ON users (name);";
+
+/// Create the index on users(email) if it does not exist
+pub const SQL_CREATE_INDEX_USERS_EMAIL: &str =
+    "CREATE INDEX IF NOT EXISTS users_email ON users (email);";
 
 /// Insert a new user into the table users
-pub const SQL_INSERT_INTO_USERS: &str =
-    "INSERT INTO users (id, name, email) VALUES(?, ?, ?);";
+pub const SQL_INSERT_INTO_USERS: &str = "INSERT INTO users (id, name, email) VALUES(?, ?, ?);";
 
 /// Select a user from the table users where the id matches
 ///
-/// `SQL` - SELECT * FROM users WHERE id = ?;
-pub const SQL_SELECT_FROM_USERS_WHERE_ID: &str = "SELECT * FROM users WHERE id = ?;";
+/// `SQL` - SELECT id, name, email FROM users WHERE id = ?;
+pub const SQL_SELECT_FROM_USERS_WHERE_ID: &str = "SELECT id, name, email FROM users WHERE id = ?;";
 
 /// Select a user from the table users where the name matches
 ///
-/// `SQL` - SELECT * FROM users WHERE name = ?;
-pub const SQL_SELECT_FROM_USERS_WHERE_NAME: &str = "SELECT * FROM users WHERE name = ?;";
+/// `SQL` - SELECT id, name, email FROM users WHERE name = ?;
+pub const SQL_SELECT_FROM_USERS_WHERE_NAME: &str = "SELECT id, name, email FROM users WHERE name = ?;";
 
 /// Select a user from the table users where the email matches
 ///
-/// `SQL` - SELECT * FROM users WHERE email = ?;
-pub const SQL_SELECT_FROM_USERS_WHERE_EMAIL: &str = "SELECT * FROM users WHERE email = ?;";
+/// `SQL` - SELECT id, name, email FROM users WHERE email = ?;
+pub const SQL_SELECT_FROM_USERS_WHERE_EMAIL: &str = "SELECT id, name, email FROM users WHERE email = ?;";
+
+/// Select the users from the table users, ordered by id
+///
+/// `SQL` - SELECT id, name, email FROM users ORDER BY id LIMIT ? OFFSET ?;
+pub const SQL_SELECT_FROM_USERS: &str =
+    "SELECT id, name, email FROM users ORDER BY id LIMIT ? OFFSET ?;";
+
+/// Update the name and email of a user in the table users where the id matches and the user has no identity
+///
+/// The name and email of an identity are certified by its primary key (see `Identity`).
+pub const SQL_UPDATE_USERS_WHERE_ID: &str = "UPDATE users SET name = ?, email = ? WHERE id = ? AND id NOT IN (SELECT user_id FROM identities);";
+
+/// Delete a user from the table users where the id matches
+///
+/// The keys of the user are deleted with the user.
+pub const SQL_DELETE_FROM_USERS_WHERE_ID: &str = "DELETE FROM users WHERE id = ?;";
 
 /*
 SQL statements for the database
 Table: private_keys
 */
 /// Create the table private_keys if it does not exist
-pub const SQL_CREATE_TABLE_PRIVATE_KEYS: &str = "CREATE TABLE IF NOT EXISTS private_keys (user_id TEXT, is_primary INTEGER, key_type TEXT, expiry TEXT, private_key BLOB, fingerprint BLOB, signature BLOB);";
+///
+/// The keys of a user are deleted with the user.
+pub const SQL_CREATE_TABLE_PRIVATE_KEYS: &str = "CREATE TABLE IF NOT EXISTS private_keys (user_id TEXT NOT NULL REFERENCES users (id) ON DELETE CASCADE, is_primary INTEGER NOT NULL CHECK (is_primary IN (0, 1)), key_type TEXT NOT NULL, expiry TEXT NOT NULL, private_key BLOB NOT NULL, fingerprint BLOB PRIMARY KEY NOT NULL, signature BLOB);";
+
+/// Create the index on private_keys(user_id) if it does not exist
+pub const SQL_CREATE_INDEX_PRIVATE_KEYS_USER_ID: &str =
+    "CREATE INDEX IF NOT EXISTS private_keys_user_id ON private_keys (user_id);";
 
 /// Insert a new private key into the table private_keys
 pub const SQL_INSERT_INTO_PRIVATE_KEYS: &str = "INSERT INTO private_keys (user_id, is_primary, key_type, expiry, private_key, fingerprint, signature) VALUES(?, ?, ?, ?, ?, ?, ?);";
 
-// Select all private keys from the table private_keys
-// pub const SQL_SELECT_FROM_PRIVATE_KEYS: &str = "SELECT * FROM private_keys;";
+/// Select the private keys from the table private_keys, ordered by user_id and fingerprint
+///
+/// `SQL` - SELECT user_id, is_primary, key_type, expiry, private_key, fingerprint, signature FROM private_keys ORDER BY user_id, fingerprint LIMIT ? OFFSET ?;
+pub const SQL_SELECT_FROM_PRIVATE_KEYS: &str =
+    "SELECT user_id, is_primary, key_type, expiry, private_key, fingerprint, signature FROM private_keys ORDER BY user_id, fingerprint LIMIT ? OFFSET ?;";
 
 /// Select a private key from the table private_keys where the user_id matches
 ///
-/// `SQL` - SELECT * FROM private_keys WHERE user_id = ?;
+/// `SQL` - SELECT user_id, is_primary, key_type, expiry, private_key, fingerprint, signature FROM private_keys WHERE user_id = ?;
 pub const SQL_SELECT_FROM_PRIVATE_KEYS_WHERE_USER_ID: &str =
-    "SELECT * FROM private_keys WHERE user_id = ?;";
+    "SELECT user_id, is_primary, key_type, expiry, private_key, fingerprint, signature FROM private_keys WHERE user_id = ?;";
 
 /// Select a private key from the table private_keys where the fingerprint matches
 ///
-/// `SQL` - SELECT * FROM private_keys WHERE fingerprint = ?;
+/// `SQL` - SELECT user_id, is_primary, key_type, expiry, private_key, fingerprint, signature FROM private_keys WHERE fingerprint = ?;
 pub const SQL_SELECT_FROM_PRIVATE_KEYS_WHERE_FINGERPRINT: &str =
-    "SELECT * FROM private_keys WHERE fingerprint = ?;";
+    "SELECT user_id, is_primary, key_type, expiry, private_key, fingerprint, signature FROM private_keys WHERE fingerprint = ?;";
+
+/// Select the private keys from the table private_keys where the key id (the last 8 bytes of the fingerprint) matches, ordered by fingerprint
+///
+/// `SQL` - SELECT user_id, is_primary, key_type, expiry, private_key, fingerprint, signature FROM private_keys WHERE substr(fingerprint, -8) = ? ORDER BY fingerprint;
+pub const SQL_SELECT_FROM_PRIVATE_KEYS_WHERE_KEY_ID: &str =
+    "SELECT user_id, is_primary, key_type, expiry, private_key, fingerprint, signature FROM private_keys WHERE substr(fingerprint, -8) = ? ORDER BY fingerprint;";
 
 /// Select a private key from the table private_keys where the user_id and fingerprint matches
 ///
-/// `SQL` - SELECT * FROM private_keys WHERE user_id = ? AND fingerprint = ?;
+/// `SQL` - SELECT user_id, is_primary, key_type, expiry, private_key, fingerprint, signature FROM private_keys WHERE user_id = ? AND fingerprint = ?;
 pub const SQL_SELECT_FROM_PRIVATE_KEYS_WHERE_USER_ID_AND_FINGERPRINT: &str =
-    "SELECT * FROM private_keys WHERE user_id = ? AND fingerprint = ?;";
+    "SELECT user_id, is_primary, key_type, expiry, private_key, fingerprint, signature FROM private_keys WHERE user_id = ? AND fingerprint = ?;";
+
+/// Update the expiry of a private key in the table private_keys where the fingerprint matches
+pub const SQL_UPDATE_PRIVATE_KEYS_SET_EXPIRY_WHERE_FINGERPRINT: &str =
+    "UPDATE private_keys SET expiry = ? WHERE fingerprint = ?;";
+
+/// Unset the primary flag of the private keys in the table private_keys where the user_id matches
+pub const SQL_UPDATE_PRIVATE_KEYS_UNSET_PRIMARY_WHERE_USER_ID: &str =
+    "UPDATE private_keys SET is_primary = 0 WHERE user_id = ?;";
+
+/// Set the primary flag of a private key in the table private_keys where the user_id and fingerprint matches
+///
+/// Only a signing key (Ed25519) can be primary.
+pub const SQL_UPDATE_PRIVATE_KEYS_SET_PRIMARY_WHERE_USER_ID_AND_FINGERPRINT: &str =
+    "UPDATE private_keys SET is_primary = 1 WHERE user_id = ? AND fingerprint = ? AND key_type = 'Ed25519';";
+
+/// Delete a private key from the table private_keys where the fingerprint matches
+pub const SQL_DELETE_FROM_PRIVATE_KEYS_WHERE_FINGERPRINT: &str =
+    "DELETE FROM private_keys WHERE fingerprint = ?;";
 
 /*
 SQL statements for the database
 Table: public_keys
 */
 /// Create the table public_keys if it does not exist
-pub const SQL_CREATE_TABLE_PUBLIC_KEYS: &str = "CREATE TABLE IF NOT EXISTS public_keys (user_id TEXT, is_primary INTEGER, key_type TEXT, expiry TEXT, public_key BLOB, fingerprint BLOB, signature BLOB);";
+///
+/// The keys of a user are deleted with the user.
+pub const SQL_CREATE_TABLE_PUBLIC_KEYS: &str = "CREATE TABLE IF NOT EXISTS public_keys (user_id TEXT NOT NULL REFERENCES users (id) ON DELETE CASCADE, is_primary INTEGER NOT NULL CHECK (is_primary IN (0, 1)), key_type TEXT NOT NULL, expiry TEXT NOT NULL, public_key BLOB NOT NULL, fingerprint BLOB PRIMARY KEY NOT NULL, signature BLOB);";
+
+/// Create the index on public_keys(user_id) if it does not exist
+pub const SQL_CREATE_INDEX_PUBLIC_KEYS_USER_ID: &str =
+    "CREATE INDEX IF NOT EXISTS public_keys_user_id ON public_keys (user_id);";
 
 /// Insert a new public key into the table public_keys
 pub const SQL_INSERT_INTO_PUBLIC_KEYS: &str = "INSERT INTO public_keys (user_id, is_primary, key_type, expiry, public_key, fingerprint, signature) VALUES(?, ?, ?, ?, ?, ?, ?);";
 
-// Select all public keys from the table public_keys
-//
-// `SQL` - SELECT * FROM public_keys;
-// pub const SQL_SELECT_FROM_PUBLIC_KEYS: &str = "SELECT * FROM public_keys;";
+/// Select the public keys from the table public_keys, ordered by user_id and fingerprint
+///
+/// `SQL` - SELECT user_id, is_primary, key_type, expiry, public_key, fingerprint, signature FROM public_keys ORDER BY user_id, fingerprint LIMIT ? OFFSET ?;
+pub const SQL_SELECT_FROM_PUBLIC_KEYS: &str =
+    "SELECT user_id, is_primary, key_type, expiry, public_key, fingerprint, signature FROM public_keys ORDER BY user_id, fingerprint LIMIT ? OFFSET ?;";
 
 /// Select a public key from the table public_keys where the user_id matches
 ///
-/// `SQL` - SELECT * FROM public_keys WHERE user_id = ?;
+/// `SQL` - SELECT user_id, is_primary, key_type, expiry, public_key, fingerprint, signature FROM public_keys WHERE user_id = ?;
 pub const SQL_SELECT_FROM_PUBLIC_KEYS_WHERE_USER_ID: &str =
-    "SELECT * FROM public_keys WHERE user_id = ?;";
+    "SELECT user_id, is_primary, key_type, expiry, public_key, fingerprint, signature FROM public_keys WHERE user_id = ?;";
 
 /// Select a public key from the table public_keys where the fingerprint matches
 ///
-/// `SQL` - SELECT * FROM public_keys WHERE fingerprint = ?;
+/// `SQL` - SELECT user_id, is_primary, key_type, expiry, public_key, fingerprint, signature FROM public_keys WHERE fingerprint = ?;
 pub const SQL_SELECT_FROM_PUBLIC_KEYS_WHERE_FINGERPRINT: &str =
-    "SELECT * FROM public_keys WHERE fingerprint = ?;";
+    "SELECT user_id, is_primary, key_type, expiry, public_key, fingerprint, signature FROM public_keys WHERE fingerprint = ?;";
+
+/// Select the public keys from the table public_keys where the key id (the last 8 bytes of the fingerprint) matches, ordered by fingerprint
+///
+/// `SQL` - SELECT user_id, is_primary, key_type, expiry, public_key, fingerprint, signature FROM public_keys WHERE substr(fingerprint, -8) = ? ORDER BY fingerprint;
+pub const SQL_SELECT_FROM_PUBLIC_KEYS_WHERE_KEY_ID: &str =
+    "SELECT user_id, is_primary, key_type, expiry, public_key, fingerprint, signature FROM public_keys WHERE substr(fingerprint, -8) = ? ORDER BY fingerprint;";
 
 /// Select a public key from the table public_keys where the user_id and fingerprint matches
 ///
-/// `SQL` - SELECT * FROM public_keys WHERE user_id = ? AND fingerprint = ?;
+/// `SQL` - SELECT user_id, is_primary, key_type, expiry, public_key, fingerprint, signature FROM public_keys WHERE user_id = ? AND fingerprint = ?;
 pub const SQL_SELECT_FROM_PUBLIC_KEYS_WHERE_USER_ID_AND_FINGERPRINT: &str =
-    "SELECT * FROM public_keys WHERE user_id = ? AND fingerprint = ?;";
+    "SELECT user_id, is_primary, key_type, expiry, public_key, fingerprint, signature FROM public_keys WHERE user_id = ? AND fingerprint = ?;";
+
+/// Update the expiry of a public key in the table public_keys where the fingerprint matches
+pub const SQL_UPDATE_PUBLIC_KEYS_SET_EXPIRY_WHERE_FINGERPRINT: &str =
+    "UPDATE public_keys SET expiry = ? WHERE fingerprint = ?;";
+
+/// Unset the primary flag of the public keys in the table public_keys where the user_id matches
+pub const SQL_UPDATE_PUBLIC_KEYS_UNSET_PRIMARY_WHERE_USER_ID: &str =
+    "UPDATE public_keys SET is_primary = 0 WHERE user_id = ?;";
+
+/// Set the primary flag of a public key in the table public_keys where the user_id and fingerprint matches
+///
+/// Only a signing key (Ed25519) can be primary.
+pub const SQL_UPDATE_PUBLIC_KEYS_SET_PRIMARY_WHERE_USER_ID_AND_FINGERPRINT: &str =
+    "UPDATE public_keys SET is_primary = 1 WHERE user_id = ? AND fingerprint = ? AND key_type = 'Ed25519';";
+
+/// Delete a public key from the table public_keys where the fingerprint matches
+pub const SQL_DELETE_FROM_PUBLIC_KEYS_WHERE_FINGERPRINT: &str =
+    "DELETE FROM public_keys WHERE fingerprint = ?;";
+
+/*
+SQL statements for the database
+Table: symmetric_keys
+*/
+/// Create the table symmetric_keys if it does not exist
+pub const SQL_CREATE_TABLE_SYMMETRIC_KEYS: &str = "CREATE TABLE IF NOT EXISTS symmetric_keys (id BLOB PRIMARY KEY NOT NULL, algorithm TEXT NOT NULL, symmetric_key BLOB NOT NULL);";
+
+/// Insert a new symmetric key into the table symmetric_keys
+pub const SQL_INSERT_INTO_SYMMETRIC_KEYS: &str =
+    "INSERT INTO symmetric_keys (id, algorithm, symmetric_key) VALUES(?, ?, ?);";
+
+/// Select a symmetric key from the table symmetric_keys where the id matches
+///
+/// `SQL` - SELECT id, algorithm, symmetric_key FROM symmetric_keys WHERE id = ?;
+pub const SQL_SELECT_FROM_SYMMETRIC_KEYS_WHERE_ID: &str =
+    "SELECT id, algorithm, symmetric_key FROM symmetric_keys WHERE id = ?;";
+
+/*
+SQL statements for the database
+Table: identities
+*/
+/// Create the table identities if it does not exist
+///
+/// One row per user bound to a primary key (see `Identity`). The identity is deleted with the user.
+pub const SQL_CREATE_TABLE_IDENTITIES: &str = "CREATE TABLE IF NOT EXISTS identities (user_id TEXT PRIMARY KEY NOT NULL REFERENCES users (id) ON DELETE CASCADE, fingerprint BLOB NOT NULL, certification BLOB NOT NULL, revocation BLOB);";
+
+/// Create the index on identities(fingerprint) if it does not exist
+pub const SQL_CREATE_INDEX_IDENTITIES_FINGERPRINT: &str =
+    "CREATE INDEX IF NOT EXISTS identities_fingerprint ON identities (fingerprint);";
+
+/// Insert a new identity into the table identities
+pub const SQL_INSERT_INTO_IDENTITIES: &str =
+    "INSERT INTO identities (user_id, fingerprint, certification, revocation) VALUES(?, ?, ?, ?);";
+
+/// Select an identity with its user from the tables identities and users where the user_id matches
+///
+/// `SQL` - SELECT u.id, u.name, u.email, i.fingerprint, i.certification, i.revocation FROM identities AS i JOIN users AS u ON u.id = i.user_id WHERE i.user_id = ?;
+pub const SQL_SELECT_FROM_IDENTITIES_WHERE_USER_ID: &str =
+    "SELECT u.id, u.name, u.email, i.fingerprint, i.certification, i.revocation FROM identities AS i JOIN users AS u ON u.id = i.user_id WHERE i.user_id = ?;";
+
+/// Select the identities with their users from the tables identities and users where the fingerprint matches, ordered by user_id
+///
+/// `SQL` - SELECT u.id, u.name, u.email, i.fingerprint, i.certification, i.revocation FROM identities AS i JOIN users AS u ON u.id = i.user_id WHERE i.fingerprint = ? ORDER BY i.user_id;
+pub const SQL_SELECT_FROM_IDENTITIES_WHERE_FINGERPRINT: &str =
+    "SELECT u.id, u.name, u.email, i.fingerprint, i.certification, i.revocation FROM identities AS i JOIN users AS u ON u.id = i.user_id WHERE i.fingerprint = ? ORDER BY i.user_id;";
+
+/// Set the revocation of an identity in the table identities where the user_id matches and it is not revoked
+pub const SQL_UPDATE_IDENTITIES_SET_REVOCATION_WHERE_USER_ID: &str =
+    "UPDATE identities SET revocation = ? WHERE user_id = ? AND revocation IS NULL;";
+
+/*
+SQL statements for the database
+Table: certifications
+*/
+/// Create the table certifications if it does not exist
+///
+/// One row per issuer, certified key and user id (see `Certification`).
+/// The keys are not required to be in the ring.
+pub const SQL_CREATE_TABLE_CERTIFICATIONS: &str = "CREATE TABLE IF NOT EXISTS certifications (issuer BLOB NOT NULL, fingerprint BLOB NOT NULL, user_id TEXT NOT NULL, trust_level TEXT NOT NULL CHECK (trust_level IN ('None', 'Marginal', 'Full')), signature BLOB NOT NULL, PRIMARY KEY (issuer, fingerprint, user_id));";
+
+/// Create the index on certifications(fingerprint) if it does not exist
+pub const SQL_CREATE_INDEX_CERTIFICATIONS_FINGERPRINT: &str =
+    "CREATE INDEX IF NOT EXISTS certifications_fingerprint ON certifications (fingerprint);";
+
+/// Insert a new certification into the table certifications
+pub const SQL_INSERT_INTO_CERTIFICATIONS: &str =
+    "INSERT INTO certifications (issuer, fingerprint, user_id, trust_level, signature) VALUES(?, ?, ?, ?, ?);";
+
+/// Select the certifications from the table certifications
+///
+/// `SQL` - SELECT issuer, fingerprint, user_id, trust_level, signature FROM certifications;
+pub const SQL_SELECT_FROM_CERTIFICATIONS: &str =
+    "SELECT issuer, fingerprint, user_id, trust_level, signature FROM certifications;";
+
+/// Select the certifications from the table certifications where the certified fingerprint matches, ordered by issuer
+///
+/// `SQL` - SELECT issuer, fingerprint, user_id, trust_level, signature FROM certifications WHERE fingerprint = ? ORDER BY issuer, user_id;
+pub const SQL_SELECT_FROM_CERTIFICATIONS_WHERE_FINGERPRINT: &str =
+    "SELECT issuer, fingerprint, user_id, trust_level, signature FROM certifications WHERE fingerprint = ? ORDER BY issuer, user_id;";
+
+/// Select the certifications from the table certifications where the issuer matches, ordered by fingerprint
+///
+/// `SQL` - SELECT issuer, fingerprint, user_id, trust_level, signature FROM certifications WHERE issuer = ? ORDER BY fingerprint, user_id;
+pub const SQL_SELECT_FROM_CERTIFICATIONS_WHERE_ISSUER: &str =
+    "SELECT issuer, fingerprint, user_id, trust_level, signature FROM certifications WHERE issuer = ? ORDER BY fingerprint, user_id;";
+
+/// Delete the certifications from the table certifications where the issuer and certified fingerprint matches
+pub const SQL_DELETE_FROM_CERTIFICATIONS_WHERE_ISSUER_AND_FINGERPRINT: &str =
+    "DELETE FROM certifications WHERE issuer = ? AND fingerprint = ?;";
+
+/*
+SQL statements for the database
+Table: known_keys
+*/
+/// Create the table known_keys if it does not exist
+///
+/// One pinned fingerprint per user id (see `KnownKey`). The user is not required to be in the ring.
+pub const SQL_CREATE_TABLE_KNOWN_KEYS: &str = "CREATE TABLE IF NOT EXISTS known_keys (user_id TEXT PRIMARY KEY NOT NULL, fingerprint BLOB NOT NULL, pinned_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP);";
+
+/// Insert a new known key into the table known_keys
+pub const SQL_INSERT_INTO_KNOWN_KEYS: &str =
+    "INSERT INTO known_keys (user_id, fingerprint) VALUES(?, ?);";
+
+/// Select a known key from the table known_keys where the user_id matches
+///
+/// `SQL` - SELECT user_id, fingerprint, pinned_at FROM known_keys WHERE user_id = ?;
+pub const SQL_SELECT_FROM_KNOWN_KEYS_WHERE_USER_ID: &str =
+    "SELECT user_id, fingerprint, pinned_at FROM known_keys WHERE user_id = ?;";
+
+/// Update the fingerprint of a known key in the table known_keys where the user_id and fingerprint matches
+pub const SQL_UPDATE_KNOWN_KEYS_SET_FINGERPRINT_WHERE_USER_ID_AND_FINGERPRINT: &str =
+    "UPDATE known_keys SET fingerprint = ?, pinned_at = CURRENT_TIMESTAMP WHERE user_id = ? AND fingerprint = ?;";
+
+/*
+SQL statements for the database
+Table: known_keys_audit
+*/
+/// Create the table known_keys_audit if it does not exist
+///
+/// One row per change of the table known_keys (see `KnownKeyAudit`), in order.
+pub const SQL_CREATE_TABLE_KNOWN_KEYS_AUDIT: &str = "CREATE TABLE IF NOT EXISTS known_keys_audit (id INTEGER PRIMARY KEY AUTOINCREMENT, user_id TEXT NOT NULL, action TEXT NOT NULL CHECK (action IN ('Pin', 'AcceptNew', 'Replace')), old_fingerprint BLOB, new_fingerprint BLOB NOT NULL, changed_by TEXT NOT NULL, changed_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP);";
+
+/// Create the index on known_keys_audit(user_id) if it does not exist
+pub const SQL_CREATE_INDEX_KNOWN_KEYS_AUDIT_USER_ID: &str =
+    "CREATE INDEX IF NOT EXISTS known_keys_audit_user_id ON known_keys_audit (user_id);";
+
+/// Insert a new change into the table known_keys_audit
+pub const SQL_INSERT_INTO_KNOWN_KEYS_AUDIT: &str = "INSERT INTO known_keys_audit (user_id, action, old_fingerprint, new_fingerprint, changed_by) VALUES(?, ?, ?, ?, ?);";
+
+/// Select the changes from the table known_keys_audit where the user_id matches, in order
+///
+/// `SQL` - SELECT user_id, action, old_fingerprint, new_fingerprint, changed_by, changed_at FROM known_keys_audit WHERE user_id = ? ORDER BY id;
+pub const SQL_SELECT_FROM_KNOWN_KEYS_AUDIT_WHERE_USER_ID: &str =
+    "SELECT user_id, action, old_fingerprint, new_fingerprint, changed_by, changed_at FROM known_keys_audit WHERE user_id = ? ORDER BY id;";
+
+/*
+SQL statements for the database
+Table: bundles
+*/
+/// Create the table bundles if it does not exist
+///
+/// One imported bundle per user, encoded as signed (see `Bundle::encode`). The bundle is deleted with the user.
+pub const SQL_CREATE_TABLE_BUNDLES: &str = "CREATE TABLE IF NOT EXISTS bundles (user_id TEXT PRIMARY KEY NOT NULL REFERENCES users (id) ON DELETE CASCADE, bundle BLOB NOT NULL);";
+
+/// Insert a new bundle into the table bundles
+pub const SQL_INSERT_INTO_BUNDLES: &str = "INSERT INTO bundles (user_id, bundle) VALUES(?, ?);";
+
+/// Select a bundle from the table bundles where the user_id matches
+///
+/// `SQL` - SELECT bundle FROM bundles WHERE user_id = ?;
+pub const SQL_SELECT_FROM_BUNDLES_WHERE_USER_ID: &str =
+    "SELECT bundle FROM bundles WHERE user_id = ?;";
+
+/*
+SQL statements for the database
+Key queries (see `KeyQuery`): the keys (k) joined with their users (u)
+*/
+/// Select the private keys with their users
+pub const SQL_SELECT_FROM_PRIVATE_KEYS_JOIN_USERS: &str = "SELECT k.user_id, k.is_primary, k.key_type, k.expiry, k.private_key, k.fingerprint, k.signature FROM private_keys AS k JOIN users AS u ON u.id = k.user_id";
+
+/// Select the public keys with their users
+pub const SQL_SELECT_FROM_PUBLIC_KEYS_JOIN_USERS: &str = "SELECT k.user_id, k.is_primary, k.key_type, k.expiry, k.public_key, k.fingerprint, k.signature FROM public_keys AS k JOIN users AS u ON u.id = k.user_id";
+
+/// The fingerprint starts with the given uppercase hex digits (length, digits)
+pub const SQL_KEY_QUERY_FINGERPRINT_PREFIX: &str = "substr(hex(k.fingerprint), 1, ?) = ?";
+
+/// The key type matches
+pub const SQL_KEY_QUERY_KEY_TYPE: &str = "k.key_type = ?";
+
+/// The key expires on or after the given date, or never expires (`0000/00/00`)
+pub const SQL_KEY_QUERY_EXPIRES_AFTER: &str = "(k.expiry = '0000/00/00' OR k.expiry >= ?)";
+
+/// The key never expires (`0000/00/00`): 1, else 0
+pub const SQL_KEY_QUERY_NEVER_EXPIRES: &str = "k.expiry = '0000/00/00'";
+
+/// The key expires on or before the given date (a key that never expires does not)
+pub const SQL_KEY_QUERY_EXPIRES_BEFORE: &str = "(k.expiry <> '0000/00/00' AND k.expiry <= ?)";
+
+/// The primary flag matches
+pub const SQL_KEY_QUERY_PRIMARY: &str = "k.is_primary = ?";
+
+/// The user name contains the given text (ASCII case-insensitive)
+pub const SQL_KEY_QUERY_NAME_CONTAINS: &str = "instr(lower(u.name), lower(?)) > 0";
+
+/// The user email contains the given text (ASCII case-insensitive)
+pub const SQL_KEY_QUERY_EMAIL_CONTAINS: &str = "instr(lower(u.email), lower(?)) > 0";
+
+/// The limit and offset (a negative limit is no limit)
+pub const SQL_KEY_QUERY_LIMIT_OFFSET: &str = "LIMIT ? OFFSET ?";
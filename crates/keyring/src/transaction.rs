@@ -0,0 +1,214 @@
+use rusqlite as sqlite;
+
+use crate::{
+    bundle::Bundle, certification::Certification, identity::Identity, query, user::User,
+};
+
+use cck_asymmetric::{PrivateKey, PublicKey};
+use cck_symmetric::SymmetricKey;
+
+/// Transaction
+///
+/// The insert and get operations of `Ring`, applied atomically by `Ring::transaction`:
+/// committed if the closure returns `Ok`, rolled back otherwise.
+///
+/// # Example
+/// ```
+/// ring.transaction(|tx| {
+///     tx.insert_user(user.clone())?;
+///
+///     tx.insert_public_key(user.clone(), public_key.clone())
+/// })?;
+/// ```
+pub struct Transaction<'a>(pub(crate) sqlite::Transaction<'a>);
+
+impl Transaction<'_> {
+    /*
+        Insert
+    */
+
+    /// Insert a new user into the table users
+    pub fn insert_user(&mut self, user: impl Into<User>) -> cck_common::Result<()> {
+        query::insert_user(&self.0, user)
+    }
+
+    /// Insert a new private_key into the table private_keys
+    pub fn insert_private_key(
+        &mut self,
+        user: impl Into<User>,
+        private_key: impl Into<PrivateKey>,
+    ) -> cck_common::Result<()> {
+        query::insert_private_key(&self.0, user, private_key)
+    }
+
+    /// Insert a new public_key into the table public_keys
+    pub fn insert_public_key(
+        &mut self,
+        user: impl Into<User>,
+        public_key: impl Into<PublicKey>,
+    ) -> cck_common::Result<()> {
+        query::insert_public_key(&self.0, user, public_key)
+    }
+
+    /// Insert a new symmetric_key into the table symmetric_keys
+    pub fn insert_symmetric_key(&mut self, symmetric_key: &SymmetricKey) -> cck_common::Result<()> {
+        query::insert_symmetric_key(&self.0, symmetric_key)
+    }
+
+    /// Insert a new identity into the tables users and identities
+    ///
+    /// The identity is verified with the primary key before it is inserted (see `Identity::verify`).
+    pub fn insert_identity(
+        &mut self,
+        identity: &Identity,
+        primary_key: &PublicKey,
+    ) -> cck_common::Result<()> {
+        query::insert_identity(&self.0, identity, primary_key)
+    }
+
+    /// Insert a new certification into the table certifications
+    ///
+    /// The certification is verified with the issuer before it is inserted (see `Certification::verify`).
+    pub fn insert_certification(
+        &mut self,
+        certification: &Certification,
+        issuer: &PublicKey,
+    ) -> cck_common::Result<()> {
+        query::insert_certification(&self.0, certification, issuer)
+    }
+
+    /// Insert the user, the public keys and the bundle itself (with its signatures)
+    ///
+    /// The bundle is verified before it is inserted (see `Bundle::verify`).
+    /// It is stored as signed, so that `Ring::export_bundle` can forward it.
+    ///
+    /// # Errors
+    /// If the bundle is invalid, the user or a key already exists: the transaction must then be rolled back,
+    /// which `Ring::transaction` does.
+    pub fn import_bundle(&mut self, bundle: &Bundle) -> cck_common::Result<()> {
+        bundle.verify()?;
+
+        query::insert_user(&self.0, bundle.user().clone())?;
+
+        for public_key in bundle.public_keys() {
+            query::insert_public_key(&self.0, bundle.user().clone(), public_key.clone())?;
+        }
+
+        query::insert_bundle(&self.0, bundle)
+    }
+
+    /*
+        Get
+    */
+
+    /// Get a user from the table users where the id matches
+    pub fn get_user_where_id_matches(&self, id: impl Into<String>) -> cck_common::Result<User> {
+        query::get_user_where_id_matches(&self.0, id)
+    }
+
+    /// Get a user from the table users where the email matches
+    pub fn get_user_where_email_matches(
+        &self,
+        email: impl Into<String>,
+    ) -> cck_common::Result<User> {
+        query::get_user_where_email_matches(&self.0, email)
+    }
+
+    /// Get a user from the table users where the name matches
+    pub fn get_users_where_name_matches(
+        &self,
+        name: impl Into<String>,
+    ) -> cck_common::Result<Vec<User>> {
+        query::get_users_where_name_matches(&self.0, name)
+    }
+
+    /// Get a private_key from the table private_keys where the user and fingerprint matches
+    pub fn get_private_key_where_user_and_fingerprint_matches(
+        &self,
+        user: impl Into<User>,
+        fingerprint: &[u8],
+    ) -> cck_common::Result<PrivateKey> {
+        query::get_private_key_where_user_and_fingerprint_matches(&self.0, user, fingerprint)
+    }
+
+    /// Get a private_key from the table private_keys where the fingerprint matches
+    pub fn get_private_key_where_fingerprint_matches(
+        &self,
+        fingerprint: &[u8],
+    ) -> cck_common::Result<PrivateKey> {
+        query::get_private_key_where_fingerprint_matches(&self.0, fingerprint)
+    }
+
+    /// Get the private_keys from the table private_keys where the user matches
+    pub fn get_private_keys_where_user_matches(
+        &self,
+        user: impl Into<User>,
+    ) -> cck_common::Result<Vec<PrivateKey>> {
+        query::get_private_keys_where_user_matches(&self.0, user)
+    }
+
+    /// Get a public_key from the table public_keys where the user and fingerprint matches
+    pub fn get_public_key_where_user_and_fingerprint_matches(
+        &self,
+        user: impl Into<User>,
+        fingerprint: &[u8],
+    ) -> cck_common::Result<PublicKey> {
+        query::get_public_key_where_user_and_fingerprint_matches(&self.0, user, fingerprint)
+    }
+
+    /// Get the public_keys from the table public_keys where the user matches
+    pub fn get_public_keys_where_user_matches(
+        &self,
+        user: impl Into<User>,
+    ) -> cck_common::Result<Vec<PublicKey>> {
+        query::get_public_keys_where_user_matches(&self.0, user)
+    }
+
+    /// Get a public_key from the table public_keys where the fingerprint matches
+    pub fn get_public_key_where_fingerprint_matches(
+        &self,
+        fingerprint: &[u8],
+    ) -> cck_common::Result<PublicKey> {
+        query::get_public_key_where_fingerprint_matches(&self.0, fingerprint)
+    }
+
+    /// Get a symmetric_key from the table symmetric_keys where the id matches
+    pub fn get_symmetric_key_where_id_matches(
+        &self,
+        id: &[u8],
+    ) -> cck_common::Result<SymmetricKey> {
+        query::get_symmetric_key_where_id_matches(&self.0, id)
+    }
+
+    /// Get an identity from the table identities where the user id matches
+    pub fn get_identity_where_id_matches(
+        &self,
+        id: impl Into<String>,
+    ) -> cck_common::Result<Identity> {
+        query::get_identity_where_id_matches(&self.0, id)
+    }
+
+    /// Get the identities from the table identities where the fingerprint of the primary key matches, revoked or not
+    pub fn get_identities_where_fingerprint_matches(
+        &self,
+        fingerprint: &[u8],
+    ) -> cck_common::Result<Vec<Identity>> {
+        query::get_identities_where_fingerprint_matches(&self.0, fingerprint)
+    }
+
+    /// Get the certifications from the table certifications where the certified fingerprint matches
+    pub fn get_certifications_where_fingerprint_matches(
+        &self,
+        fingerprint: &[u8],
+    ) -> cck_common::Result<Vec<Certification>> {
+        query::get_certifications_where_fingerprint_matches(&self.0, fingerprint)
+    }
+
+    /// Get the certifications from the table certifications where the issuer matches
+    pub fn get_certifications_where_issuer_matches(
+        &self,
+        issuer: &[u8],
+    ) -> cck_common::Result<Vec<Certification>> {
+        query::get_certifications_where_issuer_matches(&self.0, issuer)
+    }
+}
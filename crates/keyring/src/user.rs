@@ -17,11 +17,7 @@ impl User {
         let email = email.into();
 
         // Validate email syntax
-        let re = Regex::new(REGEX_EMAIL_SYNTAX).unwrap();
-
-        if !re.is_match(&email) {
-            Err(cck_common::Error)?
-        }
+        validate_email(&email)?;
 
         // Generate user ID
         let bytes = cck_hash::blake3::digest(
@@ -37,6 +33,32 @@ impl User {
         Ok(Self { id, name, email })
     }
 
+    /// Creates a user from a stored or received id, name and email.
+    ///
+    /// # Errors
+    /// If the id is empty, or the email syntax is invalid.
+    pub(crate) fn from_parts(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        email: impl Into<String>,
+    ) -> cck_common::Result<Self> {
+        let id = id.into();
+
+        let email = email.into();
+
+        if id.is_empty() {
+            Err(cck_common::Error)?
+        }
+
+        validate_email(&email)?;
+
+        Ok(Self {
+            id,
+            name: name.into(),
+            email,
+        })
+    }
+
     /// Get the user id
     pub fn id(&self) -> &str {
         &self.id
@@ -51,4 +73,39 @@ impl User {
     pub fn email(&self) -> &str {
         &self.email
     }
+
+    /// Set the user name
+    ///
+    /// The id is unchanged: store it with `Ring::update_user` (not for the user of an identity).
+    pub fn set_name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.name = name.into();
+
+        self
+    }
+
+    /// Set the user email
+    ///
+    /// The id is unchanged: store it with `Ring::update_user`.
+    ///
+    /// # Errors
+    /// If the email syntax is invalid.
+    pub fn set_email(&mut self, email: impl Into<String>) -> cck_common::Result<&mut Self> {
+        let email = email.into();
+
+        validate_email(&email)?;
+
+        self.email = email;
+
+        Ok(self)
+    }
+}
+
+fn validate_email(email: &str) -> cck_common::Result<()> {
+    let re = Regex::new(REGEX_EMAIL_SYNTAX).unwrap();
+
+    if !re.is_match(email) {
+        Err(cck_common::Error)?
+    }
+
+    Ok(())
 }
@@ -0,0 +1,357 @@
+// e.g.
+// cargo test --package keyring --test bundle --  --nocapture
+
+use cck_asymmetric::Key;
+
+/// A ring with a user, their primary key and two subkeys.
+fn ring() -> (keyring::Ring, keyring::User) {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let user = keyring::User::new("name", "name@example.com").unwrap();
+
+    let mut primary_key = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519);
+
+    primary_key.set_primary(true).unwrap();
+
+    let signing_key = primary_key
+        .derive_key(cck_asymmetric::KeyType::Ed25519)
+        .unwrap();
+
+    let encryption_key = primary_key
+        .derive_key(cck_asymmetric::KeyType::X25519)
+        .unwrap();
+
+    ring.insert_user(user.clone()).unwrap();
+
+    for private_key in [primary_key, signing_key] {
+        ring.insert_private_key(user.clone(), private_key).unwrap();
+    }
+
+    // only the public part
+    ring.insert_public_key(user.clone(), encryption_key.public_key())
+        .unwrap();
+
+    (ring, user)
+}
+
+#[test]
+fn bundle_export_import() {
+    let (ring, user) = ring();
+
+    let bundle = ring.export_bundle(user.clone()).unwrap();
+
+    bundle.verify().unwrap();
+
+    assert_eq!(bundle.user(), &user);
+
+    assert_eq!(bundle.public_keys().len(), 3);
+
+    assert_eq!(bundle.primary_key().unwrap(), &bundle.public_keys()[0]);
+
+    let pem = bundle.to_pem().unwrap();
+
+    assert_eq!(pem.starts_with("-----BEGIN CCK CERTIFICATE-----"), true);
+
+    let decoded = keyring::Bundle::from_pem(&pem).unwrap();
+
+    assert_eq!(decoded, bundle);
+
+    let mut other = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    other.import_bundle(&decoded).unwrap();
+
+    assert_eq!(other.get_user_where_id_matches(user.id()).unwrap(), user);
+
+    let public_keys = other
+        .get_public_keys_where_user_matches(user.clone())
+        .unwrap();
+
+    assert_eq!(public_keys.len(), bundle.public_keys().len());
+
+    for public_key in bundle.public_keys() {
+        assert_eq!(public_keys.contains(public_key), true);
+    }
+
+    // no private key is exported
+    assert_eq!(
+        other
+            .get_private_keys_where_user_matches(user.clone())
+            .unwrap()
+            .is_empty(),
+        true
+    );
+
+    // Without the primary private key, the imported bundle is forwarded as signed.
+    let forwarded = other.export_bundle(user.clone()).unwrap();
+
+    assert_eq!(forwarded, bundle);
+
+    let mut third = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    third.import_bundle(&forwarded).unwrap();
+
+    assert_eq!(third.export_bundle(user.clone()).unwrap(), bundle);
+
+    // The bundle is deleted with the user.
+    other.delete_user(user.clone()).unwrap();
+
+    other.insert_user(user.clone()).unwrap();
+
+    assert_eq!(other.export_bundle(user).is_err(), true);
+}
+
+#[test]
+fn bundle_tampered() {
+    let (ring, user) = ring();
+
+    let bundle = ring.export_bundle(user.clone()).unwrap();
+
+    let mut invalid = Vec::new();
+
+    // The name and the email are covered by the self-signature.
+    let mut renamed = user.clone();
+
+    renamed.set_name("other");
+
+    invalid.push(keyring::Bundle::from_parts(
+        renamed,
+        bundle.public_keys().to_vec(),
+        bundle.self_signature().to_vec(),
+    ));
+
+    let mut email = user.clone();
+
+    email.set_email("other@example.com").unwrap();
+
+    invalid.push(keyring::Bundle::from_parts(
+        email,
+        bundle.public_keys().to_vec(),
+        bundle.self_signature().to_vec(),
+    ));
+
+    // A flipped bit of the self-signature.
+    let mut self_signature = bundle.self_signature().to_vec();
+
+    self_signature[0] ^= 1;
+
+    invalid.push(keyring::Bundle::from_parts(
+        user.clone(),
+        bundle.public_keys().to_vec(),
+        self_signature,
+    ));
+
+    // A flipped bit of a subkey signature.
+    let mut public_keys = bundle.public_keys().to_vec();
+
+    let public_key = &public_keys[1];
+
+    let mut signature = public_key.signature().unwrap().to_vec();
+
+    signature[0] ^= 1;
+
+    public_keys[1] = <cck_asymmetric::PublicKey as cck_asymmetric::Key>::from(
+        false,
+        public_key.key_type().clone(),
+        public_key.expiry().clone(),
+        public_key.as_bytes().to_vec(),
+        public_key.fingerprint().to_vec(),
+        Some(signature),
+    );
+
+    invalid.push(keyring::Bundle::from_parts(
+        user.clone(),
+        public_keys,
+        bundle.self_signature().to_vec(),
+    ));
+
+    // The primary flag, key type, expiry and public key of every key are covered by the self-signature.
+    for i in 0..bundle.public_keys().len() {
+        let public_key = &bundle.public_keys()[i];
+
+        // A later expiry.
+        let mut public_keys = bundle.public_keys().to_vec();
+
+        public_keys[i] = <cck_asymmetric::PublicKey as cck_asymmetric::Key>::from(
+            public_key.is_primary(),
+            public_key.key_type().clone(),
+            cck_asymmetric::Expiry::from((9, 9, 9, 9, 1, 2, 3, 1)),
+            public_key.as_bytes().to_vec(),
+            public_key.fingerprint().to_vec(),
+            public_key.signature().map(|signature| signature.to_vec()),
+        );
+
+        invalid.push(keyring::Bundle::from_parts(
+            user.clone(),
+            public_keys,
+            bundle.self_signature().to_vec(),
+        ));
+    }
+
+    // Another key type of a subkey (its binding signature only covers the public key).
+    let mut public_keys = bundle.public_keys().to_vec();
+
+    let public_key = &public_keys[1];
+
+    let key_type = match public_key.key_type() {
+        cck_asymmetric::KeyType::Ed25519 => cck_asymmetric::KeyType::X25519,
+        cck_asymmetric::KeyType::X25519 => cck_asymmetric::KeyType::Ed25519,
+    };
+
+    public_keys[1] = <cck_asymmetric::PublicKey as cck_asymmetric::Key>::from(
+        false,
+        key_type,
+        public_key.expiry().clone(),
+        public_key.as_bytes().to_vec(),
+        public_key.fingerprint().to_vec(),
+        public_key.signature().map(|signature| signature.to_vec()),
+    );
+
+    invalid.push(keyring::Bundle::from_parts(
+        user.clone(),
+        public_keys,
+        bundle.self_signature().to_vec(),
+    ));
+
+    // The keys in another order.
+    let mut public_keys = bundle.public_keys().to_vec();
+
+    public_keys.swap(1, 2);
+
+    invalid.push(keyring::Bundle::from_parts(
+        user.clone(),
+        public_keys,
+        bundle.self_signature().to_vec(),
+    ));
+
+    let mut other = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    for bundle in invalid {
+        assert_eq!(bundle.verify().is_err(), true);
+
+        // still encoded and decoded: verified on import
+        let decoded = keyring::Bundle::from_pem(bundle.to_pem().unwrap()).unwrap();
+
+        assert_eq!(other.import_bundle(&decoded).is_err(), true);
+
+        // nothing inserted
+        assert_eq!(other.get_user_where_id_matches(user.id()).is_err(), true);
+
+        for public_key in bundle.public_keys() {
+            assert_eq!(
+                other
+                    .get_public_key_where_fingerprint_matches(public_key.fingerprint())
+                    .is_err(),
+                true
+            );
+        }
+    }
+
+    other.import_bundle(&bundle).unwrap();
+}
+
+#[test]
+fn bundle_decode_invalid() {
+    let (ring, user) = ring();
+
+    let bundle = ring.export_bundle(user).unwrap();
+
+    let bytes = bundle.encode().unwrap();
+
+    assert_eq!(keyring::Bundle::decode(&bytes).unwrap(), bundle);
+
+    // truncated
+    for len in [0, 4, 5, bytes.len() / 2, bytes.len() - 1] {
+        assert_eq!(keyring::Bundle::decode(&bytes[..len]).is_err(), true);
+    }
+
+    // trailing bytes
+    let mut trailing = bytes.clone();
+
+    trailing.push(0);
+
+    assert_eq!(keyring::Bundle::decode(&trailing).is_err(), true);
+
+    // magic and version
+    for i in [0, 4] {
+        let mut invalid = bytes.clone();
+
+        invalid[i] ^= 0xFF;
+
+        assert_eq!(keyring::Bundle::decode(&invalid).is_err(), true);
+    }
+
+    // another label
+    let pem = bundle
+        .to_pem()
+        .unwrap()
+        .replace("CCK CERTIFICATE", "CCK PUBLIC KEY");
+
+    assert_eq!(keyring::Bundle::from_pem(pem).is_err(), true);
+
+    assert_eq!(keyring::Bundle::from_pem("not a pem").is_err(), true);
+}
+
+#[test]
+fn bundle_export_without_primary_key() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let user = keyring::User::new("name", "name@example.com").unwrap();
+
+    ring.insert_user(user.clone()).unwrap();
+
+    // no key
+    assert_eq!(ring.export_bundle(user.clone()).is_err(), true);
+
+    ring.insert_private_key(
+        user.clone(),
+        cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519),
+    )
+    .unwrap();
+
+    // a key, not primary
+    assert_eq!(ring.export_bundle(user).is_err(), true);
+}
+
+#[test]
+fn bundle_export_unbound_subkey() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let user = keyring::User::new("name", "name@example.com").unwrap();
+
+    let mut primary_key = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519);
+
+    primary_key.set_primary(true).unwrap();
+
+    // generated, not derived: no binding signature by the primary key
+    let unbound = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::X25519);
+
+    ring.insert_user(user.clone()).unwrap();
+
+    ring.insert_private_key(user.clone(), primary_key.clone())
+        .unwrap();
+
+    ring.insert_public_key(user.clone(), unbound.public_key())
+        .unwrap();
+
+    assert_eq!(ring.export_bundle(user.clone()).is_err(), true);
+
+    assert_eq!(
+        keyring::Bundle::new(user.clone(), &primary_key, vec![unbound.public_key()]).is_err(),
+        true
+    );
+
+    // derived by another primary key
+    let mut other = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519);
+
+    other.set_primary(true).unwrap();
+
+    let subkey = other
+        .derive_key(cck_asymmetric::KeyType::X25519)
+        .unwrap()
+        .public_key();
+
+    assert_eq!(
+        keyring::Bundle::new(user, &primary_key, vec![subkey]).is_err(),
+        true
+    );
+}
@@ -0,0 +1,382 @@
+// e.g.
+// cargo test --package keyring --test certification --  --nocapture
+
+use cck_asymmetric::Key;
+
+/// A primary key with an identity.
+fn identity(name: &str) -> (cck_asymmetric::PrivateKey, keyring::Identity) {
+    let mut primary_key = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519);
+
+    primary_key.set_primary(true).unwrap();
+
+    let identity =
+        keyring::Identity::new(&primary_key, name, format!("{}@example.com", name)).unwrap();
+
+    (primary_key, identity)
+}
+
+/// Certify the key of the identity by the issuer, and insert the certification.
+fn certify(
+    ring: &mut keyring::Ring,
+    issuer: &cck_asymmetric::PrivateKey,
+    identity: &keyring::Identity,
+    trust_level: keyring::TrustLevel,
+) -> keyring::Certification {
+    let certification =
+        keyring::Certification::new(issuer, identity.fingerprint(), identity.id(), trust_level)
+            .unwrap();
+
+    ring.insert_certification(&certification, &issuer.public_key())
+        .unwrap();
+
+    certification
+}
+
+#[test]
+fn certification_new() {
+    let (alice, _) = identity("alice");
+
+    let (bob, bob_identity) = identity("bob");
+
+    let certification = keyring::Certification::new(
+        &alice,
+        bob.fingerprint(),
+        bob_identity.id(),
+        keyring::TrustLevel::Marginal,
+    )
+    .unwrap();
+
+    certification.verify(&alice.public_key()).unwrap();
+
+    assert_eq!(certification.issuer(), alice.fingerprint());
+
+    assert_eq!(certification.fingerprint(), bob.fingerprint());
+
+    assert_eq!(certification.user_id(), bob_identity.id());
+
+    assert_eq!(certification.trust_level(), keyring::TrustLevel::Marginal);
+
+    // not the issuer
+    assert_eq!(certification.verify(&bob.public_key()).is_err(), true);
+
+    // The trust level, fingerprint and user id are signed.
+    for tampered in [
+        keyring::Certification::from_parts(
+            alice.fingerprint().to_vec(),
+            bob.fingerprint().to_vec(),
+            bob_identity.id(),
+            keyring::TrustLevel::Full,
+            certification.signature().to_vec(),
+        ),
+        keyring::Certification::from_parts(
+            alice.fingerprint().to_vec(),
+            identity("carol").0.fingerprint().to_vec(),
+            bob_identity.id(),
+            keyring::TrustLevel::Marginal,
+            certification.signature().to_vec(),
+        ),
+        keyring::Certification::from_parts(
+            alice.fingerprint().to_vec(),
+            bob.fingerprint().to_vec(),
+            "other",
+            keyring::TrustLevel::Marginal,
+            certification.signature().to_vec(),
+        ),
+    ] {
+        assert_eq!(tampered.verify(&alice.public_key()).is_err(), true);
+    }
+
+    // itself
+    assert_eq!(
+        keyring::Certification::new(
+            &alice,
+            alice.fingerprint(),
+            "alice",
+            keyring::TrustLevel::Full
+        )
+        .is_err(),
+        true
+    );
+
+    // not primary
+    assert_eq!(
+        keyring::Certification::new(
+            &cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519),
+            bob.fingerprint(),
+            bob_identity.id(),
+            keyring::TrustLevel::Full
+        )
+        .is_err(),
+        true
+    );
+
+    assert_eq!(
+        keyring::TrustLevel::from_string(keyring::TrustLevel::Marginal.to_string()).unwrap(),
+        keyring::TrustLevel::Marginal
+    );
+}
+
+#[test]
+fn certification_ring() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let (alice, _) = identity("alice");
+
+    let (carol, _) = identity("carol");
+
+    let (bob, bob_identity) = identity("bob");
+
+    let by_alice = certify(&mut ring, &alice, &bob_identity, keyring::TrustLevel::Full);
+
+    let by_carol = certify(&mut ring, &carol, &bob_identity, keyring::TrustLevel::None);
+
+    // already inserted
+    assert_eq!(
+        ring.insert_certification(&by_alice, &alice.public_key())
+            .is_err(),
+        true
+    );
+
+    // not verified by the issuer
+    let invalid = keyring::Certification::new(
+        &alice,
+        carol.fingerprint(),
+        "carol",
+        keyring::TrustLevel::Full,
+    )
+    .unwrap();
+
+    assert_eq!(
+        ring.insert_certification(&invalid, &carol.public_key())
+            .is_err(),
+        true
+    );
+
+    assert_eq!(
+        ring.get_certifications_where_issuer_matches(alice.fingerprint())
+            .unwrap(),
+        vec![by_alice.clone()]
+    );
+
+    let mut expected = vec![by_alice.clone(), by_carol.clone()];
+
+    expected.sort_by(|a, b| a.issuer().cmp(b.issuer()));
+
+    assert_eq!(
+        ring.get_certifications_where_fingerprint_matches(bob.fingerprint())
+            .unwrap(),
+        expected
+    );
+
+    ring.delete_certifications(alice.fingerprint(), bob.fingerprint())
+        .unwrap();
+
+    assert_eq!(
+        ring.delete_certifications(alice.fingerprint(), bob.fingerprint())
+            .is_err(),
+        true
+    );
+
+    assert_eq!(
+        ring.get_certifications_where_fingerprint_matches(bob.fingerprint())
+            .unwrap(),
+        vec![by_carol]
+    );
+
+    // in a transaction
+    ring.transaction(|tx| {
+        tx.insert_certification(&by_alice, &alice.public_key())?;
+
+        assert_eq!(
+            tx.get_certifications_where_issuer_matches(alice.fingerprint())?,
+            vec![by_alice.clone()]
+        );
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn certification_validity() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let (root, _) = identity("root");
+
+    let (full, full_identity) = identity("full");
+
+    let (none, none_identity) = identity("none");
+
+    let (marginal_1, marginal_1_identity) = identity("marginal1");
+
+    let (marginal_2, marginal_2_identity) = identity("marginal2");
+
+    let (_, leaf_identity) = identity("leaf");
+
+    let (_, other_identity) = identity("other");
+
+    let (_, unknown_identity) = identity("unknown");
+
+    // root -> full (Full) -> none (None) -> other
+    certify(&mut ring, &root, &full_identity, keyring::TrustLevel::Full);
+
+    certify(&mut ring, &full, &none_identity, keyring::TrustLevel::None);
+
+    certify(&mut ring, &none, &other_identity, keyring::TrustLevel::Full);
+
+    // root -> marginal1, marginal2 (Marginal) -> leaf
+    for identity in [&marginal_1_identity, &marginal_2_identity] {
+        certify(&mut ring, &root, identity, keyring::TrustLevel::Marginal);
+    }
+
+    certify(
+        &mut ring,
+        &marginal_1,
+        &leaf_identity,
+        keyring::TrustLevel::None,
+    );
+
+    let roots = [root.fingerprint()];
+
+    let is_valid = |ring: &keyring::Ring, identity: &keyring::Identity, max_depth: u32| {
+        ring.is_key_valid(identity.fingerprint(), &roots, max_depth)
+            .unwrap()
+    };
+
+    assert_eq!(
+        ring.is_key_valid(root.fingerprint(), &roots, 0).unwrap(),
+        true
+    );
+
+    assert_eq!(is_valid(&ring, &full_identity, 0), false);
+
+    assert_eq!(is_valid(&ring, &full_identity, 1), true);
+
+    // depth 2
+    assert_eq!(is_valid(&ring, &none_identity, 1), false);
+
+    assert_eq!(is_valid(&ring, &none_identity, 2), true);
+
+    // certified by a valid key that is not trusted as an introducer
+    assert_eq!(is_valid(&ring, &other_identity, 5), false);
+
+    // one marginal introducer is not enough
+    assert_eq!(is_valid(&ring, &leaf_identity, 5), false);
+
+    // two marginal introducers
+    certify(
+        &mut ring,
+        &marginal_2,
+        &leaf_identity,
+        keyring::TrustLevel::None,
+    );
+
+    assert_eq!(is_valid(&ring, &leaf_identity, 1), false);
+
+    assert_eq!(is_valid(&ring, &leaf_identity, 2), true);
+
+    // Two marginal introducers certify a key as Full: it is only a marginal introducer.
+    let (escalated, escalated_identity) = identity("escalated");
+
+    let (_, target_identity) = identity("target");
+
+    for introducer in [&marginal_1, &marginal_2] {
+        certify(
+            &mut ring,
+            introducer,
+            &escalated_identity,
+            keyring::TrustLevel::Full,
+        );
+    }
+
+    certify(
+        &mut ring,
+        &escalated,
+        &target_identity,
+        keyring::TrustLevel::None,
+    );
+
+    assert_eq!(is_valid(&ring, &escalated_identity, 2), true);
+
+    assert_eq!(is_valid(&ring, &target_identity, 5), false);
+
+    // With a second marginal introducer, the target is valid.
+    certify(
+        &mut ring,
+        &marginal_1,
+        &target_identity,
+        keyring::TrustLevel::None,
+    );
+
+    assert_eq!(is_valid(&ring, &target_identity, 5), true);
+
+    // A fully trusted introducer certifies a key as Full: another certification by a marginal introducer
+    // does not lower its trust level.
+    let (full_introduced, full_introduced_identity) = identity("fullintroduced");
+
+    let (_, downstream_identity) = identity("downstream");
+
+    certify(
+        &mut ring,
+        &full,
+        &full_introduced_identity,
+        keyring::TrustLevel::Full,
+    );
+
+    certify(
+        &mut ring,
+        &full_introduced,
+        &downstream_identity,
+        keyring::TrustLevel::None,
+    );
+
+    assert_eq!(is_valid(&ring, &downstream_identity, 5), true);
+
+    certify(
+        &mut ring,
+        &marginal_1,
+        &full_introduced_identity,
+        keyring::TrustLevel::Full,
+    );
+
+    assert_eq!(is_valid(&ring, &downstream_identity, 5), true);
+
+    // The same issuer counts once, whatever the user ids.
+    let (_, twice_identity) = identity("twice");
+
+    for user_id in ["a", "b"] {
+        let certification = keyring::Certification::new(
+            &marginal_1,
+            twice_identity.fingerprint(),
+            user_id,
+            keyring::TrustLevel::None,
+        )
+        .unwrap();
+
+        ring.insert_certification(&certification, &marginal_1.public_key())
+            .unwrap();
+    }
+
+    assert_eq!(is_valid(&ring, &twice_identity, 5), false);
+
+    assert_eq!(is_valid(&ring, &unknown_identity, 5), false);
+
+    // without the roots
+    assert_eq!(
+        ring.is_key_valid(
+            full_identity.fingerprint(),
+            &[unknown_identity.fingerprint()],
+            5
+        )
+        .unwrap(),
+        false
+    );
+
+    // The path is broken.
+    ring.delete_certifications(root.fingerprint(), full.fingerprint())
+        .unwrap();
+
+    assert_eq!(is_valid(&ring, &full_identity, 5), false);
+
+    assert_eq!(is_valid(&ring, &none_identity, 5), false);
+}
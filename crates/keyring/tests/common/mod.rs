@@ -0,0 +1,21 @@
+// Shared by the integration tests: `mod common;`
+
+/// A database file in the temporary directory, removed when dropped.
+pub struct TempDatabase(pub std::path::PathBuf);
+
+impl TempDatabase {
+    pub fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("keyring-{}-{}.sqlite", name, std::process::id()));
+
+        let _ = std::fs::remove_file(&path);
+
+        Self(path)
+    }
+}
+
+impl Drop for TempDatabase {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
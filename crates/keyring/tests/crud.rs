@@ -0,0 +1,279 @@
+// e.g.
+// cargo test --package keyring --test crud --  --nocapture
+
+use cck_asymmetric::Key;
+
+/// A ring with a user, a primary Ed25519 key, an Ed25519 key and an X25519 key (private and public).
+fn ring() -> (
+    keyring::Ring,
+    keyring::User,
+    Vec<cck_asymmetric::PrivateKey>,
+) {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let user = keyring::User::new("name", "name@example.com").unwrap();
+
+    ring.insert_user(user.clone()).unwrap();
+
+    let mut primary = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519);
+
+    primary.set_primary(true).unwrap();
+
+    let keys = vec![
+        primary,
+        cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519),
+        cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::X25519),
+    ];
+
+    for private_key in &keys {
+        ring.insert_private_key(user.clone(), private_key.clone())
+            .unwrap();
+
+        ring.insert_public_key(user.clone(), private_key.public_key())
+            .unwrap();
+    }
+
+    (ring, user, keys)
+}
+
+#[test]
+fn crud_delete_user() {
+    let (mut ring, user, keys) = ring();
+
+    let other = keyring::User::new("other", "other@example.com").unwrap();
+
+    ring.insert_user(other.clone()).unwrap();
+
+    ring.delete_user(user.clone()).unwrap();
+
+    assert_eq!(ring.get_user_where_id_matches(user.id()).is_err(), true);
+
+    // cascade
+    for private_key in &keys {
+        assert_eq!(
+            ring.get_private_key_where_fingerprint_matches(private_key.fingerprint())
+                .is_err(),
+            true
+        );
+
+        assert_eq!(
+            ring.get_public_key_where_fingerprint_matches(private_key.fingerprint())
+                .is_err(),
+            true
+        );
+    }
+
+    assert_eq!(ring.get_user_where_id_matches(other.id()).unwrap(), other);
+
+    assert_eq!(ring.delete_user(user).is_err(), true);
+}
+
+#[test]
+fn crud_delete_key_by_fingerprint() {
+    let (mut ring, user, keys) = ring();
+
+    ring.delete_key_by_fingerprint(keys[2].fingerprint())
+        .unwrap();
+
+    assert_eq!(
+        ring.get_private_key_where_fingerprint_matches(keys[2].fingerprint())
+            .is_err(),
+        true
+    );
+
+    assert_eq!(
+        ring.get_public_key_where_fingerprint_matches(keys[2].fingerprint())
+            .is_err(),
+        true
+    );
+
+    assert_eq!(
+        ring.get_private_keys_where_user_matches(user.clone())
+            .unwrap()
+            .len(),
+        2
+    );
+
+    assert_eq!(
+        ring.get_public_keys_where_user_matches(user).unwrap().len(),
+        2
+    );
+
+    assert_eq!(
+        ring.delete_key_by_fingerprint(keys[2].fingerprint())
+            .is_err(),
+        true
+    );
+}
+
+#[test]
+fn crud_update_user() {
+    let (mut ring, mut user, _) = ring();
+
+    user.set_name("renamed")
+        .set_email("renamed@example.com")
+        .unwrap();
+
+    ring.update_user(user.clone()).unwrap();
+
+    assert_eq!(ring.get_user_where_id_matches(user.id()).unwrap(), user);
+
+    assert_eq!(
+        ring.get_user_where_email_matches("name@example.com")
+            .is_err(),
+        true
+    );
+
+    assert_eq!(user.set_email("invalid").is_err(), true);
+
+    // unknown user
+    assert_eq!(
+        ring.update_user(keyring::User::new("unknown", "unknown@example.com").unwrap())
+            .is_err(),
+        true
+    );
+}
+
+#[test]
+fn crud_update_key_expiry() {
+    let (mut ring, _, keys) = ring();
+
+    let expiry = cck_asymmetric::Expiry::from((2, 0, 3, 0, 0, 6, 3, 0));
+
+    ring.update_key_expiry(keys[1].fingerprint(), &expiry)
+        .unwrap();
+
+    assert_eq!(
+        ring.get_private_key_where_fingerprint_matches(keys[1].fingerprint())
+            .unwrap()
+            .expiry(),
+        &expiry
+    );
+
+    assert_eq!(
+        ring.get_public_key_where_fingerprint_matches(keys[1].fingerprint())
+            .unwrap()
+            .expiry(),
+        &expiry
+    );
+
+    assert_eq!(
+        ring.get_private_key_where_fingerprint_matches(keys[0].fingerprint())
+            .unwrap()
+            .expiry(),
+        &cck_asymmetric::Expiry::default()
+    );
+
+    assert_eq!(ring.update_key_expiry(&[0u8; 32], &expiry).is_err(), true);
+}
+
+#[test]
+fn crud_set_primary_key() {
+    let (mut ring, user, keys) = ring();
+
+    let primary = |ring: &keyring::Ring| -> Vec<Vec<u8>> {
+        ring.get_public_keys_where_user_matches(user.clone())
+            .unwrap()
+            .into_iter()
+            .filter(|public_key| public_key.is_primary())
+            .map(|public_key| public_key.fingerprint().to_vec())
+            .collect()
+    };
+
+    assert_eq!(primary(&ring), vec![keys[0].fingerprint().to_vec()]);
+
+    ring.set_primary_key(user.clone(), keys[1].fingerprint())
+        .unwrap();
+
+    assert_eq!(primary(&ring), vec![keys[1].fingerprint().to_vec()]);
+
+    assert_eq!(
+        ring.get_private_key_where_fingerprint_matches(keys[1].fingerprint())
+            .unwrap()
+            .is_primary(),
+        true
+    );
+
+    assert_eq!(
+        ring.get_private_key_where_fingerprint_matches(keys[0].fingerprint())
+            .unwrap()
+            .is_primary(),
+        false
+    );
+
+    // An X25519 key cannot be primary: rolled back, the primary key is kept.
+    assert_eq!(
+        ring.set_primary_key(user.clone(), keys[2].fingerprint())
+            .is_err(),
+        true
+    );
+
+    assert_eq!(primary(&ring), vec![keys[1].fingerprint().to_vec()]);
+
+    // another user's key
+    let other = keyring::User::new("other", "other@example.com").unwrap();
+
+    ring.insert_user(other.clone()).unwrap();
+
+    assert_eq!(
+        ring.set_primary_key(other, keys[0].fingerprint()).is_err(),
+        true
+    );
+
+    assert_eq!(primary(&ring), vec![keys[1].fingerprint().to_vec()]);
+}
+
+#[test]
+fn crud_list() {
+    let (mut ring, user, keys) = ring();
+
+    let mut users = vec![user];
+
+    for i in 0..4 {
+        let user =
+            keyring::User::new(format!("user-{}", i), format!("user-{}@example.com", i)).unwrap();
+
+        ring.insert_user(user.clone()).unwrap();
+
+        users.push(user);
+    }
+
+    users.sort_by(|a, b| a.id().cmp(b.id()));
+
+    assert_eq!(ring.list_users(100, 0).unwrap(), users);
+
+    // pages
+    let mut listed = Vec::new();
+
+    for offset in (0..users.len()).step_by(2) {
+        let page = ring.list_users(2, offset as u32).unwrap();
+
+        assert_eq!(page.len() <= 2, true);
+
+        listed.extend(page);
+    }
+
+    assert_eq!(listed, users);
+
+    assert_eq!(ring.list_users(2, 100).unwrap().is_empty(), true);
+
+    let private_keys = ring.list_private_keys(100, 0).unwrap();
+
+    assert_eq!(private_keys.len(), keys.len());
+
+    for private_key in &keys {
+        assert_eq!(private_keys.contains(private_key), true);
+    }
+
+    assert_eq!(ring.list_private_keys(2, 0).unwrap().len(), 2);
+
+    assert_eq!(ring.list_private_keys(2, 2).unwrap().len(), 1);
+
+    let public_keys = ring.list_public_keys(100, 0).unwrap();
+
+    assert_eq!(public_keys.len(), keys.len());
+
+    for private_key in &keys {
+        assert_eq!(public_keys.contains(&private_key.public_key()), true);
+    }
+}
@@ -0,0 +1,93 @@
+// e.g.
+// cargo test --package keyring --test envelope --  --nocapture
+
+#[test]
+fn envelope_unwrap_key() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let kek = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    ring.insert_symmetric_key(&kek).unwrap();
+
+    let data_key = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    let wrapped =
+        cck_symmetric::wrap_key(cck_symmetric::WrappingKey::Symmetric(&kek), &data_key).unwrap();
+
+    assert_eq!(ring.unwrap_key(&wrapped).unwrap(), data_key);
+
+    // The KEK is not in the keyring.
+    let other = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    let wrapped =
+        cck_symmetric::wrap_key(cck_symmetric::WrappingKey::Symmetric(&other), &data_key).unwrap();
+
+    assert_eq!(ring.unwrap_key(&wrapped).is_err(), true);
+}
+
+#[test]
+fn envelope_rewrap() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let old_kek = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    let new_kek = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::Aes256Gcm);
+
+    ring.insert_symmetric_key(&old_kek).unwrap();
+
+    ring.insert_symmetric_key(&new_kek).unwrap();
+
+    let object = cck_symmetric::envelope_encrypt(
+        cck_symmetric::WrappingKey::Symmetric(&old_kek),
+        b"associated data",
+        b"hello",
+    )
+    .unwrap();
+
+    assert_eq!(
+        ring.envelope_decrypt(b"associated data", &object).unwrap(),
+        b"hello"
+    );
+
+    assert_eq!(ring.envelope_decrypt(b"other", &object).is_err(), true);
+
+    let rewrapped = ring.envelope_rewrap(&new_kek.id(), &object).unwrap();
+
+    assert_eq!(
+        cck_symmetric::envelope_wrapped_key(&rewrapped)
+            .unwrap()
+            .kek_id(),
+        &new_kek.id()
+    );
+
+    assert_eq!(
+        ring.envelope_decrypt(b"associated data", &rewrapped)
+            .unwrap(),
+        b"hello"
+    );
+
+    // The data key is the same: only the wrapped key changed.
+    assert_eq!(
+        ring.unwrap_key(&cck_symmetric::envelope_wrapped_key(&rewrapped).unwrap())
+            .unwrap(),
+        ring.unwrap_key(&cck_symmetric::envelope_wrapped_key(&object).unwrap())
+            .unwrap()
+    );
+
+    // The new KEK is not in the keyring.
+    assert_eq!(ring.envelope_rewrap(&[0u8; 32], &object).is_err(), true);
+
+    // The old KEK is not in the keyring.
+    let other = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    let object = cck_symmetric::envelope_encrypt(
+        cck_symmetric::WrappingKey::Symmetric(&other),
+        &[],
+        b"hello",
+    )
+    .unwrap();
+
+    assert_eq!(ring.envelope_decrypt(&[], &object).is_err(), true);
+
+    assert_eq!(ring.envelope_rewrap(&new_kek.id(), &object).is_err(), true);
+}
@@ -0,0 +1,280 @@
+// e.g.
+// cargo test --package keyring --test fingerprint --  --nocapture
+
+use cck_asymmetric::Key;
+
+fn fingerprint() -> cck_asymmetric::Fingerprint {
+    cck_asymmetric::Fingerprint::from_key(&cck_asymmetric::PrivateKey::generate(
+        cck_asymmetric::KeyType::Ed25519,
+    ))
+    .unwrap()
+}
+
+#[test]
+fn fingerprint_hex() {
+    let private_key = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519);
+
+    let fingerprint = cck_asymmetric::Fingerprint::from_key(&private_key).unwrap();
+
+    assert_eq!(fingerprint.as_bytes(), private_key.fingerprint());
+
+    let hex = fingerprint.to_hex();
+
+    assert_eq!(hex, fingerprint.to_string());
+
+    let groups: Vec<&str> = hex.split(' ').collect();
+
+    assert_eq!(groups.len(), 16);
+
+    for group in groups {
+        assert_eq!(group.len(), 4);
+
+        assert_eq!(
+            group
+                .chars()
+                .all(|ch| ch.is_ascii_digit() || ch.is_ascii_uppercase()),
+            true
+        );
+    }
+
+    // grouped or not, any case
+    for text in [
+        hex.clone(),
+        hex.to_lowercase(),
+        hex.replace(' ', ""),
+        hex.replace(' ', ":"),
+        format!("  {}\n", hex),
+    ] {
+        assert_eq!(
+            cck_asymmetric::Fingerprint::parse(text).unwrap(),
+            fingerprint
+        );
+    }
+
+    // not 32 bytes
+    assert_eq!(
+        cck_asymmetric::Fingerprint::parse(&hex[..hex.len() - 5]).is_err(),
+        true
+    );
+
+    assert_eq!(
+        cck_asymmetric::Fingerprint::from_bytes(&[0u8; 31]).is_err(),
+        true
+    );
+
+    assert_eq!(cck_asymmetric::Fingerprint::parse("").is_err(), true);
+}
+
+#[test]
+fn fingerprint_key_id() {
+    let fingerprint = fingerprint();
+
+    let key_id = fingerprint.key_id();
+
+    assert_eq!(key_id.as_bytes(), &fingerprint.as_bytes()[24..]);
+
+    let text = key_id.to_string();
+
+    assert_eq!(text.len(), 16);
+
+    // the end of the fingerprint
+    assert_eq!(fingerprint.to_hex().replace(' ', "").ends_with(&text), true);
+
+    for text in [
+        text.clone(),
+        text.to_lowercase(),
+        format!("0x{}", text),
+        key_id.to_hex(),
+    ] {
+        assert_eq!(cck_asymmetric::KeyId::parse(text).unwrap(), key_id);
+    }
+
+    assert_eq!(cck_asymmetric::KeyId::parse(&text[..14]).is_err(), true);
+
+    assert_eq!(
+        cck_asymmetric::KeyId::parse(fingerprint.to_hex()).is_err(),
+        true
+    );
+}
+
+#[test]
+fn fingerprint_words() {
+    // https://en.wikipedia.org/wiki/PGP_word_list
+    let mut bytes = [0u8; 32];
+
+    bytes[..4].copy_from_slice(&[0xE5, 0x82, 0x94, 0xF2]);
+
+    bytes[31] = 0xFF;
+
+    let fingerprint = cck_asymmetric::Fingerprint::from_bytes(&bytes).unwrap();
+
+    let words = fingerprint.to_words();
+
+    assert_eq!(
+        words.starts_with("topmost Istanbul Pluto vagabond aardvark adroitness"),
+        true
+    );
+
+    assert_eq!(words.ends_with("aardvark Yucatan"), true);
+
+    assert_eq!(words.split(' ').count(), 32);
+
+    let fingerprint = self::fingerprint();
+
+    let words = fingerprint.to_words();
+
+    for text in [
+        words.clone(),
+        words.to_uppercase(),
+        words.replace(' ', "\n"),
+    ] {
+        assert_eq!(
+            cck_asymmetric::Fingerprint::parse(text).unwrap(),
+            fingerprint
+        );
+    }
+
+    let list: Vec<&str> = words.split(' ').collect();
+
+    // a missing word
+    assert_eq!(
+        cck_asymmetric::Fingerprint::parse(list[1..].join(" ")).is_err(),
+        true
+    );
+
+    // swapped words: an even word at an odd position
+    let mut swapped = list.clone();
+
+    swapped.swap(0, 1);
+
+    assert_eq!(
+        cck_asymmetric::Fingerprint::parse(swapped.join(" ")).is_err(),
+        true
+    );
+
+    // an extra word
+    assert_eq!(
+        cck_asymmetric::Fingerprint::parse(format!("{} aardvark", words)).is_err(),
+        true
+    );
+}
+
+#[test]
+fn fingerprint_randomart() {
+    let fingerprint = fingerprint();
+
+    let art = fingerprint.to_randomart();
+
+    assert_eq!(art, fingerprint.to_randomart());
+
+    let lines: Vec<&str> = art.lines().collect();
+
+    assert_eq!(lines.len(), 11);
+
+    assert_eq!(lines[0], "+----[BLAKE3]-----+");
+
+    assert_eq!(lines[10], "+-----------------+");
+
+    for line in &lines[1..10] {
+        assert_eq!(line.len(), 19);
+
+        assert_eq!(line.starts_with('|') && line.ends_with('|'), true);
+    }
+
+    // the end, on the board
+    assert_eq!(lines[1..10].concat().matches('E').count(), 1);
+
+    // All the moves are up and left: from the center to the top left corner.
+    let zero = cck_asymmetric::Fingerprint::from_bytes(&[0u8; 32])
+        .unwrap()
+        .to_randomart();
+
+    let lines: Vec<&str> = zero.lines().collect();
+
+    assert_eq!(lines[1].starts_with("|E"), true);
+
+    assert_eq!(lines[5], "|        S        |");
+
+    assert_ne!(
+        self::fingerprint().to_randomart(),
+        fingerprint.to_randomart()
+    );
+}
+
+#[test]
+fn fingerprint_ring() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let user = keyring::User::new("name", "name@example.com").unwrap();
+
+    ring.insert_user(user.clone()).unwrap();
+
+    let mut keys = Vec::new();
+
+    for _ in 0..3 {
+        let private_key = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519);
+
+        ring.insert_private_key(user.clone(), private_key.clone())
+            .unwrap();
+
+        ring.insert_public_key(user.clone(), private_key.public_key())
+            .unwrap();
+
+        keys.push(private_key);
+    }
+
+    for private_key in &keys {
+        let fingerprint = cck_asymmetric::Fingerprint::from_key(private_key).unwrap();
+
+        let base64 = cck_format::base64ct::encode(private_key.fingerprint(), &mut [0u8; 64])
+            .unwrap()
+            .to_string();
+
+        for text in [
+            fingerprint.to_hex(),
+            fingerprint.to_hex().to_lowercase().replace(' ', ""),
+            fingerprint.to_words(),
+            base64,
+            fingerprint.key_id().to_string(),
+            fingerprint.key_id().to_hex(),
+        ] {
+            assert_eq!(
+                ring.get_private_keys_where_fingerprint_text_matches(&text)
+                    .unwrap(),
+                vec![private_key.clone()]
+            );
+
+            assert_eq!(
+                ring.get_public_keys_where_fingerprint_text_matches(&text)
+                    .unwrap(),
+                vec![private_key.public_key()]
+            );
+        }
+    }
+
+    // unknown
+    let unknown = fingerprint();
+
+    assert_eq!(
+        ring.get_public_keys_where_fingerprint_text_matches(unknown.to_words())
+            .unwrap()
+            .is_empty(),
+        true
+    );
+
+    assert_eq!(
+        ring.get_public_keys_where_fingerprint_text_matches(unknown.key_id().to_string())
+            .unwrap()
+            .is_empty(),
+        true
+    );
+
+    // neither a fingerprint nor a key id
+    for text in ["", "name", "ABCD", "' OR 1=1 --"] {
+        assert_eq!(
+            ring.get_private_keys_where_fingerprint_text_matches(text)
+                .is_err(),
+            true
+        );
+    }
+}
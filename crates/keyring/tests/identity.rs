@@ -0,0 +1,314 @@
+// e.g.
+// cargo test --package keyring --test identity --  --nocapture
+
+use cck_asymmetric::Key;
+
+fn primary_key() -> cck_asymmetric::PrivateKey {
+    let mut primary_key = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519);
+
+    primary_key.set_primary(true).unwrap();
+
+    primary_key
+}
+
+#[test]
+fn identity_new() {
+    let primary_key = primary_key();
+
+    let work = keyring::Identity::new(&primary_key, "name", "name@work.com").unwrap();
+
+    let personal = keyring::Identity::new(&primary_key, "name", "name@home.org").unwrap();
+
+    for identity in [&work, &personal] {
+        identity.verify(&primary_key.public_key()).unwrap();
+
+        assert_eq!(identity.fingerprint(), primary_key.fingerprint());
+
+        assert_eq!(identity.id().len(), 64);
+
+        assert_eq!(identity.is_revoked(), false);
+    }
+
+    assert_ne!(work.id(), personal.id());
+
+    // The id is derived from the primary key, the name and the email.
+    assert_eq!(
+        keyring::Identity::new(&primary_key, "name", "name@work.com")
+            .unwrap()
+            .id(),
+        work.id()
+    );
+
+    assert_ne!(
+        keyring::Identity::new(&self::primary_key(), "name", "name@work.com")
+            .unwrap()
+            .id(),
+        work.id()
+    );
+
+    // another key
+    assert_eq!(
+        work.verify(&self::primary_key().public_key()).is_err(),
+        true
+    );
+
+    // not primary
+    assert_eq!(
+        keyring::Identity::new(
+            &cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519),
+            "name",
+            "name@work.com"
+        )
+        .is_err(),
+        true
+    );
+
+    assert_eq!(
+        keyring::Identity::new(&primary_key, "name", "invalid").is_err(),
+        true
+    );
+}
+
+#[test]
+fn identity_tampered() {
+    let primary_key = primary_key();
+
+    let identity = keyring::Identity::new(&primary_key, "name", "name@work.com").unwrap();
+
+    let other = keyring::Identity::new(&primary_key, "other", "name@home.org").unwrap();
+
+    let mut invalid = Vec::new();
+
+    // The name changed, the id and certification are kept.
+    let mut user = identity.user().clone();
+
+    user.set_name("other");
+
+    invalid.push(keyring::Identity::from_parts(
+        user,
+        identity.fingerprint().to_vec(),
+        identity.certification().to_vec(),
+        None,
+    ));
+
+    // The certification of another identity.
+    invalid.push(keyring::Identity::from_parts(
+        identity.user().clone(),
+        identity.fingerprint().to_vec(),
+        other.certification().to_vec(),
+        None,
+    ));
+
+    // A user id that is not derived from the primary key.
+    invalid.push(keyring::Identity::from_parts(
+        keyring::User::new("name", "name@work.com").unwrap(),
+        identity.fingerprint().to_vec(),
+        identity.certification().to_vec(),
+        None,
+    ));
+
+    // The revocation of another identity.
+    let mut revoked = other.clone();
+
+    revoked.revoke(&primary_key).unwrap();
+
+    invalid.push(keyring::Identity::from_parts(
+        identity.user().clone(),
+        identity.fingerprint().to_vec(),
+        identity.certification().to_vec(),
+        revoked.revocation().map(|revocation| revocation.to_vec()),
+    ));
+
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    for identity in invalid {
+        assert_eq!(identity.verify(&primary_key.public_key()).is_err(), true);
+
+        assert_eq!(
+            ring.insert_identity(&identity, &primary_key.public_key())
+                .is_err(),
+            true
+        );
+
+        // nothing inserted
+        assert_eq!(ring.get_user_where_id_matches(identity.id()).is_err(), true);
+    }
+
+    revoked.verify(&primary_key.public_key()).unwrap();
+
+    ring.insert_identity(&identity, &primary_key.public_key())
+        .unwrap();
+}
+
+#[test]
+fn identity_ring() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let primary_key = primary_key();
+
+    let work = keyring::Identity::new(&primary_key, "name", "name@work.com").unwrap();
+
+    let personal = keyring::Identity::new(&primary_key, "name", "name@home.org").unwrap();
+
+    for identity in [&work, &personal] {
+        ring.insert_identity(identity, &primary_key.public_key())
+            .unwrap();
+    }
+
+    // already inserted
+    assert_eq!(
+        ring.insert_identity(&work, &primary_key.public_key())
+            .is_err(),
+        true
+    );
+
+    // The identity is a user: the keys are inserted with it.
+    assert_eq!(
+        &ring.get_user_where_id_matches(work.id()).unwrap(),
+        work.user()
+    );
+
+    ring.insert_private_key(work.user().clone(), primary_key.clone())
+        .unwrap();
+
+    assert_eq!(ring.get_identity_where_id_matches(work.id()).unwrap(), work);
+
+    let mut expected = vec![work.clone(), personal.clone()];
+
+    expected.sort_by(|a, b| a.id().cmp(b.id()));
+
+    assert_eq!(
+        ring.get_identities_where_fingerprint_matches(primary_key.fingerprint())
+            .unwrap(),
+        expected
+    );
+
+    // revoked independently
+    let revoked = ring.revoke_identity(work.id(), &primary_key).unwrap();
+
+    assert_eq!(revoked.is_revoked(), true);
+
+    revoked.verify(&primary_key.public_key()).unwrap();
+
+    assert_eq!(
+        ring.get_identity_where_id_matches(work.id()).unwrap(),
+        revoked
+    );
+
+    assert_eq!(
+        ring.get_identity_where_id_matches(personal.id())
+            .unwrap()
+            .is_revoked(),
+        false
+    );
+
+    // already revoked
+    assert_eq!(ring.revoke_identity(work.id(), &primary_key).is_err(), true);
+
+    // another primary key
+    assert_eq!(
+        ring.revoke_identity(personal.id(), &self::primary_key())
+            .is_err(),
+        true
+    );
+
+    assert_eq!(
+        ring.get_identity_where_id_matches(personal.id())
+            .unwrap()
+            .is_revoked(),
+        false
+    );
+
+    // The identity is deleted with its user.
+    ring.delete_user(personal.user().clone()).unwrap();
+
+    assert_eq!(
+        ring.get_identity_where_id_matches(personal.id()).is_err(),
+        true
+    );
+
+    assert_eq!(
+        ring.get_identities_where_fingerprint_matches(primary_key.fingerprint())
+            .unwrap(),
+        vec![revoked]
+    );
+}
+
+#[test]
+fn identity_transaction() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let primary_key = primary_key();
+
+    let identity = keyring::Identity::new(&primary_key, "name", "name@work.com").unwrap();
+
+    // rolled back with the transaction
+    assert_eq!(
+        ring.transaction(|tx| {
+            tx.insert_identity(&identity, &primary_key.public_key())?;
+
+            assert_eq!(tx.get_identity_where_id_matches(identity.id())?, identity);
+
+            tx.insert_identity(&identity, &primary_key.public_key())
+        })
+        .is_err(),
+        true
+    );
+
+    assert_eq!(
+        ring.get_identity_where_id_matches(identity.id()).is_err(),
+        true
+    );
+
+    ring.transaction(|tx| {
+        tx.insert_identity(&identity, &primary_key.public_key())?;
+
+        tx.insert_public_key(identity.user().clone(), primary_key.public_key())
+    })
+    .unwrap();
+
+    assert_eq!(
+        ring.get_identity_where_id_matches(identity.id()).unwrap(),
+        identity
+    );
+}
+
+#[test]
+fn identity_update_user() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let primary_key = primary_key();
+
+    let identity = keyring::Identity::new(&primary_key, "name", "name@work.com").unwrap();
+
+    ring.insert_identity(&identity, &primary_key.public_key())
+        .unwrap();
+
+    // The name and email are certified: they can't be updated.
+    let mut user = identity.user().clone();
+
+    user.set_name("other").set_email("other@work.com").unwrap();
+
+    assert_eq!(ring.update_user(user).is_err(), true);
+
+    assert_eq!(
+        &ring.get_user_where_id_matches(identity.id()).unwrap(),
+        identity.user()
+    );
+
+    ring.get_identity_where_id_matches(identity.id())
+        .unwrap()
+        .verify(&primary_key.public_key())
+        .unwrap();
+
+    // A user without an identity can still be updated.
+    let mut user = keyring::User::new("name", "name@example.com").unwrap();
+
+    ring.insert_user(user.clone()).unwrap();
+
+    user.set_name("other");
+
+    ring.update_user(user.clone()).unwrap();
+
+    assert_eq!(ring.get_user_where_id_matches(user.id()).unwrap(), user);
+}
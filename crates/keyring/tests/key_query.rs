@@ -0,0 +1,293 @@
+// e.g.
+// cargo test --package keyring --test key_query --  --nocapture
+
+use cck_asymmetric::Key;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn fingerprints<K: cck_asymmetric::Key>(keys: &[K]) -> Vec<Vec<u8>> {
+    keys.iter().map(|key| key.fingerprint().to_vec()).collect()
+}
+
+/// Alice: a primary Ed25519 key expiring 2030/01/01 and an X25519 key that never expires.
+///
+/// Bob: a primary Ed25519 key expiring 2025/06/30 and an X25519 key expiring 2027/12/31.
+fn ring() -> (keyring::Ring, Vec<cck_asymmetric::PrivateKey>) {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let alice = keyring::User::new("Alice", "alice@example.com").unwrap();
+
+    let bob = keyring::User::new("Bob", "bob@test.org").unwrap();
+
+    let mut keys = Vec::new();
+
+    for (user, primary_expiry, subkey_expiry) in [
+        (
+            &alice,
+            cck_asymmetric::Expiry::from((2, 0, 3, 0, 0, 1, 0, 1)),
+            cck_asymmetric::Expiry::default(),
+        ),
+        (
+            &bob,
+            cck_asymmetric::Expiry::from((2, 0, 2, 5, 0, 6, 3, 0)),
+            cck_asymmetric::Expiry::from((2, 0, 2, 7, 1, 2, 3, 1)),
+        ),
+    ] {
+        ring.insert_user(user.clone()).unwrap();
+
+        let mut primary = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519);
+
+        primary
+            .set_primary(true)
+            .unwrap()
+            .set_expiry(primary_expiry);
+
+        let mut subkey = primary.derive_key(cck_asymmetric::KeyType::X25519).unwrap();
+
+        subkey.set_expiry(subkey_expiry);
+
+        for private_key in [primary, subkey] {
+            ring.insert_private_key(user.clone(), private_key.clone())
+                .unwrap();
+
+            ring.insert_public_key(user.clone(), private_key.public_key())
+                .unwrap();
+
+            keys.push(private_key);
+        }
+    }
+
+    (ring, keys)
+}
+
+#[test]
+fn key_query_all() {
+    let (ring, keys) = ring();
+
+    let mut expected = fingerprints(&keys);
+
+    expected.sort();
+
+    // ordered by fingerprint
+    assert_eq!(
+        fingerprints(&ring.query_private_keys(&keyring::KeyQuery::new()).unwrap()),
+        expected
+    );
+
+    assert_eq!(
+        fingerprints(&ring.query_public_keys(&keyring::KeyQuery::new()).unwrap()),
+        expected
+    );
+
+    expected.reverse();
+
+    assert_eq!(
+        fingerprints(
+            &ring
+                .query_private_keys(&keyring::KeyQuery::new().descending())
+                .unwrap()
+        ),
+        expected
+    );
+}
+
+#[test]
+fn key_query_fingerprint_prefix() {
+    let (ring, keys) = ring();
+
+    for private_key in &keys {
+        let fingerprint = hex(private_key.fingerprint());
+
+        for prefix in [&fingerprint[..3], &fingerprint[..8], &fingerprint[..]] {
+            let found = ring
+                .query_private_keys(&keyring::KeyQuery::new().fingerprint_prefix(prefix))
+                .unwrap();
+
+            assert_eq!(found.contains(private_key), true);
+
+            for key in &found {
+                assert_eq!(hex(key.fingerprint()).starts_with(prefix), true);
+            }
+
+            // case-insensitive
+            assert_eq!(
+                ring.query_public_keys(
+                    &keyring::KeyQuery::new().fingerprint_prefix(prefix.to_uppercase())
+                )
+                .unwrap()
+                .contains(&private_key.public_key()),
+                true
+            );
+        }
+    }
+
+    // not hex
+    for prefix in ["zz", "ab%", "ab' OR 1=1 --"] {
+        assert_eq!(
+            ring.query_private_keys(&keyring::KeyQuery::new().fingerprint_prefix(prefix))
+                .is_err(),
+            true
+        );
+    }
+}
+
+#[test]
+fn key_query_filters() {
+    let (ring, keys) = ring();
+
+    let query = |query: keyring::KeyQuery| -> Vec<Vec<u8>> {
+        let mut found = fingerprints(&ring.query_private_keys(&query).unwrap());
+
+        found.sort();
+
+        found
+    };
+
+    let expected = |indexes: &[usize]| -> Vec<Vec<u8>> {
+        let mut expected: Vec<Vec<u8>> = indexes
+            .iter()
+            .map(|&i| keys[i].fingerprint().to_vec())
+            .collect();
+
+        expected.sort();
+
+        expected
+    };
+
+    assert_eq!(
+        query(keyring::KeyQuery::new().key_type(cck_asymmetric::KeyType::X25519)),
+        expected(&[1, 3])
+    );
+
+    assert_eq!(
+        query(keyring::KeyQuery::new().primary(true)),
+        expected(&[0, 2])
+    );
+
+    assert_eq!(
+        query(keyring::KeyQuery::new().primary(false)),
+        expected(&[1, 3])
+    );
+
+    // A key that never expires is never before, always after.
+    assert_eq!(
+        query(
+            keyring::KeyQuery::new()
+                .expires_before(cck_asymmetric::Expiry::from((2, 0, 2, 8, 0, 1, 0, 1)))
+        ),
+        expected(&[2, 3])
+    );
+
+    assert_eq!(
+        query(
+            keyring::KeyQuery::new()
+                .expires_after(cck_asymmetric::Expiry::from((2, 0, 2, 6, 0, 1, 0, 1)))
+        ),
+        expected(&[0, 1, 3])
+    );
+
+    // range, bounds included
+    assert_eq!(
+        query(
+            keyring::KeyQuery::new()
+                .expires_after(cck_asymmetric::Expiry::from((2, 0, 2, 5, 0, 6, 3, 0)))
+                .expires_before(cck_asymmetric::Expiry::from((2, 0, 2, 7, 1, 2, 3, 1)))
+        ),
+        expected(&[2, 3])
+    );
+
+    assert_eq!(
+        query(keyring::KeyQuery::new().name_contains("LIC")),
+        expected(&[0, 1])
+    );
+
+    assert_eq!(
+        query(keyring::KeyQuery::new().email_contains("test.org")),
+        expected(&[2, 3])
+    );
+
+    // all filters must match
+    assert_eq!(
+        query(
+            keyring::KeyQuery::new()
+                .email_contains("@")
+                .key_type(cck_asymmetric::KeyType::Ed25519)
+                .expires_after(cck_asymmetric::Expiry::from((2, 0, 2, 6, 0, 1, 0, 1)))
+        ),
+        expected(&[0])
+    );
+
+    // values are parameters
+    assert_eq!(
+        query(keyring::KeyQuery::new().name_contains("' OR 1=1 --")),
+        expected(&[])
+    );
+}
+
+#[test]
+fn key_query_order_and_limit() {
+    let (ring, keys) = ring();
+
+    // 2025/06/30 < 2027/12/31 < 2030/01/01 < never (0000/00/00)
+    let by_expiry = vec![
+        keys[2].fingerprint().to_vec(),
+        keys[3].fingerprint().to_vec(),
+        keys[0].fingerprint().to_vec(),
+        keys[1].fingerprint().to_vec(),
+    ];
+
+    let query = keyring::KeyQuery::new().order_by(keyring::KeyOrder::Expiry);
+
+    assert_eq!(
+        fingerprints(&ring.query_private_keys(&query).unwrap()),
+        by_expiry
+    );
+
+    assert_eq!(
+        fingerprints(&ring.query_private_keys(&query.clone().limit(2)).unwrap()),
+        by_expiry[..2]
+    );
+
+    assert_eq!(
+        fingerprints(
+            &ring
+                .query_private_keys(&query.clone().limit(2).offset(1))
+                .unwrap()
+        ),
+        by_expiry[1..3]
+    );
+
+    assert_eq!(
+        fingerprints(&ring.query_private_keys(&query.clone().offset(3)).unwrap()),
+        by_expiry[3..]
+    );
+
+    let mut descending = by_expiry.clone();
+
+    descending.reverse();
+
+    assert_eq!(
+        fingerprints(&ring.query_private_keys(&query.descending()).unwrap()),
+        descending
+    );
+
+    // by user name: Alice's keys first
+    let by_name = ring
+        .query_public_keys(&keyring::KeyQuery::new().order_by(keyring::KeyOrder::Name))
+        .unwrap();
+
+    let alice = fingerprints(&keys[..2]);
+
+    assert_eq!(alice.contains(&by_name[0].fingerprint().to_vec()), true);
+
+    assert_eq!(alice.contains(&by_name[1].fingerprint().to_vec()), true);
+
+    assert_eq!(
+        ring.query_public_keys(&keyring::KeyQuery::new().limit(0))
+            .unwrap()
+            .is_empty(),
+        true
+    );
+}
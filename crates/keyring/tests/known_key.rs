@@ -0,0 +1,240 @@
+// e.g.
+// cargo test --package keyring --test known_key --  --nocapture
+
+use cck_asymmetric::Key;
+
+fn fingerprint() -> Vec<u8> {
+    cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519)
+        .fingerprint()
+        .to_vec()
+}
+
+#[test]
+fn known_key_pin() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let first = fingerprint();
+
+    let second = fingerprint();
+
+    // who made the change is required
+    assert_eq!(ring.pin_key("peer", &first, "").is_err(), true);
+
+    assert_eq!(
+        ring.get_known_key_where_user_id_matches("peer").is_err(),
+        true
+    );
+
+    // first use
+    assert_eq!(
+        ring.pin_key("peer", &first, "alice").unwrap(),
+        keyring::KeyStatus::New
+    );
+
+    assert_eq!(
+        ring.pin_key("peer", &first, "alice").unwrap(),
+        keyring::KeyStatus::Known
+    );
+
+    // another fingerprint is not pinned
+    assert_eq!(
+        ring.pin_key("peer", &second, "alice").unwrap(),
+        keyring::KeyStatus::Changed {
+            pinned: first.clone()
+        }
+    );
+
+    // who made the change is required, whatever the status
+    assert_eq!(ring.pin_key("peer", &first, "").is_err(), true);
+
+    assert_eq!(ring.pin_key("peer", &second, "").is_err(), true);
+
+    let known_key = ring.get_known_key_where_user_id_matches("peer").unwrap();
+
+    assert_eq!(known_key.user_id(), "peer");
+
+    assert_eq!(known_key.fingerprint(), first);
+
+    assert_eq!(known_key.pinned_at().is_empty(), false);
+
+    // other user ids are independent
+    assert_eq!(
+        ring.pin_key("other", &second, "alice").unwrap(),
+        keyring::KeyStatus::New
+    );
+
+    let audit = ring
+        .get_known_key_audit_where_user_id_matches("peer")
+        .unwrap();
+
+    assert_eq!(audit.len(), 1);
+
+    assert_eq!(audit[0].user_id(), "peer");
+
+    assert_eq!(audit[0].action(), keyring::KnownKeyAction::Pin);
+
+    assert_eq!(audit[0].old_fingerprint(), None);
+
+    assert_eq!(audit[0].new_fingerprint(), first);
+
+    assert_eq!(audit[0].changed_by(), "alice");
+}
+
+#[test]
+fn known_key_verify() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let first = fingerprint();
+
+    let second = fingerprint();
+
+    assert_eq!(
+        ring.check_known_key("peer", &first).unwrap(),
+        keyring::KeyStatus::New
+    );
+
+    // not pinned by a check
+    assert_eq!(ring.verify_known_key("peer", &first).is_err(), true);
+
+    ring.pin_key("peer", &first, "alice").unwrap();
+
+    ring.verify_known_key("peer", &first).unwrap();
+
+    assert_eq!(
+        ring.check_known_key("peer", &second).unwrap(),
+        keyring::KeyStatus::Changed {
+            pinned: first.clone()
+        }
+    );
+
+    assert_eq!(ring.verify_known_key("peer", &second).is_err(), true);
+}
+
+#[test]
+fn known_key_accept_new() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let first = fingerprint();
+
+    let second = fingerprint();
+
+    // not pinned
+    assert_eq!(
+        ring.accept_new_known_key("peer", &first, "bob").is_err(),
+        true
+    );
+
+    ring.pin_key("peer", &first, "alice").unwrap();
+
+    // not changed
+    assert_eq!(
+        ring.accept_new_known_key("peer", &first, "bob").is_err(),
+        true
+    );
+
+    assert_eq!(
+        ring.accept_new_known_key("peer", &second, "").is_err(),
+        true
+    );
+
+    ring.verify_known_key("peer", &first).unwrap();
+
+    ring.accept_new_known_key("peer", &second, "bob").unwrap();
+
+    ring.verify_known_key("peer", &second).unwrap();
+
+    assert_eq!(
+        ring.check_known_key("peer", &first).unwrap(),
+        keyring::KeyStatus::Changed {
+            pinned: second.clone()
+        }
+    );
+
+    let audit = ring
+        .get_known_key_audit_where_user_id_matches("peer")
+        .unwrap();
+
+    assert_eq!(audit.len(), 2);
+
+    assert_eq!(audit[1].action(), keyring::KnownKeyAction::AcceptNew);
+
+    assert_eq!(audit[1].old_fingerprint(), Some(first.as_slice()));
+
+    assert_eq!(audit[1].new_fingerprint(), second);
+
+    assert_eq!(audit[1].changed_by(), "bob");
+}
+
+#[test]
+fn known_key_replace() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let first = fingerprint();
+
+    let second = fingerprint();
+
+    let third = fingerprint();
+
+    // not pinned
+    assert_eq!(
+        ring.replace_known_key("peer", &first, &second, "bob")
+            .is_err(),
+        true
+    );
+
+    ring.pin_key("peer", &first, "alice").unwrap();
+
+    // not the pinned fingerprint
+    assert_eq!(
+        ring.replace_known_key("peer", &third, &second, "bob")
+            .is_err(),
+        true
+    );
+
+    // the same fingerprint
+    assert_eq!(
+        ring.replace_known_key("peer", &first, &first, "bob")
+            .is_err(),
+        true
+    );
+
+    // who made the change is required
+    assert_eq!(
+        ring.replace_known_key("peer", &first, &second, "").is_err(),
+        true
+    );
+
+    ring.verify_known_key("peer", &first).unwrap();
+
+    ring.replace_known_key("peer", &first, &second, "bob")
+        .unwrap();
+
+    ring.verify_known_key("peer", &second).unwrap();
+
+    // already replaced
+    assert_eq!(
+        ring.replace_known_key("peer", &first, &third, "carol")
+            .is_err(),
+        true
+    );
+
+    let audit = ring
+        .get_known_key_audit_where_user_id_matches("peer")
+        .unwrap();
+
+    let actions: Vec<keyring::KnownKeyAction> = audit.iter().map(|audit| audit.action()).collect();
+
+    assert_eq!(
+        actions,
+        vec![
+            keyring::KnownKeyAction::Pin,
+            keyring::KnownKeyAction::Replace
+        ]
+    );
+
+    assert_eq!(audit[1].old_fingerprint(), Some(first.as_slice()));
+
+    assert_eq!(audit[1].new_fingerprint(), second);
+
+    assert_eq!(audit[1].changed_by(), "bob");
+}
@@ -0,0 +1,273 @@
+// e.g.
+// cargo test --package keyring --test ring --  --nocapture
+
+mod common;
+
+use cck_asymmetric::Key;
+
+use common::TempDatabase;
+
+/// A primary Ed25519 key with an expiry, and an X25519 subkey with a signature.
+fn keys() -> (cck_asymmetric::PrivateKey, cck_asymmetric::PrivateKey) {
+    let mut primary = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519);
+
+    primary
+        .set_primary(true)
+        .unwrap()
+        .set_expiry(cck_asymmetric::Expiry::from((2, 0, 3, 0, 1, 2, 3, 1)));
+
+    let subkey = primary.derive_key(cck_asymmetric::KeyType::X25519).unwrap();
+
+    (primary, subkey)
+}
+
+#[test]
+fn ring_user() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let user = keyring::User::new("name", "name@example.com").unwrap();
+
+    let other = keyring::User::new("name", "other@example.com").unwrap();
+
+    ring.insert_user(user.clone()).unwrap();
+
+    ring.insert_user(other.clone()).unwrap();
+
+    assert_eq!(ring.get_user_where_id_matches(user.id()).unwrap(), user);
+
+    assert_eq!(
+        ring.get_user_where_email_matches("name@example.com")
+            .unwrap(),
+        user
+    );
+
+    let mut users = ring.get_users_where_name_matches("name").unwrap();
+
+    users.sort();
+
+    let mut expected = vec![user, other];
+
+    expected.sort();
+
+    assert_eq!(users, expected);
+
+    assert_eq!(ring.get_user_where_id_matches("unknown").is_err(), true);
+
+    assert_eq!(
+        ring.get_users_where_name_matches("unknown")
+            .unwrap()
+            .is_empty(),
+        true
+    );
+}
+
+#[test]
+fn ring_private_key() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let user = keyring::User::new("name", "name@example.com").unwrap();
+
+    let other = keyring::User::new("other", "other@example.com").unwrap();
+
+    ring.insert_user(user.clone()).unwrap();
+
+    ring.insert_user(other.clone()).unwrap();
+
+    let (primary, subkey) = keys();
+
+    for private_key in [&primary, &subkey] {
+        ring.insert_private_key(user.clone(), private_key.clone())
+            .unwrap();
+    }
+
+    for private_key in [&primary, &subkey] {
+        assert_eq!(
+            &ring
+                .get_private_key_where_fingerprint_matches(private_key.fingerprint())
+                .unwrap(),
+            private_key
+        );
+
+        assert_eq!(
+            &ring
+                .get_private_key_where_user_and_fingerprint_matches(
+                    user.clone(),
+                    private_key.fingerprint()
+                )
+                .unwrap(),
+            private_key
+        );
+
+        assert_eq!(
+            ring.get_private_key_where_user_and_fingerprint_matches(
+                other.clone(),
+                private_key.fingerprint()
+            )
+            .is_err(),
+            true
+        );
+    }
+
+    let private_keys = ring.get_private_keys_where_user_matches(user).unwrap();
+
+    assert_eq!(private_keys.len(), 2);
+
+    assert_eq!(private_keys.contains(&primary), true);
+
+    assert_eq!(private_keys.contains(&subkey), true);
+
+    assert_eq!(
+        ring.get_private_keys_where_user_matches(other)
+            .unwrap()
+            .is_empty(),
+        true
+    );
+
+    assert_eq!(
+        ring.get_private_key_where_fingerprint_matches(&[0u8; 32])
+            .is_err(),
+        true
+    );
+}
+
+#[test]
+fn ring_public_key() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let user = keyring::User::new("name", "name@example.com").unwrap();
+
+    let other = keyring::User::new("other", "other@example.com").unwrap();
+
+    ring.insert_user(user.clone()).unwrap();
+
+    ring.insert_user(other.clone()).unwrap();
+
+    let (primary, subkey) = keys();
+
+    let (primary, subkey) = (primary.public_key(), subkey.public_key());
+
+    for public_key in [&primary, &subkey] {
+        ring.insert_public_key(user.clone(), public_key.clone())
+            .unwrap();
+    }
+
+    for public_key in [&primary, &subkey] {
+        assert_eq!(
+            &ring
+                .get_public_key_where_fingerprint_matches(public_key.fingerprint())
+                .unwrap(),
+            public_key
+        );
+
+        assert_eq!(
+            &ring
+                .get_public_key_where_user_and_fingerprint_matches(
+                    user.clone(),
+                    public_key.fingerprint()
+                )
+                .unwrap(),
+            public_key
+        );
+
+        assert_eq!(
+            ring.get_public_key_where_user_and_fingerprint_matches(
+                other.clone(),
+                public_key.fingerprint()
+            )
+            .is_err(),
+            true
+        );
+    }
+
+    let public_keys = ring.get_public_keys_where_user_matches(user).unwrap();
+
+    assert_eq!(public_keys.len(), 2);
+
+    assert_eq!(public_keys.contains(&primary), true);
+
+    assert_eq!(public_keys.contains(&subkey), true);
+
+    assert_eq!(
+        ring.get_public_keys_where_user_matches(other)
+            .unwrap()
+            .is_empty(),
+        true
+    );
+
+    assert_eq!(
+        ring.get_public_key_where_fingerprint_matches(&[0u8; 32])
+            .is_err(),
+        true
+    );
+}
+
+#[test]
+fn ring_symmetric_key() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let symmetric_key = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    ring.insert_symmetric_key(&symmetric_key).unwrap();
+
+    let stored = ring
+        .get_symmetric_key_where_id_matches(&symmetric_key.id())
+        .unwrap();
+
+    assert_eq!(stored.id(), symmetric_key.id());
+
+    assert_eq!(stored.algorithm(), symmetric_key.algorithm());
+
+    assert_eq!(stored.as_bytes(), symmetric_key.as_bytes());
+
+    assert_eq!(
+        ring.get_symmetric_key_where_id_matches(&[0u8; 32]).is_err(),
+        true
+    );
+}
+
+#[test]
+fn ring_invalid_row() {
+    let database = TempDatabase::new("invalid-row");
+
+    let user = keyring::User::new("name", "name@example.com").unwrap();
+
+    let mut ring = keyring::RingBuilder::new(&database.0).unwrap().build();
+
+    ring.insert_user(user.clone()).unwrap();
+
+    ring.close().unwrap();
+
+    // Rows written by another client: an unknown key type, an invalid expiry and a truncated key.
+    {
+        let conn = rusqlite::Connection::open(&database.0).unwrap();
+
+        for (key_type, expiry, key, fingerprint) in [
+            ("RSA", "0000/00/00", vec![0u8; 32], vec![1u8; 32]),
+            ("Ed25519", "2030/1/1", vec![0u8; 32], vec![2u8; 32]),
+            ("Ed25519", "0000/00/00", vec![0u8; 16], vec![3u8; 32]),
+        ] {
+            conn.execute(
+                "INSERT INTO private_keys (user_id, is_primary, key_type, expiry, private_key, fingerprint, signature) VALUES(?, ?, ?, ?, ?, ?, ?);",
+                rusqlite::params![user.id(), 0, key_type, expiry, key, fingerprint, None::<Vec<u8>>],
+            )
+            .unwrap();
+        }
+    }
+
+    let ring = keyring::RingBuilder::new(&database.0).unwrap().build();
+
+    for fingerprint in [[1u8; 32], [2u8; 32], [3u8; 32]] {
+        assert_eq!(
+            ring.get_private_key_where_fingerprint_matches(&fingerprint)
+                .is_err(),
+            true
+        );
+    }
+
+    assert_eq!(
+        ring.get_private_keys_where_user_matches(user).is_err(),
+        true
+    );
+
+    ring.close().unwrap();
+}
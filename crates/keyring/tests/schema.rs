@@ -0,0 +1,204 @@
+// e.g.
+// cargo test --package keyring --test schema --  --nocapture
+
+mod common;
+
+use cck_asymmetric::Key;
+
+use common::TempDatabase;
+
+#[test]
+fn schema() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    assert_eq!(ring.schema_version().unwrap(), keyring::SCHEMA_VERSION);
+
+    let user = keyring::User::new("name", "name@example.com").unwrap();
+
+    ring.insert_user(user.clone()).unwrap();
+
+    // duplicate user
+    assert_eq!(ring.insert_user(user.clone()).is_err(), true);
+
+    let private_key = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519);
+
+    ring.insert_private_key(user.clone(), private_key.clone())
+        .unwrap();
+
+    ring.insert_public_key(user.clone(), private_key.public_key())
+        .unwrap();
+
+    // duplicate fingerprint
+    assert_eq!(
+        ring.insert_private_key(user.clone(), private_key.clone())
+            .is_err(),
+        true
+    );
+
+    assert_eq!(
+        ring.insert_public_key(user.clone(), private_key.public_key())
+            .is_err(),
+        true
+    );
+
+    // unknown user
+    let unknown = keyring::User::new("unknown", "unknown@example.com").unwrap();
+
+    assert_eq!(
+        ring.insert_private_key(
+            unknown.clone(),
+            cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::X25519)
+        )
+        .is_err(),
+        true
+    );
+
+    assert_eq!(
+        ring.insert_public_key(
+            unknown,
+            cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::X25519).public_key()
+        )
+        .is_err(),
+        true
+    );
+
+    // duplicate symmetric key
+    let symmetric_key = cck_symmetric::SymmetricKey::generate(cck_symmetric::Algorithm::default());
+
+    ring.insert_symmetric_key(&symmetric_key).unwrap();
+
+    assert_eq!(ring.insert_symmetric_key(&symmetric_key).is_err(), true);
+
+    assert_eq!(
+        ring.get_private_keys_where_user_matches(user)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn schema_migration() {
+    let database = TempDatabase::new("migration");
+
+    let private_key = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519);
+
+    let orphan_key = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::X25519);
+
+    // A database created before the migrations: no constraints, duplicate rows and a key without a user.
+    {
+        let conn = rusqlite::Connection::open(&database.0).unwrap();
+
+        conn.execute_batch(
+            "CREATE TABLE users (id TEXT, name TEXT, email TEXT);
+            CREATE TABLE private_keys (user_id TEXT, is_primary INTEGER, key_type TEXT, expiry TEXT, private_key BLOB, fingerprint BLOB, signature BLOB);
+            CREATE TABLE public_keys (user_id TEXT, is_primary INTEGER, key_type TEXT, expiry TEXT, public_key BLOB, fingerprint BLOB, signature BLOB);
+            CREATE TABLE symmetric_keys (id BLOB, algorithm TEXT, symmetric_key BLOB);",
+        )
+        .unwrap();
+
+        for _ in 0..2 {
+            conn.execute(
+                "INSERT INTO users (id, name, email) VALUES(?, ?, ?);",
+                rusqlite::params!["user", "name", "name@example.com"],
+            )
+            .unwrap();
+
+            conn.execute(
+                "INSERT INTO private_keys (user_id, is_primary, key_type, expiry, private_key, fingerprint, signature) VALUES(?, ?, ?, ?, ?, ?, ?);",
+                rusqlite::params![
+                    "user",
+                    1,
+                    private_key.key_type().to_string(),
+                    private_key.expiry().to_string(),
+                    private_key.as_bytes(),
+                    private_key.fingerprint(),
+                    private_key.signature()
+                ],
+            )
+            .unwrap();
+        }
+
+        conn.execute(
+            "INSERT INTO private_keys (user_id, is_primary, key_type, expiry, private_key, fingerprint, signature) VALUES(?, ?, ?, ?, ?, ?, ?);",
+            rusqlite::params![
+                "orphan",
+                0,
+                orphan_key.key_type().to_string(),
+                orphan_key.expiry().to_string(),
+                orphan_key.as_bytes(),
+                orphan_key.fingerprint(),
+                orphan_key.signature()
+            ],
+        )
+        .unwrap();
+    }
+
+    let ring = keyring::RingBuilder::new(&database.0).unwrap().build();
+
+    assert_eq!(ring.schema_version().unwrap(), keyring::SCHEMA_VERSION);
+
+    let users = ring.get_users_where_name_matches("name").unwrap();
+
+    assert_eq!(users.len(), 1);
+
+    assert_eq!(users[0].id(), "user");
+
+    assert_eq!(users[0].email(), "name@example.com");
+
+    let private_keys = ring
+        .get_private_keys_where_user_matches(users[0].clone())
+        .unwrap();
+
+    assert_eq!(private_keys.len(), 1);
+
+    assert_eq!(private_keys[0].fingerprint(), private_key.fingerprint());
+
+    assert_eq!(private_keys[0].as_bytes(), private_key.as_bytes());
+
+    // The key without a user is kept, with an empty user.
+    let orphan = ring.get_user_where_id_matches("orphan").unwrap();
+
+    assert_eq!(orphan.name(), "");
+
+    assert_eq!(
+        ring.get_private_keys_where_user_matches(orphan)
+            .unwrap()
+            .len(),
+        1
+    );
+
+    ring.close().unwrap();
+
+    // Opening a migrated database again applies nothing.
+    let mut ring = keyring::RingBuilder::new(&database.0).unwrap().build();
+
+    assert_eq!(ring.schema_version().unwrap(), keyring::SCHEMA_VERSION);
+
+    assert_eq!(ring.insert_user(users[0].clone()).is_err(), true);
+
+    ring.close().unwrap();
+}
+
+#[test]
+fn schema_newer() {
+    let database = TempDatabase::new("newer");
+
+    keyring::RingBuilder::new(&database.0)
+        .unwrap()
+        .build()
+        .close()
+        .unwrap();
+
+    {
+        let conn = rusqlite::Connection::open(&database.0).unwrap();
+
+        conn.execute(
+            "INSERT INTO schema_version (version) VALUES(?);",
+            rusqlite::params![keyring::SCHEMA_VERSION + 1],
+        )
+        .unwrap();
+    }
+
+    assert_eq!(keyring::RingBuilder::new(&database.0).is_err(), true);
+}
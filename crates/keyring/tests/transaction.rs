@@ -0,0 +1,245 @@
+// e.g.
+// cargo test --package keyring --test transaction --  --nocapture
+
+use cck_asymmetric::Key;
+
+/// A user with a primary key and two subkeys signed by it.
+fn signed_bundle() -> (keyring::Bundle, cck_asymmetric::PrivateKey) {
+    let user = keyring::User::new("name", "name@example.com").unwrap();
+
+    let mut primary_key = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519);
+
+    primary_key.set_primary(true).unwrap();
+
+    let subkeys = vec![
+        primary_key
+            .derive_key(cck_asymmetric::KeyType::Ed25519)
+            .unwrap()
+            .public_key(),
+        primary_key
+            .derive_key(cck_asymmetric::KeyType::X25519)
+            .unwrap()
+            .public_key(),
+    ];
+
+    (
+        keyring::Bundle::new(user, &primary_key, subkeys).unwrap(),
+        primary_key,
+    )
+}
+
+#[test]
+fn transaction_commit() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let user = keyring::User::new("name", "name@example.com").unwrap();
+
+    let private_key = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519);
+
+    let count = ring
+        .transaction(|tx| {
+            tx.insert_user(user.clone())?;
+
+            tx.insert_private_key(user.clone(), private_key.clone())?;
+
+            tx.insert_public_key(user.clone(), private_key.public_key())?;
+
+            // visible in the transaction
+            assert_eq!(tx.get_user_where_id_matches(user.id())?, user);
+
+            Ok(tx.get_private_keys_where_user_matches(user.clone())?.len())
+        })
+        .unwrap();
+
+    assert_eq!(count, 1);
+
+    assert_eq!(ring.get_user_where_id_matches(user.id()).unwrap(), user);
+
+    assert_eq!(
+        ring.get_private_key_where_fingerprint_matches(private_key.fingerprint())
+            .unwrap(),
+        private_key
+    );
+
+    assert_eq!(
+        ring.get_public_key_where_fingerprint_matches(private_key.fingerprint())
+            .unwrap(),
+        private_key.public_key()
+    );
+}
+
+#[test]
+fn transaction_rollback() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let user = keyring::User::new("name", "name@example.com").unwrap();
+
+    let private_key = cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::Ed25519);
+
+    // The second insert fails: duplicate fingerprint.
+    assert_eq!(
+        ring.transaction(|tx| {
+            tx.insert_user(user.clone())?;
+
+            tx.insert_private_key(user.clone(), private_key.clone())?;
+
+            tx.insert_private_key(user.clone(), private_key.clone())
+        })
+        .is_err(),
+        true
+    );
+
+    assert_eq!(ring.get_user_where_id_matches(user.id()).is_err(), true);
+
+    assert_eq!(
+        ring.get_private_key_where_fingerprint_matches(private_key.fingerprint())
+            .is_err(),
+        true
+    );
+
+    // An error of the closure itself.
+    assert_eq!(
+        ring.transaction(|tx| -> cck_common::Result<()> {
+            tx.insert_user(user.clone())?;
+
+            Err(cck_common::Error)
+        })
+        .is_err(),
+        true
+    );
+
+    assert_eq!(ring.get_user_where_id_matches(user.id()).is_err(), true);
+
+    // The ring is still usable.
+    ring.insert_user(user.clone()).unwrap();
+}
+
+#[test]
+fn transaction_import_bundle() {
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    let (bundle, _) = signed_bundle();
+
+    bundle.verify().unwrap();
+
+    ring.import_bundle(&bundle).unwrap();
+
+    assert_eq!(
+        &ring.get_user_where_id_matches(bundle.user().id()).unwrap(),
+        bundle.user()
+    );
+
+    let public_keys = ring
+        .get_public_keys_where_user_matches(bundle.user().clone())
+        .unwrap();
+
+    assert_eq!(public_keys.len(), bundle.public_keys().len());
+
+    for public_key in bundle.public_keys() {
+        assert_eq!(public_keys.contains(public_key), true);
+    }
+
+    // already imported
+    assert_eq!(ring.import_bundle(&bundle).is_err(), true);
+
+    assert_eq!(
+        ring.get_public_keys_where_user_matches(bundle.user().clone())
+            .unwrap()
+            .len(),
+        bundle.public_keys().len()
+    );
+}
+
+#[test]
+fn transaction_import_bundle_invalid() {
+    let (bundle, _) = signed_bundle();
+
+    let (other, other_primary_key) = signed_bundle();
+
+    let user = bundle.user().clone();
+
+    let self_signature = bundle.self_signature().to_vec();
+
+    let mut invalid = Vec::new();
+
+    // A subkey signed by another primary key.
+    invalid.push(keyring::Bundle::from_parts(
+        user.clone(),
+        vec![
+            bundle.public_keys()[0].clone(),
+            bundle.public_keys()[1].clone(),
+            other_primary_key
+                .derive_key(cck_asymmetric::KeyType::X25519)
+                .unwrap()
+                .public_key(),
+        ],
+        self_signature.clone(),
+    ));
+
+    // A subkey without a signature.
+    invalid.push(keyring::Bundle::from_parts(
+        user.clone(),
+        vec![
+            bundle.public_keys()[0].clone(),
+            cck_asymmetric::PrivateKey::generate(cck_asymmetric::KeyType::X25519).public_key(),
+        ],
+        self_signature.clone(),
+    ));
+
+    // A fingerprint that does not match the key.
+    let public_key = &bundle.public_keys()[2];
+
+    invalid.push(keyring::Bundle::from_parts(
+        user.clone(),
+        vec![
+            bundle.public_keys()[0].clone(),
+            <cck_asymmetric::PublicKey as cck_asymmetric::Key>::from(
+                false,
+                public_key.key_type().clone(),
+                public_key.expiry().clone(),
+                public_key.as_bytes().to_vec(),
+                other.public_keys()[2].fingerprint().to_vec(),
+                public_key.signature().map(|signature| signature.to_vec()),
+            ),
+        ],
+        self_signature.clone(),
+    ));
+
+    // Two primary keys.
+    invalid.push(keyring::Bundle::from_parts(
+        user.clone(),
+        vec![
+            bundle.public_keys()[0].clone(),
+            other.public_keys()[0].clone(),
+        ],
+        self_signature.clone(),
+    ));
+
+    // No primary key.
+    invalid.push(keyring::Bundle::from_parts(
+        user.clone(),
+        bundle.public_keys()[1..].to_vec(),
+        self_signature.clone(),
+    ));
+
+    let mut ring = keyring::RingBuilder::new_in_memory().unwrap().build();
+
+    for bundle in invalid {
+        assert_eq!(bundle.verify().is_err(), true);
+
+        assert_eq!(ring.import_bundle(&bundle).is_err(), true);
+
+        // rolled back
+        assert_eq!(ring.get_user_where_id_matches(user.id()).is_err(), true);
+
+        for public_key in bundle.public_keys() {
+            assert_eq!(
+                ring.get_public_key_where_fingerprint_matches(public_key.fingerprint())
+                    .is_err(),
+                true
+            );
+        }
+    }
+
+    ring.import_bundle(&bundle).unwrap();
+}